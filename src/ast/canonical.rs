@@ -1,46 +1,667 @@
-use core::num;
-use std::ops::Index;
-
-#[derive(Eq, PartialEq)]
-pub struct NumberFraction {
-    top: u32,
-    bottom: u32,
-}
-
-impl NumberFraction {
-    fn whole_number(number: u32) -> Self {
-        Self {
-            top: number,
-            bottom: 1,
-        }
-    }
-
-    fn new(top: u32, bottom: u32) -> Self {
-        Self { top, bottom }
-    }
-}
-
-const NUM_OF_VARS: usize = ('z' as usize) - ('a' as usize) + 1;
-
-#[derive(PartialEq, Eq)]
-pub struct VarExpMap([i32; NUM_OF_VARS]);
-
-impl Index<char> for VarExpMap {
-    type Output = i32;
-
-    fn index(&self, index: char) -> &Self::Output {
-        assert!(
-            'a' <= index && index <= 'z',
-            "The index has to be a lowercase letter, but is '{index}'."
-        );
-        let idx = (index as usize) - ('a' as usize);
-        &self.0[idx]
-    }
-}
-
-#[derive(PartialEq, Eq)]
-pub struct CanonicalTerm {
-    is_positive: bool,
-    number_frac: NumberFraction,
-    var_exp_map: VarExpMap,
-}
+use std::ops::{Index, IndexMut};
+
+use super::rational::Rational;
+use super::{Expr, NegativeExponents};
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+const NUM_OF_VARS: usize = ('z' as usize) - ('a' as usize) + 1;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct VarExpMap([i32; NUM_OF_VARS]);
+
+impl VarExpMap {
+    pub fn empty() -> Self {
+        Self([0; NUM_OF_VARS])
+    }
+
+    /// Variables with a non-zero exponent, in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (char, i32)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, exp)| **exp != 0)
+            .map(|(idx, exp)| ((b'a' + idx as u8) as char, *exp))
+    }
+}
+
+fn var_idx(var: char) -> usize {
+    assert!(
+        var.is_ascii_lowercase(),
+        "The index has to be a lowercase letter, but is '{var}'."
+    );
+    (var as usize) - ('a' as usize)
+}
+
+impl Index<char> for VarExpMap {
+    type Output = i32;
+
+    fn index(&self, index: char) -> &Self::Output {
+        &self.0[var_idx(index)]
+    }
+}
+
+impl IndexMut<char> for VarExpMap {
+    fn index_mut(&mut self, index: char) -> &mut Self::Output {
+        &mut self.0[var_idx(index)]
+    }
+}
+
+/*
+    Canonical form of a monomial, e.g. `-3/4 · a^2 · c^-1`.
+    Two monomials are equal exactly when their canonical forms are equal.
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CanonicalTerm {
+    coefficient: Rational,
+    var_exp_map: VarExpMap,
+}
+
+impl CanonicalTerm {
+    pub fn number(coefficient: Rational) -> Self {
+        Self {
+            coefficient,
+            var_exp_map: VarExpMap::empty(),
+        }
+    }
+
+    /// `None` when the coefficient does not fit into `Expr::Number`.
+    fn new(coefficient: Rational, var_exp_map: VarExpMap) -> Option<Self> {
+        u32::try_from(coefficient.numerator().unsigned_abs()).ok()?;
+        u32::try_from(coefficient.denominator()).ok()?;
+        let term = Self {
+            coefficient,
+            var_exp_map,
+        };
+        Some(term.normalize())
+    }
+
+    /// `None` when the expression is not a monomial (contains a sum with more terms,
+    /// a non-integer exponent, a division by zero, ...) or its numbers are too large.
+    pub fn from_expr(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Number(num) => Some(Self::number(Rational::integer(i64::from(*num)))),
+            Expr::Variable { symbol } => {
+                let mut term = Self::number(Rational::integer(1));
+                term.var_exp_map[*symbol] = 1;
+                Some(term)
+            }
+            Expr::UnaryMinus(inner) => Some(Self::from_expr(inner)?.negate()),
+            Expr::Multiplication(exprs) => exprs
+                .iter()
+                .try_fold(Self::number(Rational::integer(1)), |acc, expr| {
+                    acc.mul(&Self::from_expr(expr)?)
+                }),
+            Expr::Division { lhs, rhs } => Self::from_expr(lhs)?.div(&Self::from_expr(rhs)?),
+            Expr::Exp { base, exp } => {
+                let exp = Self::from_expr(exp)?.as_integer()?;
+                Self::from_expr(base)?.pow(i32::try_from(exp).ok()?)
+            }
+            Expr::Addition(exprs) => match &exprs[..] {
+                [single] => Self::from_expr(single),
+                _ => None,
+            },
+            Expr::Root { .. } => None,
+        }
+    }
+
+    /// Numbers without variables and with a whole number value.
+    pub fn as_integer(&self) -> Option<i64> {
+        if self.var_exp_map.iter().next().is_some() || self.coefficient.denominator() != 1 {
+            return None;
+        }
+        Some(self.coefficient.numerator())
+    }
+
+    /// Numbers without variables as an exact signed fraction.
+    pub fn as_rational(&self) -> Option<Rational> {
+        if self.var_exp_map.iter().next().is_some() {
+            return None;
+        }
+        Some(self.coefficient())
+    }
+
+    /// The number in front of the variables, `-3/4` for `-3/4 · a^2`.
+    pub fn coefficient(&self) -> Rational {
+        self.coefficient
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    /// Sum of two terms with the same variables, `None` when the variables differ.
+    pub fn add_like(&self, other: &Self) -> Option<Self> {
+        if self.var_exp_map != other.var_exp_map {
+            return None;
+        }
+        let coefficient = self.coefficient.add(other.coefficient)?;
+        Self::new(coefficient, self.var_exp_map.clone())
+    }
+
+    /// Sum of the exponents, `3·a^2·b` has degree 3.
+    pub fn degree(&self) -> i32 {
+        self.var_exp_map.iter().map(|(_, exp)| exp).sum()
+    }
+
+    pub fn negate(mut self) -> Self {
+        self.coefficient = self.coefficient.negate();
+        self
+    }
+
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        let mut var_exp_map = self.var_exp_map.clone();
+        for (var, exp) in other.var_exp_map.iter() {
+            var_exp_map[var] = var_exp_map[var].checked_add(exp)?;
+        }
+        Self::new(self.coefficient.mul(other.coefficient)?, var_exp_map)
+    }
+
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        self.mul(&other.pow(-1)?)
+    }
+
+    pub fn pow(&self, exponent: i32) -> Option<Self> {
+        if self.is_zero() && exponent <= 0 {
+            return None;
+        }
+        let mut var_exp_map = VarExpMap::empty();
+        for (var, exp) in self.var_exp_map.iter() {
+            var_exp_map[var] = exp.checked_mul(exponent)?;
+        }
+        Self::new(self.coefficient.pow(exponent)?, var_exp_map)
+    }
+
+    /// Splits the term into `outside^degree·inside` with `inside` as small as possible,
+    /// `72·a^3` is `(6·a)^2·(2·a)`.
+    ///
+    /// There is no absolute value, so the variables are assumed to be non-negative:
+    /// `√(a^4·b^2)` gives `a^2·b`, which is `a^2·|b|` only for `b ≥ 0`.
+    pub fn root(&self, degree: u32) -> Option<(Self, Self)> {
+        let one = || Self::number(Rational::integer(1));
+        if self.is_zero() {
+            return Some((self.clone(), one()));
+        }
+        if self.coefficient.is_negative() || self.var_exp_map.iter().any(|(_, exp)| exp < 0) {
+            return None;
+        }
+
+        let top = u32::try_from(self.coefficient.numerator()).ok()?;
+        let bottom = u32::try_from(self.coefficient.denominator()).ok()?;
+        let (top_outside, top_inside) = extract_power(top, degree);
+        let (bottom_outside, bottom_inside) = extract_power(bottom, degree);
+        if bottom_inside != 1 {
+            return None;
+        }
+        let outside = Rational::new(i64::from(top_outside), i64::from(bottom_outside))?;
+        let mut outside = Self::number(outside);
+        let mut inside = Self::number(Rational::integer(i64::from(top_inside)));
+        let degree = i32::try_from(degree).ok()?;
+        for (var, exp) in self.var_exp_map.iter() {
+            outside.var_exp_map[var] = exp / degree;
+            inside.var_exp_map[var] = exp % degree;
+        }
+        Some((outside, inside))
+    }
+
+    /// Zero has a single canonical form: without variables.
+    fn normalize(mut self) -> Self {
+        if self.is_zero() {
+            self.var_exp_map = VarExpMap::empty();
+        }
+        self
+    }
+
+    /*
+        Writes the term as `coefficient · variables`, e.g. `-3·a^2·c^(-1)/4`.
+        With `NegativeExponents::AsFraction` the variables with negative
+        exponents are moved to the denominator instead, e.g. `-3·a^2/(4·c)`.
+    */
+    pub fn to_expr(&self, policy: NegativeExponents) -> Expr {
+        let in_denominator = |exp: i32| exp < 0 && policy == NegativeExponents::AsFraction;
+        let factors_with = |number: u32, is_denominator: bool| {
+            let mut factors = vec![];
+            if number != 1 {
+                factors.push(Expr::Number(number));
+            }
+            for (var, exp) in self.var_exp_map.iter() {
+                if in_denominator(exp) != is_denominator {
+                    continue;
+                }
+                let variable = Expr::Variable { symbol: var };
+                let exp = if is_denominator { -exp } else { exp };
+                factors.push(match exp {
+                    1 => variable,
+                    exp => Expr::Exp {
+                        base: variable.into(),
+                        exp: Expr::signed_number(exp).into(),
+                    },
+                });
+            }
+            Expr::product(factors)
+        };
+
+        let small = |number: u64| u32::try_from(number).expect("Coefficients fit into u32");
+        let top = factors_with(small(self.coefficient.numerator().unsigned_abs()), false);
+        let bottom = factors_with(small(self.coefficient.denominator().unsigned_abs()), true);
+        let expr = if bottom == Expr::Number(1) {
+            top
+        } else {
+            Expr::Division {
+                lhs: top.into(),
+                rhs: bottom.into(),
+            }
+        };
+        expr.maybe_wrap_in_minus(self.coefficient.is_negative())
+    }
+}
+
+/// `72` with `degree` 2 is `(6, 2)`, because `72 = 6^2·2`.
+fn extract_power(number: u32, degree: u32) -> (u32, u32) {
+    let mut outside = 1;
+    let mut inside = number;
+    let mut factor: u32 = 2;
+    while let Some(power) = factor.checked_pow(degree).filter(|power| *power <= inside) {
+        if inside.is_multiple_of(power) {
+            inside /= power;
+            outside *= factor;
+        } else {
+            factor += 1;
+        }
+    }
+    (outside, inside)
+}
+
+/*
+    Canonical form of a polynomial, a sum of terms with different variables.
+    The terms are sorted so that e.g. `a^2 + 2·a·b + b^2` is printed in this order.
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CanonicalPolynomial {
+    terms: Vec<CanonicalTerm>,
+}
+
+impl CanonicalPolynomial {
+    pub fn zero() -> Self {
+        Self { terms: vec![] }
+    }
+
+    pub fn from_term(term: CanonicalTerm) -> Self {
+        Self::zero().add_term(&term).unwrap()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// The number when the polynomial has no variables.
+    pub fn as_constant(&self) -> Option<CanonicalTerm> {
+        match &self.terms[..] {
+            [] => Some(CanonicalTerm::number(Rational::zero())),
+            [term] if term.var_exp_map.iter().next().is_none() => Some(term.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn degree(&self) -> i32 {
+        self.terms.iter().map(|t| t.degree()).max().unwrap_or(0)
+    }
+
+    fn add_term(mut self, term: &CanonicalTerm) -> Option<Self> {
+        if term.is_zero() {
+            return Some(self);
+        }
+        let position = self
+            .terms
+            .binary_search_by(|t| term.var_exp_map.cmp(&t.var_exp_map));
+        match position {
+            Ok(idx) => {
+                let sum = self.terms[idx].add_like(term)?;
+                if sum.is_zero() {
+                    self.terms.remove(idx);
+                } else {
+                    self.terms[idx] = sum;
+                }
+            }
+            Err(idx) => self.terms.insert(idx, term.clone()),
+        }
+        Some(self)
+    }
+
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        other
+            .terms
+            .iter()
+            .try_fold(self.clone(), |acc, term| acc.add_term(term))
+    }
+
+    pub fn negate(&self) -> Self {
+        let terms = self.terms.iter().map(|t| t.clone().negate()).collect();
+        Self { terms }
+    }
+
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        let mut result = Self::zero();
+        for lhs in &self.terms {
+            for rhs in &other.terms {
+                result = result.add_term(&lhs.mul(rhs)?)?;
+            }
+        }
+        Some(result)
+    }
+
+    pub fn pow(&self, exponent: u32) -> Option<Self> {
+        let one = Self::from_term(CanonicalTerm::number(Rational::integer(1)));
+        (0..exponent).try_fold(one, |acc, _| acc.mul(self))
+    }
+
+    /// `(a, b)` when the polynomial is `a·var + b`, `None` when there is any other variable.
+    pub fn linear_coefficients(&self, var: char) -> Option<(CanonicalTerm, CanonicalTerm)> {
+        let zero = || CanonicalTerm::number(Rational::zero());
+        let (mut a, mut b) = (zero(), zero());
+        for term in &self.terms {
+            let mut constant = term.clone();
+            constant.var_exp_map[var] = 0;
+            if constant.var_exp_map.iter().next().is_some() {
+                return None;
+            }
+            match term.var_exp_map[var] {
+                0 => b = constant,
+                1 => a = constant,
+                _ => return None,
+            }
+        }
+        Some((a, b))
+    }
+
+    pub fn terms(&self) -> &[CanonicalTerm] {
+        &self.terms
+    }
+
+    /// Every term divided by `divisor`, `None` for division by zero.
+    pub fn div_term(&self, divisor: &CanonicalTerm) -> Option<Self> {
+        self.terms
+            .iter()
+            .try_fold(Self::zero(), |acc, term| acc.add_term(&term.div(divisor)?))
+    }
+
+    /*
+        The greatest monomial dividing every term, e.g. `3·a·b` for `6·a^3·b - 9·a^2·b^2 + 3·a·b`.
+        It has the sign of the first term. `None` for zero or when some term has
+        a fractional coefficient or a negative exponent.
+    */
+    pub fn common_factor(&self) -> Option<CanonicalTerm> {
+        let first = self.terms.first()?;
+        let mut factor = first.clone();
+        let mut number = 0;
+        for term in &self.terms {
+            let has_negative_exponent = term.var_exp_map.iter().any(|(_, exp)| exp < 0);
+            if term.coefficient.denominator() != 1 || has_negative_exponent {
+                return None;
+            }
+            number = gcd(number, term.coefficient.numerator().unsigned_abs());
+            let vars: Vec<(char, i32)> = factor.var_exp_map.iter().collect();
+            for (var, exp) in vars {
+                factor.var_exp_map[var] = exp.min(term.var_exp_map[var]);
+            }
+        }
+        let number = Rational::integer(i64::try_from(number).ok()?);
+        factor.coefficient = if first.coefficient.is_negative() {
+            number.negate()
+        } else {
+            number
+        };
+        Some(factor)
+    }
+
+    /// Sum of the terms in their canonical order.
+    pub fn to_expr(&self, policy: NegativeExponents) -> Expr {
+        Expr::sum(self.terms.iter().map(|term| term.to_expr(policy)).collect())
+    }
+
+    /// `2·a - 4·b` is a multiple of `-a + 2·b`, zero is not a multiple of anything.
+    pub fn is_constant_multiple_of(&self, other: &Self) -> bool {
+        if self.is_zero() || self.terms.len() != other.terms.len() {
+            return false;
+        }
+        let ratio = self.terms[0].div(&other.terms[0]);
+        ratio.is_some_and(|ratio| {
+            ratio.var_exp_map.iter().next().is_none()
+                && other.mul(&Self::from_term(ratio)).as_ref() == Some(self)
+        })
+    }
+
+    /*
+        Whether the polynomial is a sum of multiples of `others`, e.g. `3·x + y - 4`
+        is `3·(x - 1) + (y - 1)`. Gaussian elimination, every monomial has its column.
+        `None` when the numbers get too large.
+    */
+    pub fn is_combination_of(&self, others: &[Self]) -> Option<bool> {
+        let mut monomials: Vec<&VarExpMap> = others
+            .iter()
+            .chain([self])
+            .flat_map(|polynomial| polynomial.terms.iter().map(|term| &term.var_exp_map))
+            .collect();
+        monomials.sort();
+        monomials.dedup();
+        let to_row = |polynomial: &Self| -> Vec<Rational> {
+            monomials
+                .iter()
+                .map(|monomial| {
+                    polynomial
+                        .terms
+                        .iter()
+                        .find(|term| &term.var_exp_map == *monomial)
+                        .map_or(Rational::zero(), CanonicalTerm::coefficient)
+                })
+                .collect()
+        };
+        // Each row of the basis is zero in the pivot columns of the rows before it
+        let reduce = |row: &mut Vec<Rational>, basis: &[(usize, Vec<Rational>)]| {
+            for (pivot, basis_row) in basis {
+                if row[*pivot].is_zero() {
+                    continue;
+                }
+                let factor = row[*pivot].div(basis_row[*pivot])?;
+                for (value, basis_value) in row.iter_mut().zip(basis_row) {
+                    *value = value.sub(factor.mul(*basis_value)?)?;
+                }
+            }
+            Some(())
+        };
+
+        let mut basis = vec![];
+        for other in others {
+            let mut row = to_row(other);
+            reduce(&mut row, &basis)?;
+            if let Some(pivot) = row.iter().position(|value| !value.is_zero()) {
+                basis.push((pivot, row));
+            }
+        }
+        let mut row = to_row(self);
+        reduce(&mut row, &basis)?;
+        Some(row.iter().all(|value| value.is_zero()))
+    }
+
+    /// `a^2 + 4` is never zero: the terms have the same sign, only even exponents
+    /// and one of them is a nonzero number.
+    pub fn has_no_real_root(&self) -> bool {
+        let Some(first) = self.terms.first() else {
+            return false;
+        };
+        let has_constant = self
+            .terms
+            .iter()
+            .any(|term| term.var_exp_map.iter().next().is_none());
+        has_constant
+            && self.terms.iter().all(|term| {
+                term.coefficient.is_negative() == first.coefficient.is_negative()
+                    && term
+                        .var_exp_map
+                        .iter()
+                        .all(|(_, exp)| exp > 0 && exp % 2 == 0)
+            })
+    }
+}
+
+/// Fraction of two polynomials, used to compare expressions like `(a^2 - b^2)/(a + b)`.
+#[derive(Clone, Debug)]
+pub struct CanonicalFraction {
+    numerator: CanonicalPolynomial,
+    denominator: CanonicalPolynomial,
+}
+
+/// Powers of whole sums are expanded, so the exponent has to stay reasonable.
+const MAX_POLYNOMIAL_EXPONENT: u32 = 20;
+
+impl CanonicalFraction {
+    pub fn numerator(&self) -> &CanonicalPolynomial {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &CanonicalPolynomial {
+        &self.denominator
+    }
+
+    fn from_polynomial(numerator: CanonicalPolynomial) -> Self {
+        let one = CanonicalTerm::number(Rational::integer(1));
+        Self {
+            numerator,
+            denominator: CanonicalPolynomial::from_term(one),
+        }
+    }
+
+    /// `None` for division by zero, non-integer exponents or too large numbers.
+    pub fn from_expr(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Number(_) | Expr::Variable { symbol: _ } => Some(Self::from_polynomial(
+                CanonicalPolynomial::from_term(CanonicalTerm::from_expr(expr)?),
+            )),
+            Expr::UnaryMinus(inner) => {
+                let inner = Self::from_expr(inner)?;
+                Some(Self {
+                    numerator: inner.numerator.negate(),
+                    denominator: inner.denominator,
+                })
+            }
+            Expr::Addition(exprs) => {
+                let zero = Self::from_polynomial(CanonicalPolynomial::zero());
+                exprs
+                    .iter()
+                    .try_fold(zero, |acc, expr| acc.add(&Self::from_expr(expr)?))
+            }
+            Expr::Multiplication(exprs) => {
+                let one = Self::from_expr(&Expr::Number(1))?;
+                exprs
+                    .iter()
+                    .try_fold(one, |acc, expr| acc.mul(&Self::from_expr(expr)?))
+            }
+            Expr::Division { lhs, rhs } => Self::from_expr(lhs)?.div(&Self::from_expr(rhs)?),
+            Expr::Exp { base, exp } => {
+                let exp = CanonicalTerm::from_expr(exp)?.as_integer()?;
+                if let Some(term) = CanonicalTerm::from_expr(base) {
+                    let term = term.pow(i32::try_from(exp).ok()?)?;
+                    return Some(Self::from_polynomial(CanonicalPolynomial::from_term(term)));
+                }
+
+                let base = Self::from_expr(base)?;
+                let abs_exp = u32::try_from(exp.unsigned_abs()).ok()?;
+                if abs_exp > MAX_POLYNOMIAL_EXPONENT {
+                    return None;
+                }
+                let powered = Self {
+                    numerator: base.numerator.pow(abs_exp)?,
+                    denominator: base.denominator.pow(abs_exp)?,
+                };
+                if exp < 0 {
+                    powered.reciprocal()
+                } else {
+                    Some(powered)
+                }
+            }
+            Expr::Root { .. } => None,
+        }
+    }
+
+    fn reciprocal(&self) -> Option<Self> {
+        if self.numerator.is_zero() {
+            return None;
+        }
+        Some(Self {
+            numerator: self.denominator.clone(),
+            denominator: self.numerator.clone(),
+        })
+    }
+
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        if self.denominator == other.denominator {
+            return Some(Self {
+                numerator: self.numerator.add(&other.numerator)?,
+                denominator: self.denominator.clone(),
+            });
+        }
+        Some(Self {
+            numerator: self
+                .numerator
+                .mul(&other.denominator)?
+                .add(&other.numerator.mul(&self.denominator)?)?,
+            denominator: self.denominator.mul(&other.denominator)?,
+        })
+    }
+
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.mul(&other.numerator)?,
+            denominator: self.denominator.mul(&other.denominator)?,
+        })
+    }
+
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        self.mul(&other.reciprocal()?)
+    }
+
+    /// Equality of the two fractions wherever both are defined.
+    pub fn is_equal(&self, other: &Self) -> Option<bool> {
+        let lhs = self.numerator.mul(&other.denominator)?;
+        let rhs = other.numerator.mul(&self.denominator)?;
+        Some(lhs == rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(radicand: &str, degree: u32) -> Option<(String, String)> {
+        let term = CanonicalTerm::from_expr(&Expr::parse(radicand).unwrap()).unwrap();
+        let (outside, inside) = term.root(degree)?;
+        let to_string =
+            |term: CanonicalTerm| term.to_expr(NegativeExponents::AsFraction).to_string();
+        Some((to_string(outside), to_string(inside)))
+    }
+
+    #[test]
+    fn root_extracts_the_largest_power() {
+        let pair = |outside: &str, inside: &str| Some((outside.to_string(), inside.to_string()));
+        assert_eq!(root("72a^3", 2), pair("6·a", "2·a"));
+        assert_eq!(root("54", 3), pair("3", "2"));
+        assert_eq!(root("9/4", 2), pair("3/2", "1"));
+        assert_eq!(root("0", 2), pair("0", "1"));
+        // Only for b ≥ 0, the absolute value would be a^2·|b|
+        assert_eq!(root("a^4b^2", 2), pair("a^2·b", "1"));
+        assert_eq!(root("-4", 2), None);
+        assert_eq!(root("1/2", 2), None);
+    }
+}
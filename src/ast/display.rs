@@ -1,97 +1,193 @@
-use std::fmt::{self};
-
-use crate::ast::precedence;
-
-use super::{precedence::Precedence, Expr};
-
-fn write_addition(f: &mut fmt::Formatter, exprs: &Vec<Expr>) -> fmt::Result {
-    for (idx, mut expr) in exprs.iter().enumerate() {
-        if idx != 0 {
-            if let Expr::UnaryMinus(inner_expr) = expr {
-                expr = inner_expr;
-                write!(f, " - ")?;
-            } else {
-                write!(f, " + ")?;
-            }
-        }
-
-        let should_print_parenthesis = precedence::ADDITION.is_before(expr.precedence());
-
-        if should_print_parenthesis {
-            write!(f, "({expr})")?;
-        } else {
-            write!(f, "{expr}")?;
-        }
-    }
-    Ok(())
-}
-
-fn write_multiplication(f: &mut fmt::Formatter, exprs: &Vec<Expr>) -> fmt::Result {
-    for (idx, expr) in exprs.iter().enumerate() {
-        if idx != 0 {
-            write!(f, "·")?;
-        }
-
-        let should_print_parenthesis = precedence::MULTIPLICATION.is_before(expr.precedence());
-
-        if should_print_parenthesis {
-            write!(f, "({expr})")?;
-        } else {
-            write!(f, "{expr}")?;
-        }
-    }
-    Ok(())
-}
-
-impl fmt::Display for Expr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Expr::Addition(exprs) => write_addition(f, exprs),
-            Expr::Multiplication(exprs) => write_multiplication(f, exprs),
-            Expr::Division { lhs, rhs } => {
-                let should_print_parenthesis = precedence::DIVISION.is_before(lhs.precedence());
-                if should_print_parenthesis {
-                    write!(f, "({lhs})")?;
-                } else {
-                    write!(f, "{lhs}")?;
-                }
-
-                let should_print_parenthesis = precedence::DIVISION.is_before(rhs.precedence());
-                if should_print_parenthesis {
-                    write!(f, "/({rhs})")?;
-                } else {
-                    write!(f, "/{rhs}")?;
-                }
-                Ok(())
-            }
-            Expr::UnaryMinus(expr) => {
-                let should_print_parenthesis = precedence::UNARY_MINUS.is_before(expr.precedence());
-                if should_print_parenthesis {
-                    write!(f, "-({expr})")
-                } else {
-                    write!(f, "-{expr}")
-                }
-            }
-            Expr::Exp { base, exp } => {
-                let should_print_parenthesis =
-                    precedence::EXPONENTIATION.is_before(base.precedence());
-                if should_print_parenthesis {
-                    write!(f, "({base})")?;
-                } else {
-                    write!(f, "{base}")?;
-                }
-
-                let should_print_parenthesis =
-                    precedence::EXPONENTIATION.is_before(exp.precedence());
-                if should_print_parenthesis {
-                    write!(f, "^({exp})")?;
-                } else {
-                    write!(f, "^{exp}")?;
-                }
-                Ok(())
-            }
-            Expr::Number(num) => write!(f, "{num}"),
-            Expr::Variable { symbol } => write!(f, "{symbol}"),
-        }
-    }
-}
+use colored::*;
+use std::fmt::{self};
+use std::ptr;
+
+use crate::ast::precedence;
+
+use super::Expr;
+
+fn write_addition(f: &mut fmt::Formatter, exprs: &[Expr], part: Option<&Expr>) -> fmt::Result {
+    for (idx, mut expr) in exprs.iter().enumerate() {
+        let mut is_subtraction = false;
+        if idx != 0 {
+            if let Expr::UnaryMinus(inner_expr) = expr {
+                expr = inner_expr;
+                is_subtraction = true;
+                write!(f, " - ")?;
+            } else {
+                write!(f, " + ")?;
+            }
+        }
+
+        // `a - (-3)` instead of `a - -3`
+        let should_print_parenthesis = precedence::ADDITION.is_before(expr.precedence())
+            || (is_subtraction && starts_with_minus(expr));
+
+        let expr = Highlighted { expr, part };
+        if should_print_parenthesis {
+            write!(f, "({expr})")?;
+        } else {
+            write!(f, "{expr}")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_multiplication(
+    f: &mut fmt::Formatter,
+    exprs: &[Expr],
+    part: Option<&Expr>,
+) -> fmt::Result {
+    for (idx, expr) in exprs.iter().enumerate() {
+        if idx != 0 {
+            write!(f, "·")?;
+        }
+
+        // `2·(-3)` instead of `2·-3`
+        let should_print_parenthesis = precedence::MULTIPLICATION.is_before(expr.precedence())
+            || (idx != 0 && starts_with_minus(expr));
+
+        let expr = Highlighted { expr, part };
+        if should_print_parenthesis {
+            write!(f, "({expr})")?;
+        } else {
+            write!(f, "{expr}")?;
+        }
+    }
+    Ok(())
+}
+
+/// `√` for square roots, `∛`, `∜` and `⁵√` for the higher ones.
+fn write_root_symbol(f: &mut fmt::Formatter, degree: u32) -> fmt::Result {
+    const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    match degree {
+        2 => write!(f, "√"),
+        3 => write!(f, "∛"),
+        4 => write!(f, "∜"),
+        degree => {
+            for digit in degree.to_string().chars() {
+                let digit = digit.to_digit(10).unwrap() as usize;
+                write!(f, "{}", SUPERSCRIPTS[digit])?;
+            }
+            write!(f, "√")
+        }
+    }
+}
+
+fn starts_with_minus(expr: &Expr) -> bool {
+    match expr {
+        Expr::UnaryMinus(_) => true,
+        Expr::Multiplication(exprs) => exprs.first().is_some_and(|first| {
+            !precedence::MULTIPLICATION.is_before(first.precedence()) && starts_with_minus(first)
+        }),
+        Expr::Division { lhs, rhs: _ } => {
+            !precedence::DIVISION.is_before(lhs.precedence()) && starts_with_minus(lhs)
+        }
+        _ => false,
+    }
+}
+
+/// An expression with one of its parts highlighted, e.g. the operation that is evaluated next.
+pub struct Highlighted<'a> {
+    expr: &'a Expr,
+    /// Compared by address, so that only this occurrence is highlighted.
+    part: Option<&'a Expr>,
+}
+
+impl Expr {
+    pub fn highlighted<'a>(&'a self, part: &'a Expr) -> Highlighted<'a> {
+        Highlighted {
+            expr: self,
+            part: Some(part),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Highlighted {
+            expr: self,
+            part: None,
+        }
+        .fmt(f)
+    }
+}
+
+impl fmt::Display for Highlighted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = self.part;
+        if part.is_some_and(|part| ptr::eq(part, self.expr)) {
+            return write!(f, "{}", self.expr.to_string().bold().cyan());
+        }
+        let wrap = |expr| Highlighted { expr, part };
+        match self.expr {
+            Expr::Addition(exprs) => write_addition(f, exprs, part),
+            Expr::Multiplication(exprs) => write_multiplication(f, exprs, part),
+            Expr::Division { lhs, rhs } => {
+                let (lhs, rhs) = (wrap(lhs), wrap(rhs));
+                let should_print_parenthesis =
+                    precedence::DIVISION.is_before(lhs.expr.precedence());
+                if should_print_parenthesis {
+                    write!(f, "({lhs})")?;
+                } else {
+                    write!(f, "{lhs}")?;
+                }
+
+                let should_print_parenthesis =
+                    precedence::DIVISION.is_same_or_before(rhs.expr.precedence());
+                if should_print_parenthesis {
+                    write!(f, "/({rhs})")?;
+                } else {
+                    write!(f, "/{rhs}")?;
+                }
+                Ok(())
+            }
+            Expr::UnaryMinus(inner) => {
+                let expr = &**inner;
+                // `-(2·a)` is the same as `(-2)·a`, so it is printed as `-2·a`
+                let is_product = matches!(expr, Expr::Multiplication(_) | Expr::Division { .. });
+                let should_print_parenthesis =
+                    (precedence::UNARY_MINUS.is_before(expr.precedence()) && !is_product)
+                        || starts_with_minus(expr);
+                let expr = wrap(expr);
+                if should_print_parenthesis {
+                    write!(f, "-({expr})")
+                } else {
+                    write!(f, "-{expr}")
+                }
+            }
+            Expr::Exp { base, exp } => {
+                let (base, exp) = (wrap(base), wrap(exp));
+                let should_print_parenthesis =
+                    precedence::EXPONENTIATION.is_same_or_before(base.expr.precedence());
+                if should_print_parenthesis {
+                    write!(f, "({base})")?;
+                } else {
+                    write!(f, "{base}")?;
+                }
+
+                let should_print_parenthesis =
+                    precedence::EXPONENTIATION.is_before(exp.expr.precedence());
+                if should_print_parenthesis {
+                    write!(f, "^({exp})")?;
+                } else {
+                    write!(f, "^{exp}")?;
+                }
+                Ok(())
+            }
+            Expr::Root { degree, radicand } => {
+                let radicand = wrap(radicand);
+                write_root_symbol(f, *degree)?;
+                let should_print_parenthesis =
+                    precedence::ROOT.is_same_or_before(radicand.expr.precedence());
+                if should_print_parenthesis {
+                    write!(f, "({radicand})")
+                } else {
+                    write!(f, "{radicand}")
+                }
+            }
+            Expr::Number(num) => write!(f, "{num}"),
+            Expr::Variable { symbol } => write!(f, "{symbol}"),
+        }
+    }
+}
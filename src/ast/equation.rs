@@ -0,0 +1,257 @@
+use std::fmt;
+
+use super::canonical::{CanonicalFraction, CanonicalPolynomial, CanonicalTerm};
+use super::parse::ParseError;
+use super::Expr;
+
+/// Equation `lhs = rhs`, e.g. `3·x + 2 = x - 4`.
+#[derive(Clone, PartialEq)]
+pub struct Equation {
+    pub lhs: Expr,
+    pub rhs: Expr,
+}
+
+/// `lhs - rhs` as a polynomial, `None` when there are variables in a denominator.
+pub fn polynomial_difference(lhs: &Expr, rhs: &Expr) -> Option<CanonicalPolynomial> {
+    let difference = Expr::Addition(vec![lhs.clone(), Expr::UnaryMinus(rhs.clone().into())]);
+    let fraction = CanonicalFraction::from_expr(&difference)?;
+    // Multiplying by a constant denominator does not change the solutions
+    fraction.denominator().as_constant()?;
+    Some(fraction.numerator().clone())
+}
+
+pub enum LinearSolution {
+    Single(CanonicalTerm),
+    NoSolution,
+    AnyNumber,
+}
+
+/// What the student does with both sides of an equation, e.g. `| -3` or `| :(-2)`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    fn symbol(self) -> char {
+        match self {
+            Operation::Add => '+',
+            Operation::Subtract => '-',
+            Operation::Multiply => '·',
+            Operation::Divide => ':',
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Transformation {
+    pub operation: Operation,
+    pub operand: Expr,
+}
+
+impl Transformation {
+    pub fn parse(input: &str) -> Result<Transformation, ParseError> {
+        let trimmed = input.trim_start();
+        let offset = input.chars().count() - trimmed.chars().count();
+        let (offset, trimmed) = match trimmed.strip_prefix('|') {
+            Some(rest) => (offset + 1, rest.trim_start()),
+            None => (offset, trimmed),
+        };
+
+        let mut chars = trimmed.chars();
+        let operation = match chars.next() {
+            Some('+') => Operation::Add,
+            Some('-' | '−') => Operation::Subtract,
+            Some('*' | '·' | '⋅') => Operation::Multiply,
+            Some(':' | '/') => Operation::Divide,
+            _ => {
+                return Err(ParseError {
+                    position: offset,
+                    message: "Úprava musí začínat +, -, · nebo :".to_string(),
+                })
+            }
+        };
+        let operand = Expr::parse(chars.as_str()).map_err(|err| ParseError {
+            position: offset + 1 + err.position,
+            ..err
+        })?;
+        Ok(Transformation { operation, operand })
+    }
+
+    /// One side transformed and simplified.
+    pub fn apply_to(&self, side: &Expr) -> Expr {
+        let side = side.clone();
+        let operand = self.operand.clone();
+        let result = match self.operation {
+            Operation::Add => Expr::Addition(vec![side, operand]),
+            Operation::Subtract => Expr::Addition(vec![side, Expr::UnaryMinus(operand.into())]),
+            Operation::Multiply => Expr::Multiplication(vec![side, operand]),
+            Operation::Divide => Expr::Division {
+                lhs: side.into(),
+                rhs: operand.into(),
+            },
+        };
+        result.simplify().result().clone()
+    }
+
+    /// Why the transformation could change the solutions, `None` when it is fine.
+    pub fn problem(&self) -> Option<&'static str> {
+        if matches!(self.operation, Operation::Add | Operation::Subtract) {
+            return None;
+        }
+        let is_zero = CanonicalFraction::from_expr(&self.operand)
+            .is_none_or(|operand| operand.numerator().is_zero());
+        if is_zero {
+            Some("Nulou se násobit ani dělit nesmí.")
+        } else if !self.operand.variable_occurrences().is_empty() {
+            Some("Výrazem s neznámou se násobit ani dělit nemá, můžeš tím přidat nebo ztratit řešení.")
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Transformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = self.operation.symbol();
+        match self.operand {
+            Expr::UnaryMinus(_) => write!(f, "| {symbol}({})", self.operand),
+            _ => write!(f, "| {symbol}{}", self.operand),
+        }
+    }
+}
+
+/// One line when solving an equation, either a new equation or a transformation of both sides.
+pub enum EquationStep {
+    Equation(Equation),
+    Transformation(Transformation),
+}
+
+impl EquationStep {
+    pub fn parse(input: &str) -> Result<EquationStep, ParseError> {
+        if input.contains('=') {
+            Equation::parse(input).map(EquationStep::Equation)
+        } else {
+            Transformation::parse(input).map(EquationStep::Transformation)
+        }
+    }
+}
+
+impl Equation {
+    pub fn parse(input: &str) -> Result<Equation, ParseError> {
+        let Some((lhs, rhs)) = input.split_once('=') else {
+            return Err(ParseError {
+                position: input.chars().count(),
+                message: "V rovnici chybí =".to_string(),
+            });
+        };
+        let rhs_offset = lhs.chars().count() + 1;
+        if let Some(position) = rhs.find('=') {
+            return Err(ParseError {
+                position: rhs_offset + rhs[..position].chars().count(),
+                message: "Rovnice může mít jen jedno =".to_string(),
+            });
+        }
+
+        let lhs = Expr::parse(lhs)?;
+        let rhs = Expr::parse(rhs).map_err(|err| ParseError {
+            position: rhs_offset + err.position,
+            ..err
+        })?;
+        Ok(Equation { lhs, rhs })
+    }
+
+    /// Both sides transformed and simplified.
+    pub fn apply(&self, transformation: &Transformation) -> Equation {
+        Equation {
+            lhs: transformation.apply_to(&self.lhs),
+            rhs: transformation.apply_to(&self.rhs),
+        }
+    }
+
+    /// Both sides simplified, e.g. parentheses multiplied out.
+    pub fn simplify(&self) -> Equation {
+        Equation {
+            lhs: self.lhs.simplify().result().clone(),
+            rhs: self.rhs.simplify().result().clone(),
+        }
+    }
+
+    fn difference(&self) -> Option<CanonicalPolynomial> {
+        polynomial_difference(&self.lhs, &self.rhs)
+    }
+
+    /// Whether both linear equations have the same solutions,
+    /// `None` when one of them is not a polynomial equation.
+    pub fn is_equivalent(&self, other: &Equation) -> Option<bool> {
+        let lhs = self.difference()?;
+        let rhs = other.difference()?;
+        if lhs.is_zero() || rhs.is_zero() {
+            return Some(lhs.is_zero() && rhs.is_zero());
+        }
+        let is_contradiction = |p: &CanonicalPolynomial| p.as_constant().is_some();
+        if is_contradiction(&lhs) || is_contradiction(&rhs) {
+            return Some(is_contradiction(&lhs) && is_contradiction(&rhs));
+        }
+        Some(lhs.is_constant_multiple_of(&rhs))
+    }
+
+    /// Whether the equation is a sum of multiples of `equations`, so it follows from
+    /// them without anything else, `None` when some of them is not polynomial.
+    pub fn follows_from(&self, equations: &[Equation]) -> Option<bool> {
+        let others = equations
+            .iter()
+            .map(Equation::difference)
+            .collect::<Option<Vec<_>>>()?;
+        self.difference()?.is_combination_of(&others)
+    }
+
+    /// Whether the equation holds when the variables are replaced by `values`,
+    /// `None` when there is a division by zero or some variable is left.
+    pub fn holds_for(&self, values: &[(char, Expr)]) -> Option<bool> {
+        let substituted = values
+            .iter()
+            .fold(self.clone(), |equation, (var, value)| Equation {
+                lhs: equation.lhs.substitute(*var, value),
+                rhs: equation.rhs.substitute(*var, value),
+            });
+        let difference = substituted.difference()?;
+        difference.as_constant()?;
+        Some(difference.is_zero())
+    }
+
+    /// `x = 11/2` or `11/2 = x` gives `('x', 11/2)`.
+    pub fn as_solved(&self) -> Option<(char, &Expr)> {
+        let (symbol, value) = match (&self.lhs, &self.rhs) {
+            (Expr::Variable { symbol }, value) | (value, Expr::Variable { symbol }) => {
+                (*symbol, value)
+            }
+            _ => return None,
+        };
+        value
+            .variable_occurrences()
+            .is_empty()
+            .then_some((symbol, value))
+    }
+
+    /// `None` when the equation is not linear in `var` or there are other variables.
+    pub fn solve_linear(&self, var: char) -> Option<LinearSolution> {
+        let (a, b) = self.difference()?.linear_coefficients(var)?;
+        Some(if !a.is_zero() {
+            LinearSolution::Single(b.negate().div(&a)?)
+        } else if b.is_zero() {
+            LinearSolution::AnyNumber
+        } else {
+            LinearSolution::NoSolution
+        })
+    }
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.lhs, self.rhs)
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use crate::random::Random;
+
+use super::radical::RootSum;
+use super::rational::Rational;
+use super::Expr;
+
+/*
+    Comparison of two expressions by their values at random points.
+    It works for any expression that can be evaluated, also where the canonical
+    form is not available, e.g. with roots. The values are exact fractions
+    together with roots of numbers, see `RootSum`, so two different expressions
+    can not look equal because of rounding.
+*/
+
+/// How many points the expressions have to agree at before they are called equal.
+#[derive(Clone, Copy)]
+pub struct Confidence {
+    pub points: u32,
+    /// Points at poles or with too large numbers are skipped, at most this many are tried.
+    pub max_attempts: u32,
+}
+
+impl Confidence {
+    pub const NORMAL: Confidence = Confidence {
+        points: 20,
+        max_attempts: 200,
+    };
+}
+
+/// Values of the variables.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Point(Vec<(char, Rational)>);
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self
+            .0
+            .iter()
+            .map(|(var, value)| format!("{var} = {value}"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Equivalence {
+    /// The values were the same at every point.
+    Equal,
+    /// Different values at `point`, `lhs` and `rhs` are the values there.
+    Different {
+        point: Point,
+        lhs: RootSum,
+        rhs: RootSum,
+    },
+    /// Not enough points where both expressions have a value.
+    Unknown,
+}
+
+/// Exact value at `point`, `None` at a pole, for an even root of a negative number
+/// or too large numbers.
+pub fn evaluate(expr: &Expr, point: &Point) -> Option<RootSum> {
+    match expr {
+        Expr::Number(number) => Some(RootSum::rational(Rational::integer(i64::from(*number)))),
+        Expr::Variable { symbol } => point
+            .0
+            .iter()
+            .find(|(var, _)| var == symbol)
+            .map(|(_, value)| RootSum::rational(*value)),
+        Expr::Addition(exprs) => exprs.iter().try_fold(
+            RootSum::rational(Rational::zero()),
+            |acc, expr| match expr {
+                Expr::UnaryMinus(inner) => acc.sub(&evaluate(inner, point)?),
+                _ => acc.add(&evaluate(expr, point)?),
+            },
+        ),
+        Expr::Multiplication(exprs) => exprs
+            .iter()
+            .try_fold(RootSum::rational(Rational::integer(1)), |acc, expr| {
+                acc.mul(&evaluate(expr, point)?)
+            }),
+        Expr::Division { lhs, rhs } => evaluate(lhs, point)?.div(&evaluate(rhs, point)?),
+        Expr::UnaryMinus(inner) => Some(evaluate(inner, point)?.negate()),
+        Expr::Exp { base, exp } => {
+            let exp = evaluate(exp, point)?.as_rational()?;
+            if exp.denominator() != 1 {
+                return None;
+            }
+            let exp = i32::try_from(exp.numerator()).ok()?;
+            evaluate(base, point)?.pow(exp)
+        }
+        Expr::Root { degree, radicand } => evaluate(radicand, point)?.root(*degree),
+    }
+}
+
+/// Product of the degrees of all roots, 1 without roots.
+fn root_degrees(expr: &Expr) -> u32 {
+    match expr {
+        Expr::Addition(exprs) | Expr::Multiplication(exprs) => exprs
+            .iter()
+            .map(root_degrees)
+            .fold(1, |acc, degree| acc.saturating_mul(degree)),
+        Expr::Division { lhs, rhs } => root_degrees(lhs).saturating_mul(root_degrees(rhs)),
+        Expr::Exp { base, exp } => root_degrees(base).saturating_mul(root_degrees(exp)),
+        Expr::UnaryMinus(inner) => root_degrees(inner),
+        Expr::Root { degree, radicand } => degree.saturating_mul(root_degrees(radicand)),
+        Expr::Number(_) | Expr::Variable { symbol: _ } => 1,
+    }
+}
+
+/// Small number, raised to `power` so that the roots of a single variable come out exact.
+/// Half of them are whole numbers, the counter-examples are easier to check with them.
+fn random_value(rng: &mut Random, power: u32) -> Option<Rational> {
+    let denominator = if rng.random_bool(0.5) {
+        1
+    } else {
+        rng.random_range(2..=6)
+    };
+    let value = Rational::new(rng.random_range(-12..=12), denominator)?;
+    let power = i32::try_from(power).ok()?;
+    value.pow(power)
+}
+
+/// Compares the values of `lhs` and `rhs` at random points where both of them are defined.
+pub fn compare_at_points(
+    lhs: &Expr,
+    rhs: &Expr,
+    confidence: Confidence,
+    rng: &mut Random,
+) -> Equivalence {
+    let mut variables = lhs.variable_occurrences();
+    variables.extend(rhs.variable_occurrences());
+    variables.sort();
+    variables.dedup();
+    // Roots of powers are exact, the even ones make the variables nonnegative like
+    // in the root excercises. The power is limited to keep the numbers small.
+    let power = root_degrees(lhs).max(root_degrees(rhs)).min(4);
+
+    let mut agreed = 0;
+    for _ in 0..confidence.max_attempts {
+        let values = variables
+            .iter()
+            .map(|var| Some((*var, random_value(rng, power)?)))
+            .collect::<Option<Vec<_>>>();
+        let Some(values) = values else {
+            continue;
+        };
+        let point = Point(values);
+        let (Some(lhs), Some(rhs)) = (evaluate(lhs, &point), evaluate(rhs, &point)) else {
+            continue;
+        };
+        if lhs != rhs {
+            return Equivalence::Different { point, lhs, rhs };
+        }
+        agreed += 1;
+        if agreed >= confidence.points {
+            return Equivalence::Equal;
+        }
+    }
+    Equivalence::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_points_find_a_counter_example() {
+        let mut rng = Random::from_seed(0);
+        let mut compare = |lhs: &str, rhs: &str| {
+            let (lhs, rhs) = (Expr::parse(lhs).unwrap(), Expr::parse(rhs).unwrap());
+            compare_at_points(&lhs, &rhs, Confidence::NORMAL, &mut rng)
+        };
+        assert_eq!(compare("(a^2 - b^2)/(a + b)", "a - b"), Equivalence::Equal);
+        assert_eq!(compare("√(18a^2)/√2", "3a"), Equivalence::Equal);
+        assert!(matches!(
+            compare("√(12a^2)", "4·√3"),
+            Equivalence::Different { .. }
+        ));
+        assert_eq!(compare("√(4a^2·b)", "2a·√b"), Equivalence::Equal);
+        let Equivalence::Different { point, lhs, rhs } = compare("(a^2 - b^2)/(a + b)", "a + b")
+        else {
+            panic!("a - b and a + b differ");
+        };
+        let at_point = |expr: &str| evaluate(&Expr::parse(expr).unwrap(), &point);
+        assert_ne!(lhs, rhs);
+        assert_eq!(at_point("a - b"), Some(lhs));
+        assert_eq!(at_point("a + b"), Some(rhs));
+    }
+}
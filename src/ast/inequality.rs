@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::equation::{polynomial_difference, Operation, Transformation};
+use super::interval::{Bound, Interval, IntervalUnion};
+use super::parse::ParseError;
+use super::rational::Rational;
+use super::Expr;
+
+/// Comparison between the two sides of an inequality.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Relation {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Relation {
+    /// The longer symbols go first, so that `<=` is not read as `<`.
+    const SYMBOLS: [(&'static str, Relation); 8] = [
+        ("<=", Relation::LessOrEqual),
+        (">=", Relation::GreaterOrEqual),
+        ("≤", Relation::LessOrEqual),
+        ("≥", Relation::GreaterOrEqual),
+        ("⩽", Relation::LessOrEqual),
+        ("⩾", Relation::GreaterOrEqual),
+        ("<", Relation::Less),
+        (">", Relation::Greater),
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Relation::Less => "<",
+            Relation::LessOrEqual => "≤",
+            Relation::Greater => ">",
+            Relation::GreaterOrEqual => "≥",
+        }
+    }
+
+    /// The relation after swapping the sides or multiplying by a negative number.
+    pub fn flipped(self) -> Relation {
+        match self {
+            Relation::Less => Relation::Greater,
+            Relation::LessOrEqual => Relation::GreaterOrEqual,
+            Relation::Greater => Relation::Less,
+            Relation::GreaterOrEqual => Relation::LessOrEqual,
+        }
+    }
+
+    /// Whether `lhs relation rhs` holds when `lhs.cmp(rhs)` is `ordering`.
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Relation::Less => ordering.is_lt(),
+            Relation::LessOrEqual => ordering.is_le(),
+            Relation::Greater => ordering.is_gt(),
+            Relation::GreaterOrEqual => ordering.is_ge(),
+        }
+    }
+
+    /// Every `x` with `x relation value`.
+    pub fn solution_set(self, value: Rational) -> IntervalUnion {
+        let bound = |is_closed| Some(Bound { value, is_closed });
+        let interval = match self {
+            Relation::Less | Relation::LessOrEqual => Interval {
+                lower: None,
+                upper: bound(self == Relation::LessOrEqual),
+            },
+            Relation::Greater | Relation::GreaterOrEqual => Interval {
+                lower: bound(self == Relation::GreaterOrEqual),
+                upper: None,
+            },
+        };
+        IntervalUnion::from_intervals(vec![interval])
+    }
+}
+
+/// Inequality `lhs relation rhs`, e.g. `2·x - 3 < 5`.
+#[derive(Clone, PartialEq)]
+pub struct Inequality {
+    pub lhs: Expr,
+    pub relation: Relation,
+    pub rhs: Expr,
+}
+
+/// One line when solving an inequality, either a new inequality or a transformation of both sides.
+pub enum InequalityStep {
+    Inequality(Inequality),
+    Transformation(Transformation),
+}
+
+impl InequalityStep {
+    pub fn parse(input: &str) -> Result<InequalityStep, ParseError> {
+        if input.contains(['<', '>', '≤', '≥', '⩽', '⩾']) {
+            Inequality::parse(input).map(InequalityStep::Inequality)
+        } else {
+            Transformation::parse(input).map(InequalityStep::Transformation)
+        }
+    }
+}
+
+/// The first relation symbol in `input`, as `(byte index, length, relation)`.
+fn find_relation(input: &str) -> Option<(usize, usize, Relation)> {
+    input.char_indices().find_map(|(idx, _)| {
+        Relation::SYMBOLS
+            .iter()
+            .find(|(symbol, _)| input[idx..].starts_with(symbol))
+            .map(|(symbol, relation)| (idx, symbol.len(), *relation))
+    })
+}
+
+impl Inequality {
+    pub fn parse(input: &str) -> Result<Inequality, ParseError> {
+        let Some((idx, len, relation)) = find_relation(input) else {
+            return Err(ParseError {
+                position: input.chars().count(),
+                message: "V nerovnici chybí <, >, ≤ nebo ≥".to_string(),
+            });
+        };
+        let (lhs, rhs) = (&input[..idx], &input[idx + len..]);
+        let rhs_offset = input[..idx + len].chars().count();
+        if let Some((position, _, _)) = find_relation(rhs) {
+            return Err(ParseError {
+                position: rhs_offset + rhs[..position].chars().count(),
+                message: "Nerovnice může mít jen jedno znaménko nerovnosti".to_string(),
+            });
+        }
+        if let Some(position) = input
+            .find('=')
+            .filter(|position| *position < idx || *position >= idx + len)
+        {
+            return Err(ParseError {
+                position: input[..position].chars().count(),
+                message: "Rovnítko se píše jen jako součást <= nebo >=".to_string(),
+            });
+        }
+
+        let lhs = Expr::parse(lhs)?;
+        let rhs = Expr::parse(rhs).map_err(|err| ParseError {
+            position: rhs_offset + err.position,
+            ..err
+        })?;
+        Ok(Inequality { lhs, relation, rhs })
+    }
+
+    /// Whether the transformation multiplies or divides by a negative number.
+    pub fn flips(transformation: &Transformation) -> bool {
+        matches!(
+            transformation.operation,
+            Operation::Multiply | Operation::Divide
+        ) && Rational::from_expr(&transformation.operand).is_some_and(Rational::is_negative)
+    }
+
+    /// Both sides transformed and simplified, the relation flips for negative numbers.
+    pub fn apply(&self, transformation: &Transformation) -> Inequality {
+        let relation = if Self::flips(transformation) {
+            self.relation.flipped()
+        } else {
+            self.relation
+        };
+        Inequality {
+            lhs: transformation.apply_to(&self.lhs),
+            relation,
+            rhs: transformation.apply_to(&self.rhs),
+        }
+    }
+
+    /// Both sides simplified, e.g. parentheses multiplied out.
+    pub fn simplify(&self) -> Inequality {
+        Inequality {
+            lhs: self.lhs.simplify().result().clone(),
+            relation: self.relation,
+            rhs: self.rhs.simplify().result().clone(),
+        }
+    }
+
+    /// The same inequality with the relation turned the other way.
+    pub fn with_flipped_relation(&self) -> Inequality {
+        Inequality {
+            relation: self.relation.flipped(),
+            ..self.clone()
+        }
+    }
+
+    /// Every solution, `None` when the inequality is not linear in `var` or there are other variables.
+    pub fn solve_linear(&self, var: char) -> Option<IntervalUnion> {
+        let difference = polynomial_difference(&self.lhs, &self.rhs)?;
+        let (a, b) = difference.linear_coefficients(var)?;
+        let (a, b) = (a.as_rational()?, b.as_rational()?);
+        // a·var + b  relation  0
+        if a.is_zero() {
+            return Some(if self.relation.holds(b.cmp(&Rational::zero())) {
+                IntervalUnion::all()
+            } else {
+                IntervalUnion::empty()
+            });
+        }
+        let value = b.negate().div(a)?;
+        let relation = if a.is_negative() {
+            self.relation.flipped()
+        } else {
+            self.relation
+        };
+        Some(relation.solution_set(value))
+    }
+
+    /// `x < 4` or `4 > x` gives `('x', Less, 4)`.
+    pub fn as_solved(&self) -> Option<(char, Relation, &Expr)> {
+        let (symbol, relation, value) = match (&self.lhs, &self.rhs) {
+            (Expr::Variable { symbol }, value) => (*symbol, self.relation, value),
+            (value, Expr::Variable { symbol }) => (*symbol, self.relation.flipped(), value),
+            _ => return None,
+        };
+        value
+            .variable_occurrences()
+            .is_empty()
+            .then_some((symbol, relation, value))
+    }
+}
+
+impl fmt::Display for Inequality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.relation.symbol(), self.rhs)
+    }
+}
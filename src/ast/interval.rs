@@ -0,0 +1,318 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::rational::Rational;
+use super::Expr;
+use crate::input::number::parse_decimal;
+
+/// One end of an interval, `is_closed` when the end itself belongs to it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bound {
+    pub value: Rational,
+    pub is_closed: bool,
+}
+
+/// Interval of real numbers, `None` bounds are `-∞` and `∞`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Interval {
+    pub lower: Option<Bound>,
+    pub upper: Option<Bound>,
+}
+
+/*
+    Union of intervals, e.g. `(-∞; 2) ∪ ⟨5; ∞)`.
+    The intervals are sorted, non-empty and do not overlap or touch,
+    so two unions describe the same set exactly when they are equal.
+*/
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IntervalUnion {
+    intervals: Vec<Interval>,
+}
+
+/// `None` is `-∞`, of two equal values the closed bound starts first.
+fn cmp_lower(a: &Option<Bound>, b: &Option<Bound>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => a.value.cmp(&b.value).then(b.is_closed.cmp(&a.is_closed)),
+    }
+}
+
+/// `None` is `∞`, of two equal values the closed bound ends last.
+fn cmp_upper(a: &Option<Bound>, b: &Option<Bound>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.value.cmp(&b.value).then(a.is_closed.cmp(&b.is_closed)),
+    }
+}
+
+impl Interval {
+    pub fn is_empty(&self) -> bool {
+        let (Some(lower), Some(upper)) = (self.lower, self.upper) else {
+            return false;
+        };
+        match lower.value.cmp(&upper.value) {
+            Ordering::Less => false,
+            Ordering::Equal => !(lower.is_closed && upper.is_closed),
+            Ordering::Greater => true,
+        }
+    }
+
+    /// Whether `next`, which does not start earlier, overlaps or touches this interval.
+    fn reaches(&self, next: &Interval) -> bool {
+        let (Some(upper), Some(lower)) = (self.upper, next.lower) else {
+            return true;
+        };
+        match upper.value.cmp(&lower.value) {
+            Ordering::Less => false,
+            Ordering::Equal => upper.is_closed || lower.is_closed,
+            Ordering::Greater => true,
+        }
+    }
+}
+
+impl IntervalUnion {
+    pub fn empty() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    pub fn all() -> Self {
+        Self::from_intervals(vec![Interval {
+            lower: None,
+            upper: None,
+        }])
+    }
+
+    pub fn from_intervals(mut intervals: Vec<Interval>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort_by(|a, b| cmp_lower(&a.lower, &b.lower));
+
+        let mut merged: Vec<Interval> = vec![];
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.reaches(&interval) => {
+                    if cmp_upper(&interval.upper, &last.upper) == Ordering::Greater {
+                        last.upper = interval.upper;
+                    }
+                }
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /*
+        Parses the Czech notation, closed ends can be written as `⟨ ⟩`, `< >` or `[ ]`:
+            (-∞; 4)
+            ⟨-2; 3/2) ∪ (5; ∞)
+            {3}
+            ∅, R
+        The values may be separated by a comma as well, then a decimal number
+        needs a dot, `(1.5, 3)`, with a semicolon also a comma, `(1,5; 3)`.
+    */
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        match input {
+            "∅" | "{}" | "prázdná množina" => return Ok(Self::empty()),
+            "R" | "ℝ" => return Ok(Self::all()),
+            _ => {}
+        }
+
+        let intervals = input
+            .split(['∪', 'U'])
+            .map(parse_interval)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_intervals(intervals))
+    }
+}
+
+enum Value {
+    Number(Rational),
+    MinusInfinity,
+    Infinity,
+}
+
+/// `2,5` is `5/2`, exactly with as many decimal places as were written.
+fn decimal_value(input: &str) -> Option<Rational> {
+    let value = parse_decimal(input)?;
+    let places = input
+        .rsplit_once([',', '.'])
+        .map_or(0, |(_, decimals)| decimals.trim().len());
+    let scale = 10i64.checked_pow(u32::try_from(places).ok()?)?;
+    Rational::new((value * scale as f64).round() as i64, scale)
+}
+
+fn parse_value(input: &str) -> Result<Value, String> {
+    let input = input.trim();
+    let (is_negative, unsigned) = match input.strip_prefix(['-', '−']) {
+        Some(rest) => (true, rest.trim()),
+        None => (false, input.strip_prefix('+').unwrap_or(input).trim()),
+    };
+    if matches!(unsigned, "∞" | "inf") {
+        return Ok(if is_negative {
+            Value::MinusInfinity
+        } else {
+            Value::Infinity
+        });
+    }
+    if let Some(value) = decimal_value(input) {
+        return Ok(Value::Number(value));
+    }
+    let expr = Expr::parse(input).map_err(|err| format!("\"{input}\": {}", err.message))?;
+    match Rational::from_expr(&expr) {
+        Some(value) => Ok(Value::Number(value)),
+        None => Err(format!("\"{input}\" není číslo.")),
+    }
+}
+
+fn parse_interval(input: &str) -> Result<Interval, String> {
+    let input = input.trim();
+    if let Some(inner) = input
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        let Value::Number(value) = parse_value(inner)? else {
+            return Err("Nekonečno není číslo.".to_string());
+        };
+        let bound = Some(Bound {
+            value,
+            is_closed: true,
+        });
+        return Ok(Interval {
+            lower: bound,
+            upper: bound,
+        });
+    }
+
+    let mut chars = input.chars();
+    let (Some(first), Some(last)) = (chars.next(), chars.next_back()) else {
+        return Err(format!("\"{input}\" není interval."));
+    };
+    let is_lower_closed = match first {
+        '(' => false,
+        '⟨' | '<' | '[' => true,
+        _ => return Err(format!("Interval \"{input}\" musí začínat závorkou.")),
+    };
+    let is_upper_closed = match last {
+        ')' => false,
+        '⟩' | '>' | ']' => true,
+        _ => return Err(format!("Interval \"{input}\" musí končit závorkou.")),
+    };
+
+    let inner = chars.as_str();
+    let separator = if inner.contains(';') { ';' } else { ',' };
+    if separator == ',' && inner.matches(',').count() > 1 {
+        return Err(format!(
+            "V intervalu \"{input}\" odděl krajní body středníkem, čárka patří k desetinnému číslu."
+        ));
+    }
+    let Some((lower, upper)) = inner.split_once(separator) else {
+        return Err(format!(
+            "V intervalu \"{input}\" chybí středník mezi krajními body."
+        ));
+    };
+
+    let lower = match parse_value(lower)? {
+        Value::Number(value) => Some(Bound {
+            value,
+            is_closed: is_lower_closed,
+        }),
+        Value::MinusInfinity if !is_lower_closed => None,
+        Value::MinusInfinity => return Err("U nekonečna musí být kulatá závorka.".to_string()),
+        Value::Infinity => return Err("Interval nemůže začínat v +∞.".to_string()),
+    };
+    let upper = match parse_value(upper)? {
+        Value::Number(value) => Some(Bound {
+            value,
+            is_closed: is_upper_closed,
+        }),
+        Value::Infinity if !is_upper_closed => None,
+        Value::Infinity => return Err("U nekonečna musí být kulatá závorka.".to_string()),
+        Value::MinusInfinity => return Err("Interval nemůže končit v -∞.".to_string()),
+    };
+    Ok(Interval { lower, upper })
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(lower), Some(upper)) = (self.lower, self.upper) {
+            if lower == upper {
+                return write!(f, "{{{}}}", lower.value);
+            }
+        }
+        match self.lower {
+            Some(Bound {
+                value,
+                is_closed: true,
+            }) => write!(f, "⟨{value}")?,
+            Some(Bound { value, .. }) => write!(f, "({value}")?,
+            None => write!(f, "(-∞")?,
+        }
+        write!(f, "; ")?;
+        match self.upper {
+            Some(Bound {
+                value,
+                is_closed: true,
+            }) => write!(f, "{value}⟩"),
+            Some(Bound { value, .. }) => write!(f, "{value})"),
+            None => write!(f, "∞)"),
+        }
+    }
+}
+
+impl fmt::Display for IntervalUnion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.intervals[..] {
+            [] => write!(f, "∅"),
+            [Interval {
+                lower: None,
+                upper: None,
+            }] => write!(f, "ℝ"),
+            intervals => {
+                let parts: Vec<String> = intervals.iter().map(Interval::to_string).collect();
+                write!(f, "{}", parts.join(" ∪ "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> String {
+        IntervalUnion::parse(input).unwrap().to_string()
+    }
+
+    #[test]
+    fn intervals_are_parsed() {
+        assert_eq!(parse("(-∞; 4)"), "(-∞; 4)");
+        assert_eq!(parse("<-2, 3/2) U (5; inf)"), "⟨-2; 3/2) ∪ (5; ∞)");
+        assert_eq!(parse("[1; 3] ∪ (2; 5⟩"), "⟨1; 5⟩");
+        assert_eq!(parse("{-3}"), "{-3}");
+        assert_eq!(parse("R"), "ℝ");
+        assert_eq!(parse("∅"), "∅");
+    }
+
+    #[test]
+    fn bounds_can_be_decimal() {
+        assert_eq!(parse("(-∞; 2,5)"), "(-∞; 5/2)");
+        assert_eq!(parse("⟨-0,75; 1.2)"), "⟨-3/4; 6/5)");
+        assert_eq!(parse("(1.5, 3)"), "(3/2; 3)");
+        assert!(IntervalUnion::parse("(1,5, 3)").is_err());
+    }
+
+    #[test]
+    fn infinity_needs_open_end() {
+        assert!(IntervalUnion::parse("⟨-∞; 4)").is_err());
+        assert!(IntervalUnion::parse("(4; -∞)").is_err());
+        assert!(IntervalUnion::parse("(2; 3").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use super::precedence;
+use super::Expr;
+
+/*
+    LaTeX form of an expression, e.g. `(a + 1)/√2` is `\frac{a + 1}{\sqrt{2}}`.
+    Parentheses are added by the same rules as in `Display`, except that
+    fractions need none, because they are written on two lines.
+*/
+impl Expr {
+    pub fn to_latex(&self) -> String {
+        match self {
+            Expr::Addition(exprs) => {
+                let mut result = String::new();
+                for (idx, mut expr) in exprs.iter().enumerate() {
+                    if idx != 0 {
+                        if let Expr::UnaryMinus(inner_expr) = expr {
+                            expr = inner_expr;
+                            result.push_str(" - ");
+                        } else {
+                            result.push_str(" + ");
+                        }
+                    }
+                    let needs_parentheses = precedence::ADDITION.is_before(expr.precedence());
+                    result.push_str(&wrap(expr, needs_parentheses));
+                }
+                result
+            }
+            Expr::Multiplication(exprs) => exprs
+                .iter()
+                .map(|expr| {
+                    let needs_parentheses = precedence::MULTIPLICATION.is_before(expr.precedence());
+                    wrap(expr, needs_parentheses)
+                })
+                .collect::<Vec<_>>()
+                .join(" \\cdot "),
+            Expr::Division { lhs, rhs } => {
+                format!("\\frac{{{}}}{{{}}}", lhs.to_latex(), rhs.to_latex())
+            }
+            Expr::UnaryMinus(expr) => {
+                let is_negative_product = match &**expr {
+                    Expr::Multiplication(exprs) => {
+                        matches!(exprs.first(), Some(Expr::UnaryMinus(_)))
+                    }
+                    Expr::Division { .. } => false,
+                    expr => precedence::UNARY_MINUS.is_before(expr.precedence()),
+                };
+                format!("-{}", wrap(expr, is_negative_product))
+            }
+            Expr::Exp { base, exp } => {
+                let needs_parentheses =
+                    precedence::EXPONENTIATION.is_same_or_before(base.precedence());
+                format!("{}^{{{}}}", wrap(base, needs_parentheses), exp.to_latex())
+            }
+            Expr::Root {
+                degree: 2,
+                radicand,
+            } => format!("\\sqrt{{{}}}", radicand.to_latex()),
+            Expr::Root { degree, radicand } => {
+                format!("\\sqrt[{degree}]{{{}}}", radicand.to_latex())
+            }
+            Expr::Number(num) => num.to_string(),
+            Expr::Variable { symbol } => symbol.to_string(),
+        }
+    }
+}
+
+fn wrap(expr: &Expr, needs_parentheses: bool) -> String {
+    if needs_parentheses {
+        format!("\\left({}\\right)", expr.to_latex())
+    } else {
+        expr.to_latex()
+    }
+}
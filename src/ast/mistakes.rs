@@ -0,0 +1,264 @@
+use super::canonical::{CanonicalFraction, CanonicalTerm};
+use super::Expr;
+
+/*
+    Typical errors made while simplifying an expression.
+    Each of them is a mutation of the correct expression, so a wrong answer
+    can be recognized by making the mistake on purpose and comparing the results.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mistake {
+    /// One term with the opposite sign, e.g. `-(a - b) = -a - b`.
+    Sign,
+    /// `(3a)^2 = 3a^2`
+    CoefficientNotPowered,
+    /// `(a^3)^2 = a^5`
+    ExponentsAdded,
+    /// One term of a sum got lost.
+    DroppedTerm,
+    /// `(a + b)^2 = a^2 + b^2`
+    PowerOfSum,
+}
+
+impl Mistake {
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Mistake::Sign => "Vypadá to na chybu ve znaménku, jeden člen má opačné znaménko.",
+            Mistake::CoefficientNotPowered => "Umocni i číslo v závorce: (3a)^2 = 9a^2, ne 3a^2.",
+            Mistake::ExponentsAdded => {
+                "Při umocnění mocniny se exponenty násobí: (a^3)^2 = a^6, ne a^5."
+            }
+            Mistake::DroppedTerm => "Jeden člen se ti cestou ztratil.",
+            Mistake::PowerOfSum => {
+                "Součet se neumocňuje člen po členu: (a + b)^2 = a^2 + 2ab + b^2, ne a^2 + b^2."
+            }
+        }
+    }
+}
+
+fn negate(expr: &Expr) -> Expr {
+    match expr {
+        Expr::UnaryMinus(inner) => (**inner).clone(),
+        _ => Expr::UnaryMinus(expr.clone().into()),
+    }
+}
+
+fn integer_exponent(exp: &Expr) -> Option<i64> {
+    CanonicalTerm::from_expr(exp)?.as_integer()
+}
+
+/// `base^(exp + added)` instead of `base^(exp·added)`.
+fn add_exponent(base: &Expr, added: i64) -> Option<Expr> {
+    let (base, exp) = match base {
+        Expr::Exp { base, exp } => ((**base).clone(), integer_exponent(exp)?),
+        _ => (base.clone(), 1),
+    };
+    Some(Expr::Exp {
+        base: base.into(),
+        exp: Expr::integer(exp + added)?.into(),
+    })
+}
+
+/// Mistakes made at the top of `expr`, its parts stay correct.
+fn local_mutations(expr: &Expr, found: &mut Vec<(Mistake, Expr)>) {
+    match expr {
+        Expr::Addition(exprs) => {
+            for idx in 0..exprs.len() {
+                let mut flipped = exprs.clone();
+                flipped[idx] = negate(&exprs[idx]);
+                found.push((Mistake::Sign, Expr::Addition(flipped)));
+
+                if exprs.len() > 1 {
+                    let mut dropped = exprs.clone();
+                    dropped.remove(idx);
+                    found.push((Mistake::DroppedTerm, Expr::Addition(dropped)));
+                }
+            }
+        }
+        Expr::UnaryMinus(inner) => {
+            found.push((Mistake::Sign, (**inner).clone()));
+            // The minus only applied to the first term of the parentheses
+            if let Expr::Addition(exprs) = &**inner {
+                let mut first_negated = exprs.clone();
+                first_negated[0] = negate(&exprs[0]);
+                found.push((Mistake::Sign, Expr::Addition(first_negated)));
+            }
+        }
+        Expr::Exp { base, exp } => {
+            let Some(power) = integer_exponent(exp) else {
+                return;
+            };
+            match &**base {
+                Expr::Addition(exprs) => {
+                    let powered = exprs
+                        .iter()
+                        .map(|expr| Expr::Exp {
+                            base: expr.clone().into(),
+                            exp: exp.clone(),
+                        })
+                        .collect();
+                    found.push((Mistake::PowerOfSum, Expr::Addition(powered)));
+                }
+                Expr::Multiplication(factors) => {
+                    let (numbers, rest): (Vec<Expr>, Vec<Expr>) = factors
+                        .iter()
+                        .cloned()
+                        .partition(|factor| matches!(factor, Expr::Number(_)));
+                    if !numbers.is_empty() && !rest.is_empty() {
+                        let mut not_powered = numbers;
+                        not_powered.push(Expr::Exp {
+                            base: Expr::Multiplication(rest).into(),
+                            exp: exp.clone(),
+                        });
+                        found.push((
+                            Mistake::CoefficientNotPowered,
+                            Expr::Multiplication(not_powered),
+                        ));
+                    }
+
+                    // Every power inside gets the exponent added, the rest is powered correctly
+                    if factors
+                        .iter()
+                        .any(|factor| matches!(factor, Expr::Exp { .. }))
+                    {
+                        let added = factors
+                            .iter()
+                            .map(|factor| match factor {
+                                Expr::Exp { .. } => add_exponent(factor, power),
+                                _ => Some(Expr::Exp {
+                                    base: factor.clone().into(),
+                                    exp: exp.clone(),
+                                }),
+                            })
+                            .collect::<Option<Vec<_>>>();
+                        if let Some(added) = added {
+                            found.push((Mistake::ExponentsAdded, Expr::Multiplication(added)));
+                        }
+                    }
+                }
+                Expr::Exp { .. } => {
+                    if let Some(added) = add_exponent(base, power) {
+                        found.push((Mistake::ExponentsAdded, added));
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `expr` with its `idx`-th part replaced.
+fn with_part(expr: &Expr, idx: usize, part: Expr) -> Expr {
+    match expr {
+        Expr::Addition(exprs) | Expr::Multiplication(exprs) => {
+            let mut exprs = exprs.clone();
+            exprs[idx] = part;
+            match expr {
+                Expr::Addition(_) => Expr::Addition(exprs),
+                _ => Expr::Multiplication(exprs),
+            }
+        }
+        Expr::Division { lhs, rhs } => match idx {
+            0 => Expr::Division {
+                lhs: part.into(),
+                rhs: rhs.clone(),
+            },
+            _ => Expr::Division {
+                lhs: lhs.clone(),
+                rhs: part.into(),
+            },
+        },
+        Expr::UnaryMinus(_) => Expr::UnaryMinus(part.into()),
+        Expr::Exp { exp, .. } => Expr::Exp {
+            base: part.into(),
+            exp: exp.clone(),
+        },
+        Expr::Root { degree, .. } => Expr::Root {
+            degree: *degree,
+            radicand: part.into(),
+        },
+        Expr::Number(_) | Expr::Variable { symbol: _ } => expr.clone(),
+    }
+}
+
+/// The parts a mistake can be made in, exponents are left alone.
+fn parts(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::Addition(exprs) | Expr::Multiplication(exprs) => exprs.iter().collect(),
+        Expr::Division { lhs, rhs } => vec![lhs, rhs],
+        Expr::UnaryMinus(inner) => vec![inner],
+        Expr::Exp { base, .. } => vec![base],
+        Expr::Root { radicand, .. } => vec![radicand],
+        Expr::Number(_) | Expr::Variable { symbol: _ } => vec![],
+    }
+}
+
+/// Every expression that differs from `expr` by exactly one mistake.
+pub fn mutations(expr: &Expr) -> Vec<(Mistake, Expr)> {
+    let mut found = vec![];
+    local_mutations(expr, &mut found);
+    for (idx, part) in parts(expr).into_iter().enumerate() {
+        for (mistake, mutated) in mutations(part) {
+            found.push((mistake, with_part(expr, idx, mutated)));
+        }
+    }
+    found
+}
+
+/// The single mistake which turns `correct` into `answer`, if there is one.
+pub fn diagnose(answer: &Expr, correct: &Expr) -> Option<Mistake> {
+    let answer = CanonicalFraction::from_expr(answer)?;
+    let correct_value = CanonicalFraction::from_expr(correct)?;
+    if answer.is_equal(&correct_value) != Some(false) {
+        return None;
+    }
+    let whole_negated = (Mistake::Sign, negate(correct));
+    mutations(correct)
+        .into_iter()
+        .chain([whole_negated])
+        .find(|(_, mutated)| {
+            let Some(mutated) = CanonicalFraction::from_expr(mutated) else {
+                return false;
+            };
+            // Some mistakes do not change anything, e.g. `(a^2)^2 = a^(2 + 2)`
+            mutated.is_equal(&correct_value) == Some(false)
+                && mutated.is_equal(&answer) == Some(true)
+        })
+        .map(|(mistake, _)| mistake)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typical_mistakes_are_recognized() {
+        let diagnose = |correct: &str, answer: &str| {
+            diagnose(
+                &Expr::parse(answer).unwrap(),
+                &Expr::parse(correct).unwrap(),
+            )
+        };
+        assert_eq!(diagnose("(a^3)^2", "a^5"), Some(Mistake::ExponentsAdded));
+        assert_eq!(
+            diagnose("(2a^3b)^2", "4a^5b^2"),
+            Some(Mistake::ExponentsAdded)
+        );
+        assert_eq!(
+            diagnose("(3a)^2", "3a^2"),
+            Some(Mistake::CoefficientNotPowered)
+        );
+        assert_eq!(
+            diagnose("(a + b)^2", "a^2 + b^2"),
+            Some(Mistake::PowerOfSum)
+        );
+        assert_eq!(diagnose("-(a - b)", "-a - b"), Some(Mistake::Sign));
+        assert_eq!(
+            diagnose("a^2 + 2ab + b^2", "a^2 + b^2"),
+            Some(Mistake::DroppedTerm)
+        );
+        assert_eq!(diagnose("(a + b)^2", "a^2 + 2ab + b^2"), None);
+        assert_eq!(diagnose("(a + b)^2", "7b"), None);
+    }
+}
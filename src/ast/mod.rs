@@ -1,54 +1,161 @@
-use std::fmt::{self, write};
-use std::rc::Rc;
-pub mod canonical;
-pub mod display;
-pub mod precedence;
-
-use precedence::*;
-
-pub type Digit = u32;
-
-#[derive(Clone, PartialEq, Eq)]
-pub enum Expr {
-    Addition(Vec<Expr>),
-    Multiplication(Vec<Expr>),
-    Division { lhs: Rc<Expr>, rhs: Rc<Expr> },
-    UnaryMinus(Rc<Expr>),
-    Number(u32),
-    Variable { symbol: char },
-    Exp { base: Rc<Expr>, exp: Rc<Expr> },
-}
-
-impl Expr {
-    pub fn zero() -> Expr {
-        Expr::Number(0)
-    }
-
-    pub fn maybe_wrap_in_minus(self, should_be_minus: bool) -> Expr {
-        if should_be_minus {
-            Expr::UnaryMinus(self.into())
-        } else {
-            self
-        }
-    }
-
-    pub fn signed_number(number: i32) -> Expr {
-        if number.is_negative() {
-            Expr::UnaryMinus(Expr::Number(number.unsigned_abs()).into())
-        } else {
-            Expr::Number(number.unsigned_abs())
-        }
-    }
-
-    pub fn precedence(&self) -> Precedence {
-        match self {
-            Expr::Addition(_) => ADDITION,
-            Expr::Multiplication(_) => MULTIPLICATION,
-            Expr::Division { lhs: _, rhs: _ } => DIVISION,
-            Expr::UnaryMinus(_) => UNARY_MINUS,
-            Expr::Exp { base: _, exp: _ } => EXPONENTIATION,
-            Expr::Number(_) => NUMBER,
-            Expr::Variable { symbol: _ } => VARIABLE,
-        }
-    }
-}
+use std::rc::Rc;
+pub mod canonical;
+pub mod display;
+pub mod equivalence;
+pub mod equation;
+pub mod inequality;
+pub mod interval;
+pub mod latex;
+pub mod mistakes;
+pub mod parse;
+pub mod precedence;
+pub mod radical;
+pub mod rational;
+pub mod simplify;
+
+use precedence::*;
+
+pub type Digit = u32;
+
+/// What to do with negative exponents, `a^-2` can be also written as `1/a^2`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NegativeExponents {
+    Keep,
+    AsFraction,
+}
+
+/// Powers which cannot be computed, the generators pick other numbers then.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowError {
+    ZeroToZero,
+    ZeroToNegative,
+    TooLarge,
+    /// `(a + b)^2` would have to be expanded.
+    Sum,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Expr {
+    Addition(Vec<Expr>),
+    Multiplication(Vec<Expr>),
+    Division { lhs: Rc<Expr>, rhs: Rc<Expr> },
+    UnaryMinus(Rc<Expr>),
+    Number(u32),
+    Variable { symbol: char },
+    Exp { base: Rc<Expr>, exp: Rc<Expr> },
+    Root { degree: u32, radicand: Rc<Expr> },
+}
+
+impl Expr {
+    pub fn zero() -> Expr {
+        Expr::Number(0)
+    }
+
+    pub fn maybe_wrap_in_minus(self, should_be_minus: bool) -> Expr {
+        if should_be_minus {
+            Expr::UnaryMinus(self.into())
+        } else {
+            self
+        }
+    }
+
+    pub fn signed_number(number: i32) -> Expr {
+        if number.is_negative() {
+            Expr::UnaryMinus(Expr::Number(number.unsigned_abs()).into())
+        } else {
+            Expr::Number(number.unsigned_abs())
+        }
+    }
+
+    /// Every occurrence of a variable, `a·b/a` gives `['a', 'b', 'a']`.
+    pub fn variable_occurrences(&self) -> Vec<char> {
+        match self {
+            Expr::Addition(exprs) | Expr::Multiplication(exprs) => exprs
+                .iter()
+                .flat_map(|expr| expr.variable_occurrences())
+                .collect(),
+            Expr::Division { lhs: a, rhs: b } | Expr::Exp { base: a, exp: b } => {
+                let mut occurrences = a.variable_occurrences();
+                occurrences.extend(b.variable_occurrences());
+                occurrences
+            }
+            Expr::UnaryMinus(expr)
+            | Expr::Root {
+                degree: _,
+                radicand: expr,
+            } => expr.variable_occurrences(),
+            Expr::Number(_) => vec![],
+            Expr::Variable { symbol } => vec![*symbol],
+        }
+    }
+
+    /// Replaces every occurrence of `var` with `value`.
+    pub fn substitute(&self, var: char, value: &Expr) -> Expr {
+        let sub = |expr: &Rc<Expr>| Rc::new(expr.substitute(var, value));
+        match self {
+            Expr::Addition(exprs) => {
+                Expr::Addition(exprs.iter().map(|e| e.substitute(var, value)).collect())
+            }
+            Expr::Multiplication(exprs) => {
+                Expr::Multiplication(exprs.iter().map(|e| e.substitute(var, value)).collect())
+            }
+            Expr::Division { lhs, rhs } => Expr::Division {
+                lhs: sub(lhs),
+                rhs: sub(rhs),
+            },
+            Expr::Exp { base, exp } => Expr::Exp {
+                base: sub(base),
+                exp: sub(exp),
+            },
+            Expr::UnaryMinus(expr) => Expr::UnaryMinus(sub(expr)),
+            Expr::Root { degree, radicand } => Expr::Root {
+                degree: *degree,
+                radicand: sub(radicand),
+            },
+            Expr::Variable { symbol } if *symbol == var => value.clone(),
+            Expr::Number(_) | Expr::Variable { symbol: _ } => self.clone(),
+        }
+    }
+
+    /// Value of an expression without variables, `None` when it is not a real number.
+    pub fn approximate(&self) -> Option<f64> {
+        let value = match self {
+            Expr::Addition(exprs) => exprs.iter().map(Expr::approximate).sum::<Option<f64>>()?,
+            Expr::Multiplication(exprs) => exprs
+                .iter()
+                .map(Expr::approximate)
+                .product::<Option<f64>>()?,
+            Expr::Division { lhs, rhs } => lhs.approximate()? / rhs.approximate()?,
+            Expr::UnaryMinus(expr) => -expr.approximate()?,
+            Expr::Number(num) => *num as f64,
+            Expr::Variable { symbol: _ } => return None,
+            Expr::Exp { base, exp } => base.approximate()?.powf(exp.approximate()?),
+            Expr::Root { degree, radicand } => {
+                let radicand = radicand.approximate()?;
+                let root = radicand.abs().powf(1.0 / *degree as f64);
+                match (radicand < 0.0, degree % 2 == 0) {
+                    (true, true) => return None,
+                    (true, false) => -root,
+                    (false, _) => root,
+                }
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+
+    pub fn precedence(&self) -> Precedence {
+        match self {
+            Expr::Addition(_) => ADDITION,
+            Expr::Multiplication(_) => MULTIPLICATION,
+            Expr::Division { lhs: _, rhs: _ } => DIVISION,
+            Expr::UnaryMinus(_) => UNARY_MINUS,
+            Expr::Exp { base: _, exp: _ } => EXPONENTIATION,
+            Expr::Root {
+                degree: _,
+                radicand: _,
+            } => ROOT,
+            Expr::Number(_) => NUMBER,
+            Expr::Variable { symbol: _ } => VARIABLE,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+use std::iter::Enumerate;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use super::Expr;
+
+/*
+    Parses what the student typed, e.g. `-2a^3b^-2 + (a - 1)/4`.
+
+        sum      = product (("+" | "-") product)*
+        product  = implicit (("*" | "·" | "/" | ":") implicit)*
+        implicit = signed atom_pow*           (`2ab` means `2·a·b`)
+        signed   = "-" signed | atom_pow
+        atom_pow = atom ("^" signed)?
+        atom     = number | variable | "(" sum ")" | root
+        root     = ("√" | "sqrt") atom
+
+    Factors written next to each other bind tighter than `/`,
+    so `a/2b` is `a/(2·b)`, the same as it would be read on paper.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (na pozici {})", self.message, self.position + 1)
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Enumerate<Chars<'a>>>,
+    len: usize,
+}
+
+type ParseResult = Result<Expr, ParseError>;
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().enumerate().peekable(),
+            len: input.chars().count(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, chr)| chr.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|(_, chr)| *chr)
+    }
+
+    fn position(&mut self) -> usize {
+        self.skip_whitespace();
+        self.chars.peek().map(|(pos, _)| *pos).unwrap_or(self.len)
+    }
+
+    fn error<T>(&mut self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position(),
+            message: message.into(),
+        })
+    }
+
+    fn eat(&mut self, expected: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        self.chars
+            .next_if(|(_, chr)| expected.contains(chr))
+            .map(|(_, chr)| chr)
+    }
+
+    fn sum(&mut self) -> ParseResult {
+        let mut terms = vec![self.product()?];
+        while let Some(operator) = self.eat(&['+', '-', '−']) {
+            let term = self.product()?;
+            terms.push(term.maybe_wrap_in_minus(operator != '+'));
+        }
+        Ok(Expr::sum(terms))
+    }
+
+    fn product(&mut self) -> ParseResult {
+        let mut factors = vec![self.implicit_product()?];
+        while let Some(operator) = self.eat(&['*', '·', '⋅', '/', ':']) {
+            let factor = self.implicit_product()?;
+            if operator == '/' || operator == ':' {
+                let lhs = Expr::product(std::mem::take(&mut factors));
+                factors.push(Expr::Division {
+                    lhs: lhs.into(),
+                    rhs: factor.into(),
+                });
+            } else {
+                factors.push(factor);
+            }
+        }
+        Ok(Expr::product(factors))
+    }
+
+    fn implicit_product(&mut self) -> ParseResult {
+        let mut factors = vec![self.signed()?];
+        while let Some(chr) = self.peek() {
+            if !(chr.is_ascii_digit() || chr.is_ascii_lowercase() || chr == '(' || chr == '√') {
+                break;
+            }
+            factors.push(self.atom_pow()?);
+        }
+        Ok(Expr::product(factors))
+    }
+
+    fn signed(&mut self) -> ParseResult {
+        if self.eat(&['-', '−']).is_some() {
+            Ok(Expr::UnaryMinus(self.signed()?.into()))
+        } else {
+            self.atom_pow()
+        }
+    }
+
+    fn atom_pow(&mut self) -> ParseResult {
+        let base = self.atom()?;
+        if self.eat(&['^']).is_some() {
+            let exp = self.signed()?;
+            Ok(Expr::Exp {
+                base: base.into(),
+                exp: exp.into(),
+            })
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Skips `keyword` when the input continues with it.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let mut lookahead = self.chars.clone();
+        let matches = keyword
+            .chars()
+            .all(|expected| lookahead.next().is_some_and(|(_, chr)| chr == expected));
+        if matches {
+            self.chars = lookahead;
+        }
+        matches
+    }
+
+    fn atom(&mut self) -> ParseResult {
+        if self.eat(&['√']).is_some() || self.eat_keyword("sqrt") {
+            return Ok(Expr::Root {
+                degree: 2,
+                radicand: self.atom()?.into(),
+            });
+        }
+        match self.peek() {
+            Some(chr) if chr.is_ascii_digit() => self.number(),
+            Some(chr) if chr.is_ascii_lowercase() => {
+                self.chars.next();
+                Ok(Expr::Variable { symbol: chr })
+            }
+            Some('(') => {
+                self.chars.next();
+                let inner = self.sum()?;
+                if self.eat(&[')']).is_none() {
+                    return self.error("Chybí zavírací závorka");
+                }
+                Ok(inner)
+            }
+            Some(')') => self.error("Přebývá zavírací závorka"),
+            Some(chr) if chr.is_alphabetic() => self.error(format!(
+                "\"{chr}\" není proměnná, používej malá písmena a-z"
+            )),
+            Some(chr) => self.error(format!("Tady jsem nečekal \"{chr}\"")),
+            None => self.error("Výraz tady nemůže skončit"),
+        }
+    }
+
+    fn number(&mut self) -> ParseResult {
+        let start = self.position();
+        let mut digits = String::new();
+        while let Some((_, chr)) = self.chars.next_if(|(_, chr)| chr.is_ascii_digit()) {
+            digits.push(chr);
+        }
+        digits.parse().map(Expr::Number).map_err(|_| ParseError {
+            position: start,
+            message: format!("Číslo {digits} je moc velké"),
+        })
+    }
+}
+
+impl Expr {
+    pub fn parse(input: &str) -> Result<Expr, ParseError> {
+        let mut parser = Parser::new(input);
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(')') => parser.error("Přebývá zavírací závorka"),
+            Some(chr) => parser.error(format!("Tady jsem nečekal \"{chr}\"")),
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::canonical::{gcd, lcm};
+use super::rational::Rational;
+use super::Expr;
+
+/*
+    Exact value of an expression with roots of numbers, e.g. `2 - 3·√2/4 + ∛5`.
+    It is a sum of rational multiples of roots and every root is reduced,
+    `√8` is `2·√2` and `⁴√9` is `√3`. Different reduced roots can not be
+    summed up to a rational number, so two equal values have equal terms.
+*/
+
+/// `radicand` has no `degree`-th power in it and `degree` is the smallest possible.
+/// The rational part of a sum is kept at the root of degree 1 of 1.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Radical {
+    degree: u32,
+    radicand: u32,
+}
+
+impl Radical {
+    const ONE: Radical = Radical {
+        degree: 1,
+        radicand: 1,
+    };
+
+    /// `√2·∛2` is `⁶√32`, the whole part of the product is returned separately.
+    fn mul(self, other: Radical) -> Option<(Rational, Radical)> {
+        let degree = u32::try_from(lcm(self.degree.into(), other.degree.into())).ok()?;
+        let mut factors = BTreeMap::new();
+        add_prime_factors(&mut factors, self.radicand, degree / self.degree);
+        add_prime_factors(&mut factors, other.radicand, degree / other.degree);
+        reduce(degree, &factors)
+    }
+}
+
+/// Adds the prime factors of `number` raised to `power`, `12` adds `2^2` and `3^1`.
+fn add_prime_factors(factors: &mut BTreeMap<u32, u32>, mut number: u32, power: u32) {
+    let mut prime = 2;
+    while u64::from(prime) * u64::from(prime) <= u64::from(number) {
+        while number.is_multiple_of(prime) {
+            number /= prime;
+            *factors.entry(prime).or_default() += power;
+        }
+        prime += 1;
+    }
+    if number > 1 {
+        *factors.entry(number).or_default() += power;
+    }
+}
+
+/// `degree`-th root of the product of `factors`, split into its whole part and a reduced root.
+fn reduce(degree: u32, factors: &BTreeMap<u32, u32>) -> Option<(Rational, Radical)> {
+    let mut outside = Rational::integer(1);
+    let mut inside = vec![];
+    for (&prime, &exponent) in factors {
+        let whole = Rational::integer(prime.into()).pow(i32::try_from(exponent / degree).ok()?)?;
+        outside = outside.mul(whole)?;
+        if exponent % degree != 0 {
+            inside.push((prime, exponent % degree));
+        }
+    }
+    // ⁴√9 = √3, the exponents and the degree are divided by their common divisor
+    let divisor = inside.iter().fold(u64::from(degree), |acc, (_, exponent)| {
+        gcd(acc, u64::from(*exponent))
+    }) as u32;
+    let mut radicand = 1u32;
+    for (prime, exponent) in inside {
+        radicand = radicand.checked_mul(prime.checked_pow(exponent / divisor)?)?;
+    }
+    let radical = if radicand == 1 {
+        Radical::ONE
+    } else {
+        Radical {
+            degree: degree / divisor,
+            radicand,
+        }
+    };
+    Some((outside, radical))
+}
+
+/// Sum of rational multiples of reduced roots, zero terms are left out.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RootSum(BTreeMap<Radical, Rational>);
+
+impl RootSum {
+    pub fn rational(value: Rational) -> Self {
+        let mut sum = Self(BTreeMap::new());
+        if !value.is_zero() {
+            sum.0.insert(Radical::ONE, value);
+        }
+        sum
+    }
+
+    /// `None` when there is a root in the value.
+    pub fn as_rational(&self) -> Option<Rational> {
+        match self.single_term() {
+            Some((Radical::ONE, value)) => Some(value),
+            Some(_) => None,
+            None if self.0.is_empty() => Some(Rational::zero()),
+            None => None,
+        }
+    }
+
+    fn single_term(&self) -> Option<(Radical, Rational)> {
+        match self.0.iter().collect::<Vec<_>>()[..] {
+            [(radical, value)] => Some((*radical, *value)),
+            _ => None,
+        }
+    }
+
+    fn add_term(&mut self, radical: Radical, value: Rational) -> Option<()> {
+        let sum = self
+            .0
+            .get(&radical)
+            .map_or(Some(value), |old| old.add(value))?;
+        if sum.is_zero() {
+            self.0.remove(&radical);
+        } else {
+            self.0.insert(radical, sum);
+        }
+        Some(())
+    }
+
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        let mut sum = self.clone();
+        for (radical, value) in &other.0 {
+            sum.add_term(*radical, *value)?;
+        }
+        Some(sum)
+    }
+
+    pub fn sub(&self, other: &Self) -> Option<Self> {
+        self.add(&other.negate())
+    }
+
+    pub fn negate(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(radical, value)| (*radical, value.negate()))
+                .collect(),
+        )
+    }
+
+    pub fn mul(&self, other: &Self) -> Option<Self> {
+        let mut product = Self(BTreeMap::new());
+        for (lhs_radical, lhs_value) in &self.0 {
+            for (rhs_radical, rhs_value) in &other.0 {
+                let (outside, radical) = lhs_radical.mul(*rhs_radical)?;
+                product.add_term(radical, lhs_value.mul(*rhs_value)?.mul(outside)?)?;
+            }
+        }
+        Some(product)
+    }
+
+    /// `None` for zero, for too large numbers and for sums with higher roots than square roots.
+    fn inverse(&self) -> Option<Self> {
+        let one = Rational::integer(1);
+        if let Some((radical, value)) = self.single_term() {
+            // 1/(c·∛k) = ∛(k^2)/(c·k)
+            let root = Self(BTreeMap::from([(radical, one)]));
+            let power = root.pow(i32::try_from(radical.degree).ok()? - 1)?;
+            let divisor = value.mul(Rational::integer(radical.radicand.into()))?;
+            return power.mul(&Self::rational(one.div(divisor)?));
+        }
+        if self.0.keys().any(|radical| radical.degree > 2) {
+            return None;
+        }
+        // Every conjugate removes one prime from under the roots, 1/(1 + √2) = (1 - √2)/(1 - 2)
+        let mut numerator = Self::rational(one);
+        let mut denominator = self.clone();
+        while let Some(radical) = denominator.0.keys().find(|radical| radical.degree == 2) {
+            let mut factors = BTreeMap::new();
+            add_prime_factors(&mut factors, radical.radicand, 1);
+            let prime = *factors.keys().next()?;
+            let conjugate = Self(
+                denominator
+                    .0
+                    .iter()
+                    .map(
+                        |(radical, value)| match radical.radicand.is_multiple_of(prime) {
+                            true => (*radical, value.negate()),
+                            false => (*radical, *value),
+                        },
+                    )
+                    .collect(),
+            );
+            numerator = numerator.mul(&conjugate)?;
+            denominator = denominator.mul(&conjugate)?;
+        }
+        numerator.mul(&Self::rational(one.div(denominator.as_rational()?)?))
+    }
+
+    /// `None` for division by zero.
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        self.mul(&other.inverse()?)
+    }
+
+    /// `None` for a negative power of zero or too large numbers.
+    pub fn pow(&self, exponent: i32) -> Option<Self> {
+        if let Some(value) = self.as_rational() {
+            return Some(Self::rational(value.pow(exponent)?));
+        }
+        let base = if exponent < 0 {
+            self.inverse()?
+        } else {
+            self.clone()
+        };
+        (0..exponent.unsigned_abs()).try_fold(Self::rational(Rational::integer(1)), |acc, _| {
+            acc.mul(&base)
+        })
+    }
+
+    /// `None` for an even root of a negative number and for roots of sums, like `√(2 + √3)`.
+    pub fn root(&self, degree: u32) -> Option<Self> {
+        if self.0.is_empty() {
+            return (degree != 0).then(|| self.clone());
+        }
+        let (radical, value) = self.single_term()?;
+        // ⁿ√(c·ᵈ√k) = ᵈⁿ√(c^d·k)
+        let value = value
+            .pow(i32::try_from(radical.degree).ok()?)?
+            .mul(Rational::integer(radical.radicand.into()))?;
+        Self::root_of_rational(value, degree.checked_mul(radical.degree)?)
+    }
+
+    fn root_of_rational(value: Rational, degree: u32) -> Option<Self> {
+        if degree == 0 || (value.is_negative() && degree.is_multiple_of(2)) {
+            return None;
+        }
+        // ᵈ√(p/q) = ᵈ√(p·q^(d-1))/q
+        let numerator = u32::try_from(value.numerator().unsigned_abs()).ok()?;
+        let denominator = u32::try_from(value.denominator()).ok()?;
+        let mut factors = BTreeMap::new();
+        add_prime_factors(&mut factors, numerator, 1);
+        add_prime_factors(&mut factors, denominator, degree - 1);
+        let (outside, radical) = reduce(degree, &factors)?;
+        let outside = outside.div(Rational::integer(denominator.into()))?;
+        let outside = if value.is_negative() {
+            outside.negate()
+        } else {
+            outside
+        };
+        let mut root = Self(BTreeMap::new());
+        root.add_term(radical, outside)?;
+        Some(root)
+    }
+}
+
+impl fmt::Display for RootSum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "0");
+        }
+        for (idx, (radical, value)) in self.0.iter().enumerate() {
+            let sign = match (idx, value.is_negative()) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+            let root = (*radical != Radical::ONE).then(|| Expr::Root {
+                degree: radical.degree,
+                radicand: Expr::Number(radical.radicand).into(),
+            });
+            let value = value.abs();
+            match (value.numerator(), root) {
+                (numerator, None) => write!(f, "{sign}{numerator}")?,
+                (1, Some(root)) => write!(f, "{sign}{root}")?,
+                (numerator, Some(root)) => write!(f, "{sign}{numerator}·{root}")?,
+            }
+            if value.denominator() != 1 {
+                write!(f, "/{}", value.denominator())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::equivalence::{evaluate, Point};
+
+    fn value(expr: &Expr) -> Option<String> {
+        Some(evaluate(expr, &Point::default())?.to_string())
+    }
+
+    fn parsed(input: &str) -> Option<String> {
+        value(&Expr::parse(input).unwrap())
+    }
+
+    #[test]
+    fn roots_are_reduced() {
+        assert_eq!(parsed("√8").as_deref(), Some("2·√2"));
+        assert_eq!(parsed("√(9/8)").as_deref(), Some("3·√2/4"));
+        assert_eq!(parsed("√(√9)").as_deref(), Some("√3"));
+        assert_eq!(parsed("√2·√3 - √6").as_deref(), Some("0"));
+        assert_eq!(parsed("√18/√2").as_deref(), Some("3"));
+        assert_eq!(parsed("(√3)^3").as_deref(), Some("3·√3"));
+        assert_eq!(parsed("√(-4)"), None);
+    }
+
+    #[test]
+    fn denominators_are_rationalized() {
+        assert_eq!(parsed("2/(√3 - 1)").as_deref(), Some("1 + √3"));
+        assert_eq!(parsed("1/(√2 + √3)").as_deref(), Some("-√2 + √3"));
+        assert_eq!(parsed("6/(2·√15)").as_deref(), Some("√15/5"));
+        assert_eq!(parsed("1/(√2 - √2)"), None);
+        let cube_root = Expr::Division {
+            lhs: Expr::Number(1).into(),
+            rhs: Expr::Root {
+                degree: 3,
+                radicand: Expr::Number(2).into(),
+            }
+            .into(),
+        };
+        assert_eq!(value(&cube_root).as_deref(), Some("∛4/2"));
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::canonical::{gcd, CanonicalTerm};
+use super::Expr;
+
+/*
+    Exact signed fraction, e.g. a bound of an interval.
+    It is always reduced and the denominator is positive, so two equal
+    numbers have equal fields.
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    /// `None` when the denominator is zero.
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i64;
+        let sign = denominator.signum();
+        Some(Self {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        })
+    }
+
+    pub fn integer(value: i64) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::integer(0)
+    }
+
+    /// Value of an expression without variables, `None` for roots, variables or too large numbers.
+    pub fn from_expr(expr: &Expr) -> Option<Self> {
+        CanonicalTerm::from_expr(expr)?.as_rational()
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.numerator < 0
+    }
+
+    /// Reduces a result computed in `i128`, `None` when it does not fit back.
+    fn from_wide(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let (mut a, mut b) = (numerator.unsigned_abs(), denominator.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let divisor = a as i128 * denominator.signum();
+        Some(Self {
+            numerator: i64::try_from(numerator / divisor).ok()?,
+            denominator: i64::try_from(denominator / divisor).ok()?,
+        })
+    }
+
+    pub fn add(self, other: Self) -> Option<Self> {
+        let (a, b) = (i128::from(self.numerator), i128::from(self.denominator));
+        let (c, d) = (i128::from(other.numerator), i128::from(other.denominator));
+        Self::from_wide(a * d + c * b, b * d)
+    }
+
+    pub fn sub(self, other: Self) -> Option<Self> {
+        self.add(other.negate())
+    }
+
+    pub fn mul(self, other: Self) -> Option<Self> {
+        let (a, b) = (i128::from(self.numerator), i128::from(self.denominator));
+        let (c, d) = (i128::from(other.numerator), i128::from(other.denominator));
+        Self::from_wide(a * c, b * d)
+    }
+
+    pub fn numerator(self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i64 {
+        self.denominator
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// `None` for division by zero.
+    pub fn div(self, other: Self) -> Option<Self> {
+        let (a, b) = (i128::from(self.numerator), i128::from(self.denominator));
+        let (c, d) = (i128::from(other.numerator), i128::from(other.denominator));
+        Self::from_wide(a * d, b * c)
+    }
+
+    /// `None` for a negative power of zero or too large numbers.
+    pub fn pow(self, exponent: i32) -> Option<Self> {
+        let base = if exponent < 0 {
+            Self::integer(1).div(self)?
+        } else {
+            self
+        };
+        // Powers of a reduced fraction are reduced too
+        Some(Self {
+            numerator: base.numerator.checked_pow(exponent.unsigned_abs())?,
+            denominator: base.denominator.checked_pow(exponent.unsigned_abs())?,
+        })
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator,
+        }
+    }
+
+    pub fn negate(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = i128::from(self.numerator) * i128::from(other.denominator);
+        let rhs = i128::from(other.numerator) * i128::from(self.denominator);
+        lhs.cmp(&rhs)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
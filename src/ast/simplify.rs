@@ -0,0 +1,809 @@
+use std::fmt;
+
+use super::canonical::{gcd, CanonicalTerm};
+use super::{Expr, NegativeExponents};
+
+/// Safety net against rule sets that would rewrite an expression back and forth forever.
+const MAX_STEPS: usize = 500;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rule {
+    FlattenAddition,
+    FlattenMultiplication,
+    FoldConstants,
+    RemoveZeroTerm,
+    CombineLikeTerms,
+    MultiplyByZero,
+    RemoveMultiplicationByOne,
+    MoveCoefficientToFront,
+    PullOutMinus,
+    DoubleNegation,
+    RemoveExponentOne,
+    ZeroExponent,
+    PowerOfPower,
+    PowerOfProduct,
+    PowerOfQuotient,
+    PowerOfNegative,
+    ProductOfPowers,
+    QuotientOfPowers,
+    CancelCoefficients,
+    DivisionByOne,
+    ZeroDividend,
+    Distribute,
+    MultiplyFraction,
+    DivideSum,
+    AddFractions,
+    ExtractFromRoot,
+    PowerOfRoot,
+}
+
+impl Rule {
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::FlattenAddition => "odstranění závorek u sčítání",
+            Rule::FlattenMultiplication => "odstranění závorek u násobení",
+            Rule::FoldConstants => "výpočet s čísly",
+            Rule::RemoveZeroTerm => "vynechání přičtené nuly",
+            Rule::CombineLikeTerms => "sečtení podobných členů",
+            Rule::MultiplyByZero => "násobení nulou",
+            Rule::RemoveMultiplicationByOne => "vynechání násobení jedničkou",
+            Rule::MoveCoefficientToFront => "přesun čísla na začátek součinu",
+            Rule::PullOutMinus => "vytknutí mínus",
+            Rule::DoubleNegation => "-(-a) = a",
+            Rule::RemoveExponentOne => "a^1 = a",
+            Rule::ZeroExponent => "a^0 = 1",
+            Rule::PowerOfPower => "(a^m)^n = a^(m·n)",
+            Rule::PowerOfProduct => "(a·b)^n = a^n·b^n",
+            Rule::PowerOfQuotient => "(a/b)^n = a^n/b^n",
+            Rule::PowerOfNegative => "(-a)^n = ±a^n",
+            Rule::ProductOfPowers => "a^m·a^n = a^(m+n)",
+            Rule::QuotientOfPowers => "a^m/a^n = a^(m-n)",
+            Rule::CancelCoefficients => "krácení čísel",
+            Rule::DivisionByOne => "a/1 = a",
+            Rule::ZeroDividend => "0/a = 0",
+            Rule::Distribute => "roznásobení závorky",
+            Rule::MultiplyFraction => "a·(b/c) = (a·b)/c",
+            Rule::DivideSum => "(a + b)/c = a/c + b/c",
+            Rule::AddFractions => "sčítání zlomků",
+            Rule::ExtractFromRoot => "částečné odmocnění, proměnné jsou nezáporné",
+            Rule::PowerOfRoot => "(√a)^2 = a",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One rewrite of the whole expression, `result` is the expression after the rule was applied.
+#[derive(Clone)]
+pub struct Step {
+    pub rule: Rule,
+    pub result: Expr,
+}
+
+pub struct Simplification {
+    pub original: Expr,
+    pub steps: Vec<Step>,
+}
+
+impl Simplification {
+    pub fn result(&self) -> &Expr {
+        self.steps
+            .last()
+            .map(|step| &step.result)
+            .unwrap_or(&self.original)
+    }
+}
+
+/*
+    Prints the worked solution, e.g.:
+        (2·a^3)^2
+        = 2^2·(a^3)^2      [(a·b)^n = a^n·b^n]
+        = 4·(a^3)^2        [výpočet s čísly]
+        ...
+*/
+impl fmt::Display for Simplification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut last_printed = self.original.to_string();
+        write!(f, "{last_printed}")?;
+        for step in &self.steps {
+            // Some rewrites (like flattening nested products) look the same when printed
+            let printed = step.result.to_string();
+            if printed == last_printed {
+                continue;
+            }
+            write!(f, "\n= {printed}      [{}]", step.rule)?;
+            last_printed = printed;
+        }
+        Ok(())
+    }
+}
+
+impl Expr {
+    /// Builds a product, without wrapping a single factor into `Multiplication`.
+    pub fn product(mut factors: Vec<Expr>) -> Expr {
+        match factors.len() {
+            0 => Expr::Number(1),
+            1 => factors.pop().unwrap(),
+            _ => Expr::Multiplication(factors),
+        }
+    }
+
+    /// Builds a sum, without wrapping a single term into `Addition`.
+    pub fn sum(mut terms: Vec<Expr>) -> Expr {
+        match terms.len() {
+            0 => Expr::zero(),
+            1 => terms.pop().unwrap(),
+            _ => Expr::Addition(terms),
+        }
+    }
+
+    /// `Number` or a `UnaryMinus` directly wrapping a `Number`.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Expr::Number(num) => Some(*num as i64),
+            Expr::UnaryMinus(inner) => match **inner {
+                Expr::Number(num) => Some(-(num as i64)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Inverse of `as_integer`, `None` when the number does not fit into `Expr::Number`.
+    pub fn integer(value: i64) -> Option<Expr> {
+        let abs = u32::try_from(value.unsigned_abs()).ok()?;
+        Some(Expr::Number(abs).maybe_wrap_in_minus(value < 0))
+    }
+
+    pub fn simplify(&self) -> Simplification {
+        let mut steps = vec![];
+        let mut current = self.clone();
+        while steps.len() < MAX_STEPS {
+            let Some((rule, next)) = current.rewrite_once() else {
+                break;
+            };
+            steps.push(Step {
+                rule,
+                result: next.clone(),
+            });
+            current = next;
+        }
+
+        Simplification {
+            original: self.clone(),
+            steps,
+        }
+    }
+
+    /// Applies the first applicable rule, innermost expressions first.
+    fn rewrite_once(&self) -> Option<(Rule, Expr)> {
+        match self {
+            Expr::Addition(exprs) | Expr::Multiplication(exprs) => {
+                for (idx, expr) in exprs.iter().enumerate() {
+                    if let Some((rule, new_expr)) = expr.rewrite_once() {
+                        let mut new_exprs = exprs.clone();
+                        new_exprs[idx] = new_expr;
+                        let rebuilt = match self {
+                            Expr::Addition(_) => Expr::Addition(new_exprs),
+                            _ => Expr::Multiplication(new_exprs),
+                        };
+                        return Some((rule, rebuilt));
+                    }
+                }
+            }
+            Expr::Division { lhs, rhs } => {
+                if let Some((rule, new_lhs)) = lhs.rewrite_once() {
+                    return Some((
+                        rule,
+                        Expr::Division {
+                            lhs: new_lhs.into(),
+                            rhs: rhs.clone(),
+                        },
+                    ));
+                }
+                if let Some((rule, new_rhs)) = rhs.rewrite_once() {
+                    return Some((
+                        rule,
+                        Expr::Division {
+                            lhs: lhs.clone(),
+                            rhs: new_rhs.into(),
+                        },
+                    ));
+                }
+            }
+            Expr::Exp { base, exp } => {
+                if let Some((rule, new_base)) = base.rewrite_once() {
+                    return Some((
+                        rule,
+                        Expr::Exp {
+                            base: new_base.into(),
+                            exp: exp.clone(),
+                        },
+                    ));
+                }
+                if let Some((rule, new_exp)) = exp.rewrite_once() {
+                    return Some((
+                        rule,
+                        Expr::Exp {
+                            base: base.clone(),
+                            exp: new_exp.into(),
+                        },
+                    ));
+                }
+            }
+            Expr::UnaryMinus(inner) => {
+                if let Some((rule, new_inner)) = inner.rewrite_once() {
+                    return Some((rule, Expr::UnaryMinus(new_inner.into())));
+                }
+            }
+            Expr::Root { degree, radicand } => {
+                if let Some((rule, new_radicand)) = radicand.rewrite_once() {
+                    return Some((
+                        rule,
+                        Expr::Root {
+                            degree: *degree,
+                            radicand: new_radicand.into(),
+                        },
+                    ));
+                }
+            }
+            Expr::Number(_) | Expr::Variable { symbol: _ } => {}
+        }
+
+        self.rewrite_here()
+    }
+
+    fn rewrite_here(&self) -> Option<(Rule, Expr)> {
+        match self {
+            Expr::Addition(terms) => rewrite_addition(terms),
+            Expr::Multiplication(factors) => rewrite_multiplication(factors),
+            Expr::Division { lhs, rhs } => rewrite_division(lhs, rhs),
+            Expr::Exp { base, exp } => rewrite_exp(base, exp),
+            Expr::UnaryMinus(inner) => match &**inner {
+                Expr::UnaryMinus(inner_inner) => {
+                    Some((Rule::DoubleNegation, (**inner_inner).clone()))
+                }
+                Expr::Number(0) => Some((Rule::FoldConstants, Expr::zero())),
+                Expr::Addition(terms) => {
+                    let negated = terms.iter().map(negate).collect();
+                    Some((Rule::Distribute, Expr::Addition(negated)))
+                }
+                _ => None,
+            },
+            Expr::Root { degree, radicand } => rewrite_root(*degree, radicand),
+            Expr::Number(_) | Expr::Variable { symbol: _ } => None,
+        }
+    }
+
+    /// Splits `expr` into `base^exponent`, anything that is not a power is `expr^1`.
+    fn as_power(&self) -> (Expr, Expr) {
+        match self {
+            Expr::Exp { base, exp } => ((**base).clone(), (**exp).clone()),
+            expr => (expr.clone(), Expr::Number(1)),
+        }
+    }
+
+    /// Splits a term of a sum into its integer coefficient and the remaining factors.
+    fn split_coefficient(&self) -> (i64, Vec<Expr>) {
+        match self {
+            Expr::UnaryMinus(inner) => {
+                let (coefficient, factors) = inner.split_coefficient();
+                (-coefficient, factors)
+            }
+            Expr::Number(num) => (*num as i64, vec![]),
+            Expr::Multiplication(factors) => {
+                let number_idx = factors.iter().position(|f| f.as_integer().is_some());
+                match number_idx {
+                    Some(idx) => {
+                        let mut rest = factors.clone();
+                        let number = rest.remove(idx).as_integer().unwrap();
+                        (number, rest)
+                    }
+                    None => (1, factors.clone()),
+                }
+            }
+            expr => (1, vec![expr.clone()]),
+        }
+    }
+
+    /// Sum of the terms `coefficient·factors`, leaving out the zero ones.
+    pub fn linear_combination(terms: Vec<(i64, Vec<Expr>)>) -> Option<Expr> {
+        let mut new_terms = vec![];
+        for (coefficient, factors) in terms {
+            if coefficient != 0 {
+                new_terms.push(Expr::from_coefficient(coefficient, factors)?);
+            }
+        }
+        Some(Expr::sum(new_terms))
+    }
+
+    /// Term `coefficient·factors`, `None` when the coefficient does not fit into `Expr::Number`.
+    pub fn from_coefficient(coefficient: i64, factors: Vec<Expr>) -> Option<Expr> {
+        if coefficient == 0 {
+            return Some(Expr::zero());
+        }
+        if factors.is_empty() {
+            return Expr::integer(coefficient);
+        }
+
+        let abs = u32::try_from(coefficient.unsigned_abs()).ok()?;
+        let mut new_factors = vec![];
+        if abs != 1 {
+            new_factors.push(Expr::Number(abs));
+        }
+        new_factors.extend(factors);
+        Some(Expr::product(new_factors).maybe_wrap_in_minus(coefficient < 0))
+    }
+}
+
+fn negate(expr: &Expr) -> Expr {
+    match expr {
+        Expr::UnaryMinus(inner) => (**inner).clone(),
+        expr => Expr::UnaryMinus(expr.clone().into()),
+    }
+}
+
+/// Same factors, ignoring their order.
+fn same_factors(lhs: &[Expr], rhs: &[Expr]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    let mut used = vec![false; rhs.len()];
+    lhs.iter().all(|factor| {
+        let found = (0..rhs.len()).find(|&idx| !used[idx] && rhs[idx] == *factor);
+        if let Some(idx) = found {
+            used[idx] = true;
+        }
+        found.is_some()
+    })
+}
+
+fn rewrite_addition(terms: &[Expr]) -> Option<(Rule, Expr)> {
+    if terms.len() < 2 || terms.iter().any(|t| matches!(t, Expr::Addition(_))) {
+        let mut new_terms = vec![];
+        for term in terms {
+            match term {
+                Expr::Addition(inner) => new_terms.extend(inner.iter().cloned()),
+                term => new_terms.push(term.clone()),
+            }
+        }
+        return Some((Rule::FlattenAddition, Expr::sum(new_terms)));
+    }
+
+    if let Some(idx) = terms.iter().position(|t| *t == Expr::zero()) {
+        let mut new_terms = terms.to_vec();
+        new_terms.remove(idx);
+        return Some((Rule::RemoveZeroTerm, Expr::sum(new_terms)));
+    }
+
+    let number_idxs: Vec<usize> = (0..terms.len())
+        .filter(|&idx| terms[idx].as_integer().is_some())
+        .collect();
+    if number_idxs.len() >= 2 {
+        let total = number_idxs
+            .iter()
+            .try_fold(0i64, |acc, &idx| acc.checked_add(terms[idx].as_integer()?));
+        if let Some(folded) = total.and_then(Expr::integer) {
+            let mut new_terms = vec![];
+            for (idx, term) in terms.iter().enumerate() {
+                if idx == number_idxs[0] {
+                    new_terms.push(folded.clone());
+                } else if !number_idxs.contains(&idx) {
+                    new_terms.push(term.clone());
+                }
+            }
+            return Some((Rule::FoldConstants, Expr::sum(new_terms)));
+        }
+    }
+
+    let is_constant = |term: &Expr| term.variable_occurrences().is_empty();
+    let constant_idxs: Vec<usize> = (0..terms.len())
+        .filter(|&idx| is_constant(&terms[idx]))
+        .collect();
+    if constant_idxs.len() >= 2 {
+        let total = constant_idxs
+            .iter()
+            .try_fold(CanonicalTerm::from_expr(&Expr::zero())?, |acc, &idx| {
+                acc.add_like(&CanonicalTerm::from_expr(&terms[idx])?)
+            });
+        if let Some(total) = total {
+            let mut new_terms = vec![];
+            for (idx, term) in terms.iter().enumerate() {
+                if idx == constant_idxs[0] {
+                    new_terms.push(total.to_expr(NegativeExponents::AsFraction));
+                } else if !constant_idxs.contains(&idx) {
+                    new_terms.push(term.clone());
+                }
+            }
+            return Some((Rule::AddFractions, Expr::sum(new_terms)));
+        }
+    }
+
+    for i in 0..terms.len() {
+        let (coef_i, factors_i) = terms[i].split_coefficient();
+        if factors_i.is_empty() {
+            continue;
+        }
+        for j in (i + 1)..terms.len() {
+            let (coef_j, factors_j) = terms[j].split_coefficient();
+            if !same_factors(&factors_i, &factors_j) {
+                continue;
+            }
+            let Some(combined) = coef_i
+                .checked_add(coef_j)
+                .and_then(|coef| Expr::from_coefficient(coef, factors_i.clone()))
+            else {
+                continue;
+            };
+
+            let mut new_terms = terms.to_vec();
+            new_terms[i] = combined;
+            new_terms.remove(j);
+            return Some((Rule::CombineLikeTerms, Expr::sum(new_terms)));
+        }
+    }
+
+    None
+}
+
+fn rewrite_multiplication(factors: &[Expr]) -> Option<(Rule, Expr)> {
+    if factors.len() < 2 || factors.iter().any(|f| matches!(f, Expr::Multiplication(_))) {
+        let mut new_factors = vec![];
+        for factor in factors {
+            match factor {
+                Expr::Multiplication(inner) => new_factors.extend(inner.iter().cloned()),
+                factor => new_factors.push(factor.clone()),
+            }
+        }
+        return Some((Rule::FlattenMultiplication, Expr::product(new_factors)));
+    }
+
+    if factors.contains(&Expr::zero()) {
+        return Some((Rule::MultiplyByZero, Expr::zero()));
+    }
+
+    let number_idxs: Vec<usize> = (0..factors.len())
+        .filter(|&idx| factors[idx].as_integer().is_some())
+        .collect();
+    if number_idxs.len() >= 2 {
+        let total = number_idxs.iter().try_fold(1i64, |acc, &idx| {
+            acc.checked_mul(factors[idx].as_integer()?)
+        });
+        if let Some(folded) = total.and_then(Expr::integer) {
+            let mut new_factors = vec![folded];
+            new_factors.extend(
+                (0..factors.len())
+                    .filter(|idx| !number_idxs.contains(idx))
+                    .map(|idx| factors[idx].clone()),
+            );
+            return Some((Rule::FoldConstants, Expr::product(new_factors)));
+        }
+    }
+
+    if let Some(idx) = factors.iter().position(|f| *f == Expr::Number(1)) {
+        let mut new_factors = factors.to_vec();
+        new_factors.remove(idx);
+        return Some((Rule::RemoveMultiplicationByOne, Expr::product(new_factors)));
+    }
+
+    if let Some(idx) = factors
+        .iter()
+        .position(|f| matches!(f, Expr::UnaryMinus(_)))
+    {
+        let mut new_factors = factors.to_vec();
+        if let Expr::UnaryMinus(inner) = &factors[idx] {
+            new_factors[idx] = (**inner).clone();
+        }
+        let product = Expr::product(new_factors);
+        return Some((Rule::PullOutMinus, Expr::UnaryMinus(product.into())));
+    }
+
+    if let Some(idx) = factors.iter().position(|f| matches!(f, Expr::Number(_))) {
+        if idx != 0 {
+            let mut new_factors = factors.to_vec();
+            let number = new_factors.remove(idx);
+            new_factors.insert(0, number);
+            return Some((Rule::MoveCoefficientToFront, Expr::product(new_factors)));
+        }
+    }
+
+    for i in 0..factors.len() {
+        let (base_i, exp_i) = factors[i].as_power();
+        if let Expr::Number(_) = base_i {
+            continue;
+        }
+        for j in (i + 1)..factors.len() {
+            let (base_j, exp_j) = factors[j].as_power();
+            if base_i != base_j {
+                continue;
+            }
+
+            let mut new_factors = factors.to_vec();
+            new_factors[i] = Expr::Exp {
+                base: base_i.into(),
+                exp: Expr::Addition(vec![exp_i, exp_j]).into(),
+            };
+            new_factors.remove(j);
+            return Some((Rule::ProductOfPowers, Expr::product(new_factors)));
+        }
+    }
+
+    if let Some(idx) = factors.iter().position(|f| matches!(f, Expr::Addition(_))) {
+        if let Expr::Addition(terms) = &factors[idx] {
+            let new_terms = terms
+                .iter()
+                .map(|term| {
+                    let mut new_factors = factors.to_vec();
+                    new_factors[idx] = term.clone();
+                    Expr::Multiplication(new_factors)
+                })
+                .collect();
+            return Some((Rule::Distribute, Expr::Addition(new_terms)));
+        }
+    }
+
+    if let Some(idx) = factors
+        .iter()
+        .position(|f| matches!(f, Expr::Division { .. }))
+    {
+        if let Expr::Division { lhs, rhs } = &factors[idx] {
+            let mut new_factors = factors.to_vec();
+            new_factors[idx] = (**lhs).clone();
+            let division = Expr::Division {
+                lhs: Expr::Multiplication(new_factors).into(),
+                rhs: rhs.clone(),
+            };
+            return Some((Rule::MultiplyFraction, division));
+        }
+    }
+
+    None
+}
+
+fn factors_of(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Multiplication(factors) => factors.clone(),
+        expr => vec![expr.clone()],
+    }
+}
+
+fn rewrite_division(lhs: &Expr, rhs: &Expr) -> Option<(Rule, Expr)> {
+    // Division by zero is left as it is, no rule applies to it
+    if factors_of(rhs).contains(&Expr::zero()) {
+        return None;
+    }
+    if *rhs == Expr::Number(1) {
+        return Some((Rule::DivisionByOne, lhs.clone()));
+    }
+    if *lhs == Expr::zero() {
+        return Some((Rule::ZeroDividend, Expr::zero()));
+    }
+
+    if let Expr::UnaryMinus(inner) = lhs {
+        let division = Expr::Division {
+            lhs: inner.clone(),
+            rhs: rhs.clone().into(),
+        };
+        return Some((Rule::PullOutMinus, Expr::UnaryMinus(division.into())));
+    }
+    if let Expr::UnaryMinus(inner) = rhs {
+        let division = Expr::Division {
+            lhs: lhs.clone().into(),
+            rhs: inner.clone(),
+        };
+        return Some((Rule::PullOutMinus, Expr::UnaryMinus(division.into())));
+    }
+
+    let mut top = factors_of(lhs);
+    let mut bottom = factors_of(rhs);
+
+    let top_number = top.iter().position(|f| matches!(f, Expr::Number(_)));
+    let bottom_number = bottom.iter().position(|f| matches!(f, Expr::Number(_)));
+    if let (Some(top_idx), Some(bottom_idx)) = (top_number, bottom_number) {
+        if let (Expr::Number(top_num), Expr::Number(bottom_num)) =
+            (&top[top_idx], &bottom[bottom_idx])
+        {
+            let divisor = gcd(*top_num as u64, *bottom_num as u64) as u32;
+            if divisor > 1 {
+                top[top_idx] = Expr::Number(top_num / divisor);
+                bottom[bottom_idx] = Expr::Number(bottom_num / divisor);
+                let division = Expr::Division {
+                    lhs: Expr::product(top).into(),
+                    rhs: Expr::product(bottom).into(),
+                };
+                return Some((Rule::CancelCoefficients, division));
+            }
+        }
+    }
+
+    for i in 0..top.len() {
+        let (base_i, exp_i) = top[i].as_power();
+        if let Expr::Number(_) = base_i {
+            continue;
+        }
+        for j in 0..bottom.len() {
+            let (base_j, exp_j) = bottom[j].as_power();
+            if base_i != base_j {
+                continue;
+            }
+
+            top[i] = Expr::Exp {
+                base: base_i.into(),
+                exp: Expr::Addition(vec![exp_i, Expr::UnaryMinus(exp_j.into())]).into(),
+            };
+            bottom.remove(j);
+            let division = Expr::Division {
+                lhs: Expr::product(top).into(),
+                rhs: Expr::product(bottom).into(),
+            };
+            return Some((Rule::QuotientOfPowers, division));
+        }
+    }
+
+    if let Expr::Addition(terms) = lhs {
+        let new_terms = terms
+            .iter()
+            .map(|term| Expr::Division {
+                lhs: term.clone().into(),
+                rhs: rhs.clone().into(),
+            })
+            .collect();
+        return Some((Rule::DivideSum, Expr::Addition(new_terms)));
+    }
+
+    None
+}
+
+/// `√72 = 6·√2`, `√(a^3) = a·√a`, only for non-negative variables, see `CanonicalTerm::root`.
+fn rewrite_root(degree: u32, radicand: &Expr) -> Option<(Rule, Expr)> {
+    let (outside, inside) = CanonicalTerm::from_expr(radicand)?.root(degree)?;
+    let one = CanonicalTerm::from_expr(&Expr::Number(1))?;
+    let outside_expr = outside.to_expr(NegativeExponents::AsFraction);
+    if inside == one {
+        return Some((Rule::ExtractFromRoot, outside_expr));
+    }
+    if outside == one {
+        return None;
+    }
+    let root = Expr::Root {
+        degree,
+        radicand: inside.to_expr(NegativeExponents::AsFraction).into(),
+    };
+    Some((
+        Rule::ExtractFromRoot,
+        Expr::Multiplication(vec![outside_expr, root]),
+    ))
+}
+
+fn rewrite_exp(base: &Expr, exp: &Expr) -> Option<(Rule, Expr)> {
+    if *exp == Expr::Number(1) {
+        return Some((Rule::RemoveExponentOne, base.clone()));
+    }
+    // 0^0 is not defined, so it is left as it is
+    if *exp == Expr::zero() && *base != Expr::zero() {
+        return Some((Rule::ZeroExponent, Expr::Number(1)));
+    }
+
+    if let (Expr::Number(base_num), Expr::Number(exp_num)) = (base, exp) {
+        if let Some(result) = base_num.checked_pow(*exp_num) {
+            return Some((Rule::FoldConstants, Expr::Number(result)));
+        }
+    }
+
+    if let (Expr::Root { degree, radicand }, Some(exp)) = (base, exp.as_integer()) {
+        let degree = i64::from(*degree);
+        if exp > 0 && exp % degree == 0 {
+            let power = Expr::Exp {
+                base: radicand.clone(),
+                exp: Expr::integer(exp / degree)?.into(),
+            };
+            return Some((Rule::PowerOfRoot, power));
+        }
+    }
+
+    match base {
+        Expr::Exp {
+            base: inner_base,
+            exp: inner_exp,
+        } => Some((
+            Rule::PowerOfPower,
+            Expr::Exp {
+                base: inner_base.clone(),
+                exp: Expr::Multiplication(vec![(**inner_exp).clone(), exp.clone()]).into(),
+            },
+        )),
+        Expr::Multiplication(factors) => {
+            let new_factors = factors
+                .iter()
+                .map(|factor| Expr::Exp {
+                    base: factor.clone().into(),
+                    exp: exp.clone().into(),
+                })
+                .collect();
+            Some((Rule::PowerOfProduct, Expr::Multiplication(new_factors)))
+        }
+        Expr::Division { lhs, rhs } => Some((
+            Rule::PowerOfQuotient,
+            Expr::Division {
+                lhs: Expr::Exp {
+                    base: lhs.clone(),
+                    exp: exp.clone().into(),
+                }
+                .into(),
+                rhs: Expr::Exp {
+                    base: rhs.clone(),
+                    exp: exp.clone().into(),
+                }
+                .into(),
+            },
+        )),
+        Expr::UnaryMinus(inner) => {
+            let exp_num = exp.as_integer()?;
+            let power = Expr::Exp {
+                base: inner.clone(),
+                exp: exp.clone().into(),
+            };
+            Some((
+                Rule::PowerOfNegative,
+                power.maybe_wrap_in_minus(exp_num % 2 != 0),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simplify(input: &str) -> String {
+        Expr::parse(input).unwrap().simplify().result().to_string()
+    }
+
+    #[test]
+    fn expressions_are_simplified() {
+        assert_eq!(simplify("(2a^3)^2"), "4·a^6");
+        assert_eq!(simplify("a^5/a^2"), "a^3");
+        assert_eq!(simplify("2a + 3a - a"), "4·a");
+        assert_eq!(simplify("3·(a + 2)"), "3·a + 6");
+        assert_eq!(simplify("(6a^2b)/(3a)"), "2·a·b");
+        assert_eq!(simplify("x·0 + 5"), "5");
+        assert_eq!(simplify("-(-a)"), "a");
+        assert_eq!(simplify("(a^2)^0"), "1");
+    }
+
+    #[test]
+    fn division_by_zero_is_not_simplified() {
+        assert_eq!(simplify("0/0"), "0/0");
+        assert_eq!(simplify("5/0"), "5/0");
+        assert_eq!(simplify("(6a)/(0·b)"), "(6·a)/0");
+    }
+
+    #[test]
+    fn roots_are_simplified() {
+        assert_eq!(simplify("√(12a^2)"), "2·a·√3");
+        assert_eq!(simplify("(√3)^2"), "3");
+    }
+
+    #[test]
+    fn steps_name_their_rules() {
+        let simplification = Expr::parse("(2a^3)^2").unwrap().simplify();
+        let rules: Vec<Rule> = simplification.steps.iter().map(|step| step.rule).collect();
+        assert_eq!(
+            rules,
+            [
+                Rule::PowerOfProduct,
+                Rule::FoldConstants,
+                Rule::PowerOfPower,
+                Rule::FoldConstants
+            ]
+        );
+        assert!(simplification
+            .to_string()
+            .ends_with("= 4·a^6      [výpočet s čísly]"));
+    }
+}
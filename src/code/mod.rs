@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// Crockford's base 32, without the letters which look like digits.
+const ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// The settings are written only with letters, so that they are not mixed up with the version.
+const LETTERS: &[u8] = b"ABCDEFGHJKMNPQRSTVWXYZ";
+const SEED_LENGTH: u32 = 5;
+/// Seeds of the single excercises are below this, so that they fit into the code.
+pub const SEED_COUNT: u64 = 32u64.pow(SEED_LENGTH);
+
+/*
+    Code of a single excercise, e.g. `DS2-4K9QX`, which can be typed in to get
+    exactly the same excercise again:
+        `DS`     the kind of the excercise
+        `2`      version of its generator, codes from other versions are refused
+        (`C`)    settings, left out when they are all off
+        `4K9QX`  seed of the excercise
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExcerciseCode {
+    pub kind: String,
+    pub version: u32,
+    pub params: u64,
+    pub seed: u64,
+}
+
+fn encode(mut value: u64, alphabet: &[u8], min_length: u32) -> String {
+    let base = alphabet.len() as u64;
+    let mut digits = vec![];
+    while value > 0 || digits.len() < min_length as usize {
+        digits.push(alphabet[(value % base) as usize] as char);
+        value /= base;
+    }
+    digits.iter().rev().collect()
+}
+
+fn decode(input: &str, alphabet: &[u8]) -> Option<u64> {
+    input.chars().try_fold(0u64, |value, chr| {
+        // Typos which are read the same way
+        let chr = match chr.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            chr => chr,
+        };
+        let digit = alphabet.iter().position(|known| *known as char == chr)?;
+        value
+            .checked_mul(alphabet.len() as u64)?
+            .checked_add(digit as u64)
+    })
+}
+
+impl ExcerciseCode {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || format!("\"{input}\" není kód příkladu, ten vypadá třeba jako DS2-4K9QX.");
+        let (head, seed) = input.trim().split_once('-').ok_or_else(invalid)?;
+        if seed.is_empty() {
+            return Err(invalid());
+        }
+        let seed = decode(seed, ALPHABET)
+            .filter(|seed| *seed < SEED_COUNT)
+            .ok_or_else(invalid)?;
+        let kind: String = head.chars().take(2).collect();
+        if kind.len() != 2 || !kind.chars().all(|chr| chr.is_ascii_alphabetic()) {
+            return Err(invalid());
+        }
+        let rest = &head[2..];
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let version = rest[..digits].parse().map_err(|_| invalid())?;
+        let params = decode(&rest[digits..], LETTERS).ok_or_else(invalid)?;
+        Ok(Self {
+            kind: kind.to_ascii_uppercase(),
+            version,
+            params,
+            seed,
+        })
+    }
+}
+
+impl fmt::Display for ExcerciseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = if self.params == 0 {
+            String::new()
+        } else {
+            encode(self.params, LETTERS, 1)
+        };
+        write!(
+            f,
+            "{}{}{params}-{}",
+            self.kind,
+            self.version,
+            encode(self.seed, ALPHABET, SEED_LENGTH)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_is_read_back() {
+        let code = ExcerciseCode {
+            kind: "QE".to_string(),
+            version: 2,
+            params: 9,
+            seed: 123_456,
+        };
+        assert_eq!(code.to_string(), "QE2K-03RJ0");
+        assert_eq!(ExcerciseCode::parse("qe2k-o3rjo"), Ok(code));
+    }
+
+    #[test]
+    fn incomplete_codes_are_refused() {
+        for input in [
+            "DS2-",
+            "DS2",
+            "D2-4K9QX",
+            "DS-4K9QX",
+            "DS2-4K9QXU",
+            "DS2-ZZZZZZ",
+        ] {
+            assert!(ExcerciseCode::parse(input).is_err(), "{input}");
+        }
+    }
+}
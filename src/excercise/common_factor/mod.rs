@@ -0,0 +1,359 @@
+use colored::*;
+
+use crate::ast::canonical::{CanonicalFraction, CanonicalPolynomial, CanonicalTerm};
+use crate::ast::mistakes::diagnose;
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{generate_term, TermConfig};
+
+const MAX_ATTEMPTS: u32 = 3;
+const VARIABLE_SETS: [&[char]; 3] = [&['a', 'b'], &['x', 'y'], &['a', 'b', 'c']];
+
+/*
+ * Represents the excercise:
+ *      `polynomial` = `factor`·(`rest`), e.g.
+ *      6·a^3·b - 9·a^2·b^2 + 3·a·b = 3·a·b·(2·a^2 - 3·a·b + 1)
+ *
+ * `factor` is the greatest common monomial of the terms.
+ */
+pub struct CommonFactor {
+    polynomial: CanonicalPolynomial,
+    factor: CanonicalTerm,
+    rest: CanonicalPolynomial,
+}
+
+fn canonical(term: &Expr) -> CanonicalTerm {
+    CanonicalTerm::from_expr(term).expect("Generated term is a monomial")
+}
+
+/// `config` describes the terms in the parentheses, their variables are picked
+/// from one of `VARIABLE_SETS`, so that the terms share them.
+pub fn generate_common_factor(rng: &mut Random, config: &TermConfig) -> CommonFactor {
+    let vars = VARIABLE_SETS[rng.random_range(0..VARIABLE_SETS.len())];
+    let term_config = TermConfig {
+        variables: vars.to_vec(),
+        max_vars: vars.len() as u32,
+        ..config.clone()
+    };
+    let factor_config = TermConfig {
+        coefficients: 2..=6,
+        exponents: 1..=3,
+        negative_coefficients: false,
+        fractional_coefficients: false,
+        ..term_config.clone()
+    };
+
+    let factor = loop {
+        let factor = canonical(&generate_term(rng, &factor_config));
+        if factor.degree() > 0 {
+            break factor;
+        }
+    };
+
+    // Nothing more can be factored out of the rest: the coefficients are coprime
+    // and every variable is missing from at least one of the terms
+    let rest = loop {
+        let term_count = rng.random_range(2..=4);
+        let rest = (0..term_count).try_fold(CanonicalPolynomial::zero(), |acc, _| {
+            acc.add(&CanonicalPolynomial::from_term(canonical(&generate_term(
+                rng,
+                &term_config,
+            ))))
+        });
+        let Some(rest) = rest else {
+            continue;
+        };
+        let is_coprime = rest
+            .common_factor()
+            .and_then(|factor| factor.as_integer())
+            .is_some_and(|number| number.abs() == 1);
+        // Like terms could have been added together
+        if is_coprime && rest.terms().len() == term_count {
+            break rest;
+        }
+    };
+
+    let polynomial = rest
+        .mul(&CanonicalPolynomial::from_term(factor.clone()))
+        .expect("Generated numbers are small");
+    // The factor takes the sign of the first term
+    let factor = polynomial
+        .common_factor()
+        .expect("Coefficients are integers");
+    let rest = polynomial.div_term(&factor).expect("Factor is not zero");
+    CommonFactor {
+        polynomial,
+        factor,
+        rest,
+    }
+}
+
+enum Verdict {
+    Correct,
+    /// The factor is right, but the parentheses still contain like terms and so on.
+    NotSimplified,
+    /// This can be still factored out of the parentheses.
+    Incomplete(CanonicalTerm),
+    /// Fractions or negative exponents appeared in the parentheses.
+    TooMuch,
+    NotProduct,
+    Wrong,
+}
+
+/// `-3·a·b·(…)` is `(-3·a·b, [terms in the parentheses])`.
+fn split_product(expr: &Expr) -> Option<(CanonicalTerm, &[Expr])> {
+    match expr {
+        Expr::UnaryMinus(inner) => {
+            let (factor, terms) = split_product(inner)?;
+            Some((factor.negate(), terms))
+        }
+        Expr::Addition(terms) => Some((CanonicalTerm::from_expr(&Expr::Number(1))?, terms)),
+        Expr::Multiplication(factors) => {
+            let (sums, others): (Vec<&Expr>, Vec<&Expr>) = factors
+                .iter()
+                .partition(|factor| matches!(factor, Expr::Addition(_)));
+            let [Expr::Addition(terms)] = sums[..] else {
+                return None;
+            };
+            let others = Expr::product(others.into_iter().cloned().collect());
+            Some((CanonicalTerm::from_expr(&others)?, terms))
+        }
+        _ => None,
+    }
+}
+
+fn check_answer(answer: &Expr, excercise: &CommonFactor) -> Verdict {
+    let CommonFactor {
+        polynomial,
+        factor,
+        rest,
+    } = excercise;
+    let original = polynomial.to_expr(NegativeExponents::AsFraction);
+    let is_equal = CanonicalFraction::from_expr(answer)
+        .zip(CanonicalFraction::from_expr(&original))
+        .and_then(|(answer, original)| answer.is_equal(&original));
+    if is_equal != Some(true) {
+        return Verdict::Wrong;
+    }
+
+    let Some((found, terms)) = split_product(answer) else {
+        return Verdict::NotProduct;
+    };
+    if found == *factor || found == factor.clone().negate() {
+        return if terms.len() == rest.terms().len() {
+            Verdict::Correct
+        } else {
+            Verdict::NotSimplified
+        };
+    }
+    let Some(remaining) = factor.div(&found) else {
+        return Verdict::Wrong;
+    };
+    // Whatever is left in the parentheses has to be a polynomial with integer coefficients
+    match CanonicalPolynomial::from_term(remaining.clone()).common_factor() {
+        Some(_) => Verdict::Incomplete(remaining),
+        None => Verdict::TooMuch,
+    }
+}
+
+fn print_model_solution(io: &mut dyn Io, excercise: &CommonFactor) {
+    let CommonFactor {
+        polynomial,
+        factor,
+        rest,
+    } = excercise;
+    let coefficients: Vec<String> = polynomial
+        .terms()
+        .iter()
+        .map(|term| term.coefficient().to_string())
+        .collect();
+    writeln!(
+        io,
+        "Největší společný dělitel koeficientů {} je {}.",
+        coefficients.join(", "),
+        factor.coefficient().abs()
+    );
+    writeln!(
+        io,
+        "Každou proměnnou vytkneme s nejmenším exponentem, se kterým je ve všech členech."
+    );
+    writeln!(
+        io,
+        "{} = {}·({})",
+        polynomial.to_expr(NegativeExponents::AsFraction),
+        factor.to_expr(NegativeExponents::AsFraction),
+        rest.to_expr(NegativeExponents::AsFraction)
+    );
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: CommonFactor) -> Answer<bool> {
+    writeln!(
+        io,
+        "Vytkni z výrazu co nejvíc (největší společný dělitel všech členů):"
+    );
+    writeln!(
+        io,
+        "{}",
+        excercise.polynomial.to_expr(NegativeExponents::AsFraction)
+    );
+    writeln!(io);
+
+    let coefficients: Vec<String> = excercise
+        .polynomial
+        .terms()
+        .iter()
+        .map(|term| term.coefficient().abs().to_string())
+        .collect();
+    let factor = excercise.factor.to_expr(NegativeExponents::AsFraction);
+    io.hints().offer(vec![
+        format!(
+            "Jaký je největší společný dělitel čísel {}?",
+            coefficients.join(", ")
+        ),
+        "Každou proměnnou vytkni s nejmenším exponentem, se kterým je ve všech členech."
+            .to_string(),
+        format!("Vytkni {factor} a každý člen jím vyděl."),
+    ]);
+    // Mistakes are looked for in the solution, the answer is a product as well
+    let factored = Expr::Multiplication(vec![
+        factor,
+        excercise.rest.to_expr(NegativeExponents::AsFraction),
+    ]);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_expr(io, "Zadej výsledek ve tvaru součinu, např. 2a(3a - b)")?;
+        match check_answer(&answer, &excercise) {
+            Verdict::Correct => {
+                writeln!(io, "{}", "Správně!".green().bold());
+                return Ok(true);
+            }
+            Verdict::NotSimplified => {
+                writeln!(
+                    io,
+                    "{}",
+                    "Vytknuto je správně, ale v závorce sečti podobné členy.".yellow()
+                )
+            }
+            Verdict::Incomplete(remaining) => writeln!(
+                io,
+                "{}",
+                format!(
+                    "Rovná se to, ale ze závorky jde vytknout ještě {}.",
+                    remaining.to_expr(NegativeExponents::AsFraction)
+                )
+                .yellow()
+            ),
+            Verdict::TooMuch => writeln!(io,
+                "{}",
+                "Rovná se to, ale v závorce zůstaly zlomky nebo záporné exponenty. Vytknuto je moc."
+                    .yellow()
+            ),
+            Verdict::NotProduct => writeln!(
+                io,
+                "{}",
+                "Rovná se to, ale výsledek má být součin výrazu a jedné závorky.".yellow()
+            ),
+            Verdict::Wrong => {
+                writeln!(io, "{}", "Tohle se zadání nerovná.".red());
+                if let Some(mistake) = diagnose(&answer, &factored) {
+                    writeln!(io, "{}", mistake.explanation().yellow());
+                }
+            }
+        }
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    print_model_solution(io, &excercise);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    /// 6·a^3·b - 9·a^2·b^2 + 3·a·b = 3·a·b·(2·a^2 - 3·a·b + 1)
+    fn excercise() -> CommonFactor {
+        let polynomial = ["6a^3b", "-9a^2b^2", "3ab"]
+            .iter()
+            .try_fold(CanonicalPolynomial::zero(), |acc, term| {
+                acc.add(&CanonicalPolynomial::from_term(canonical(
+                    &Expr::parse(term).unwrap(),
+                )))
+            })
+            .unwrap();
+        let factor = polynomial.common_factor().unwrap();
+        let rest = polynomial.div_term(&factor).unwrap();
+        CommonFactor {
+            polynomial,
+            factor,
+            rest,
+        }
+    }
+
+    fn verdict(answer: &str) -> Verdict {
+        check_answer(&Expr::parse(answer).unwrap(), &excercise())
+    }
+
+    #[test]
+    fn answers_are_checked() {
+        assert!(matches!(verdict("3ab(2a^2 - 3ab + 1)"), Verdict::Correct));
+        assert!(matches!(verdict("-3ab(-2a^2 + 3ab - 1)"), Verdict::Correct));
+        assert!(matches!(
+            verdict("3ab(2a^2 - 3ab + a - a + 1)"),
+            Verdict::NotSimplified
+        ));
+        assert!(matches!(
+            verdict("3a(2a^2b - 3ab^2 + b)"),
+            Verdict::Incomplete(_)
+        ));
+        assert!(matches!(
+            verdict("6ab(a^2 - 3ab/2 + 1/2)"),
+            Verdict::TooMuch
+        ));
+        assert!(matches!(
+            verdict("(3ab(2a^2 - 3ab + 1))^1"),
+            Verdict::NotProduct
+        ));
+        assert!(matches!(verdict("3ab(2a^2 - 3ab)"), Verdict::Wrong));
+    }
+
+    #[test]
+    fn factored_answer_is_correct() {
+        let mut io = ScriptedIo::new(&["3ab(2a^2 - 3ab + 1)"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(true)));
+        assert!(io.output().contains("Správně!"));
+    }
+
+    #[test]
+    fn wrong_answers_end_with_the_model_solution() {
+        let mut io = ScriptedIo::new(&["3a(2a^2b - 3ab^2 + b)", "3ab(2a^2 - 3ab)", "3ab"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert!(output.contains("Rovná se to, ale ze závorky jde vytknout ještě b."));
+        assert_eq!(output.matches("Tohle se zadání nerovná.").count(), 2);
+        assert!(output.contains("Největší společný dělitel koeficientů 6, -9, 3 je 3."));
+        assert!(output.contains("= 3·a·b·(2·a^2 - 3·a·b + 1)"));
+        assert!(!output.contains("Správně!"));
+    }
+
+    #[test]
+    fn nothing_is_left_to_factor_out_of_the_rest() {
+        let config = TermConfig {
+            coefficients: 1..=9,
+            exponents: 1..=2,
+            negative_coefficients: true,
+            ..TermConfig::default()
+        };
+        for seed in 0..50 {
+            let excercise = generate_common_factor(&mut Random::from_seed(seed), &config);
+            let product = excercise
+                .rest
+                .mul(&CanonicalPolynomial::from_term(excercise.factor.clone()))
+                .unwrap();
+            assert_eq!(product, excercise.polynomial);
+            let remaining = excercise.rest.common_factor().unwrap();
+            assert_eq!(remaining.as_integer().map(i64::abs), Some(1));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use colored::*;
+
+use crate::input::*;
+use crate::random::Random;
+use crate::stats::SessionStats;
+
+const DIVISORS: [u64; 10] = [2, 3, 4, 5, 6, 8, 9, 10, 11, 25];
+
+/*
+ * Represents the excercise:
+ *      Is `number` divisible by `divisor`? e.g.
+ *      Is 7 416 divisible by 8?   =>   yes, 416 = 8·52
+ */
+pub struct Divisibility {
+    number: u64,
+    divisor: u64,
+}
+
+/// What the student has to compute to use the rule.
+enum Check {
+    /// The last `count` digits as a number.
+    LastDigits {
+        count: u32,
+    },
+    DigitSum,
+    /// Digits on the odd places minus digits on the even places, counted from the right.
+    AlternatingSum,
+}
+
+fn checks(divisor: u64) -> Vec<Check> {
+    match divisor {
+        2 | 5 | 10 => vec![Check::LastDigits { count: 1 }],
+        4 | 25 => vec![Check::LastDigits { count: 2 }],
+        8 => vec![Check::LastDigits { count: 3 }],
+        3 | 9 => vec![Check::DigitSum],
+        6 => vec![Check::LastDigits { count: 1 }, Check::DigitSum],
+        11 => vec![Check::AlternatingSum],
+        _ => unreachable!("Only the divisors from DIVISORS have a rule"),
+    }
+}
+
+fn rule(divisor: u64) -> &'static str {
+    match divisor {
+        2 => "Číslo je dělitelné 2, když je jeho poslední číslice sudá.",
+        3 => "Číslo je dělitelné 3, když je jeho ciferný součet dělitelný 3.",
+        4 => "Číslo je dělitelné 4, když je jeho poslední dvojčíslí dělitelné 4.",
+        5 => "Číslo je dělitelné 5, když končí číslicí 0 nebo 5.",
+        6 => "Číslo je dělitelné 6, když je dělitelné 2 i 3.",
+        8 => "Číslo je dělitelné 8, když je jeho poslední trojčíslí dělitelné 8.",
+        9 => "Číslo je dělitelné 9, když je jeho ciferný součet dělitelný 9.",
+        10 => "Číslo je dělitelné 10, když končí číslicí 0.",
+        11 => "Číslo je dělitelné 11, když je rozdíl součtu číslic na lichých a na sudých místech dělitelný 11.",
+        25 => "Číslo je dělitelné 25, když končí dvojčíslím 00, 25, 50 nebo 75.",
+        _ => unreachable!("Only the divisors from DIVISORS have a rule"),
+    }
+}
+
+fn digits(number: u64) -> Vec<u64> {
+    number
+        .to_string()
+        .chars()
+        .map(|chr| u64::from(chr.to_digit(10).unwrap()))
+        .collect()
+}
+
+fn digit_sum(number: u64) -> u64 {
+    digits(number).iter().sum()
+}
+
+fn alternating_sum(number: u64) -> i64 {
+    digits(number)
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(idx, digit)| {
+            if idx % 2 == 0 {
+                *digit as i64
+            } else {
+                -(*digit as i64)
+            }
+        })
+        .sum()
+}
+
+/// Half of the numbers are divisible, the rest is not, but often close to it.
+pub fn generate_divisibility(rng: &mut Random) -> Divisibility {
+    let divisor = DIVISORS[rng.random_range(0..DIVISORS.len())];
+    let digit_count = rng.random_range(3..=6);
+    let max = 10u64.pow(digit_count) - 1;
+    let multiple =
+        divisor * rng.random_range(10u64.pow(digit_count - 1) / divisor + 1..=max / divisor);
+    let number = if rng.random_bool(0.5) {
+        multiple
+    } else {
+        let shift = rng.random_range(1..divisor);
+        if multiple + shift <= max {
+            multiple + shift
+        } else {
+            multiple - shift
+        }
+    };
+    Divisibility { number, divisor }
+}
+
+/// Asks for the number the rule works with, `false` when the student got it wrong.
+fn ask_check(io: &mut dyn Io, check: &Check, number: u64) -> Answer<bool> {
+    io.hints().offer(check_hints(check, number));
+    match check {
+        Check::LastDigits { count } => {
+            let expected = number % 10u64.pow(*count);
+            let question = match count {
+                1 => "Jaká je poslední číslice?".to_string(),
+                2 => "Jaké je poslední dvojčíslí?".to_string(),
+                _ => "Jaké je poslední trojčíslí?".to_string(),
+            };
+            let answer: u64 = get_number(io, &question)?;
+            Ok(answer == expected)
+        }
+        Check::DigitSum => {
+            let answer: u64 = get_number(io, "Jaký je ciferný součet?")?;
+            Ok(answer == digit_sum(number))
+        }
+        Check::AlternatingSum => {
+            let answer: i64 = get_number(io, "Kolik je součet číslic na lichých místech (zprava) minus součet na sudých místech?",
+            )?;
+            Ok(answer == alternating_sum(number))
+        }
+    }
+}
+
+fn check_hints(check: &Check, number: u64) -> Vec<String> {
+    let joined = |digits: Vec<u64>, separator: &str| {
+        digits
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+    match check {
+        Check::LastDigits { count } => vec![format!(
+            "Stačí opsat posledních {count} číslic čísla {number}."
+        )],
+        Check::DigitSum => vec![
+            "Sečti všechny číslice čísla.".to_string(),
+            format!("{} = ?", joined(digits(number), " + ")),
+        ],
+        Check::AlternatingSum => {
+            let (odd, even): (Vec<_>, Vec<_>) = digits(number)
+                .into_iter()
+                .rev()
+                .enumerate()
+                .partition(|(idx, _)| idx % 2 == 0);
+            let strip =
+                |place: Vec<(usize, u64)>| place.into_iter().map(|(_, digit)| digit).collect();
+            vec![
+                "Místa počítej zprava, poslední číslice je na prvním (lichém) místě.".to_string(),
+                format!(
+                    "({}) - ({}) = ?",
+                    joined(strip(odd), " + "),
+                    joined(strip(even), " + ")
+                ),
+            ]
+        }
+    }
+}
+
+fn print_check(io: &mut dyn Io, check: &Check, number: u64) {
+    match check {
+        Check::LastDigits { count } => {
+            let digits = (number % 10u64.pow(*count)).to_string();
+            let padding = "0".repeat(*count as usize - digits.len().min(*count as usize));
+            writeln!(io, "Konec čísla: {padding}{digits}");
+        }
+        Check::DigitSum => {
+            let sum = digits(number)
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(" + ");
+            writeln!(io, "Ciferný součet: {sum} = {}", digit_sum(number));
+        }
+        Check::AlternatingSum => {
+            writeln!(
+                io,
+                "Střídavý součet číslic zprava: {}",
+                alternating_sum(number)
+            );
+        }
+    }
+}
+
+pub fn solve_excercise(
+    io: &mut dyn Io,
+    excercise: Divisibility,
+    stats: &mut SessionStats,
+) -> Answer<bool> {
+    let Divisibility { number, divisor } = excercise;
+    let is_divisible = number.is_multiple_of(divisor);
+    let rule_name = format!("dělitelnost {divisor}");
+    writeln!(io, "Je číslo {number} dělitelné {divisor}?");
+    writeln!(io, "Zdůvodni to podle pravidla dělitelnosti.");
+    writeln!(io);
+
+    let mut is_correct = true;
+    for check in checks(divisor) {
+        if !ask_check(io, &check, number)? {
+            writeln!(io, "{}", "Špatně.".red());
+            print_check(io, &check, number);
+            is_correct = false;
+        }
+    }
+
+    io.hints().offer(vec![rule(divisor).to_string()]);
+    let answer = get_yes_no(io, &format!("Je {number} dělitelné {divisor}?"))?;
+    if answer != is_divisible {
+        writeln!(io, "{}", "Špatně.".red());
+        is_correct = false;
+    }
+
+    if is_correct {
+        writeln!(io, "{}", "Správně!".green().bold());
+    } else {
+        stats.record_wrong_rule(&rule_name);
+        writeln!(io, "{}", rule(divisor));
+        if is_divisible {
+            writeln!(io, "{number} = {divisor}·{}", number / divisor);
+        } else {
+            writeln!(
+                io,
+                "{number} = {divisor}·{} + {}",
+                number / divisor,
+                number % divisor
+            );
+        }
+    }
+    Ok(is_correct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    fn summary(stats: &SessionStats) -> String {
+        let mut io = ScriptedIo::new(&[]);
+        stats.print_summary(&mut io);
+        io.output().to_string()
+    }
+
+    #[test]
+    fn digits_are_summed() {
+        assert_eq!(digit_sum(7416), 18);
+        assert_eq!(digit_sum(0), 0);
+        assert_eq!(alternating_sum(7416), 2);
+        assert_eq!(alternating_sum(918082), -22);
+        assert_eq!(alternating_sum(5), 5);
+    }
+
+    #[test]
+    fn every_divisor_has_a_rule() {
+        for divisor in DIVISORS {
+            assert!(!checks(divisor).is_empty());
+            assert!(rule(divisor).contains(&format!("dělitelné {divisor},")));
+        }
+        assert!(matches!(
+            checks(6)[..],
+            [Check::LastDigits { count: 1 }, Check::DigitSum]
+        ));
+        assert!(matches!(checks(8)[..], [Check::LastDigits { count: 3 }]));
+        assert!(matches!(checks(11)[..], [Check::AlternatingSum]));
+    }
+
+    #[test]
+    fn generated_numbers_have_three_to_six_digits() {
+        for seed in 0..100 {
+            let Divisibility { number, divisor } =
+                generate_divisibility(&mut Random::from_seed(seed));
+            assert!((100..=999_999).contains(&number));
+            assert!(DIVISORS.contains(&divisor));
+        }
+    }
+
+    #[test]
+    fn rule_used_correctly() {
+        let mut io = ScriptedIo::new(&["416", "a"]);
+        let mut stats = SessionStats::default();
+        let excercise = Divisibility {
+            number: 7416,
+            divisor: 8,
+        };
+        assert!(matches!(
+            solve_excercise(&mut io, excercise, &mut stats),
+            Ok(true)
+        ));
+        assert!(io.output().contains("Správně!"));
+        assert!(summary(&stats).contains("Zatím žádné chyby."));
+    }
+
+    #[test]
+    fn wrong_digit_sum_is_recorded() {
+        let mut io = ScriptedIo::new(&["6", "17", "a"]);
+        let mut stats = SessionStats::default();
+        let excercise = Divisibility {
+            number: 7416,
+            divisor: 6,
+        };
+        assert!(matches!(
+            solve_excercise(&mut io, excercise, &mut stats),
+            Ok(false)
+        ));
+        let output = io.output();
+        assert_eq!(output.matches("Špatně.").count(), 1);
+        assert!(output.contains("Ciferný součet: 7 + 4 + 1 + 6 = 18"));
+        assert!(output.contains("7416 = 6·1236"));
+        assert!(summary(&stats).contains("dělitelnost 6: 1×"));
+    }
+}
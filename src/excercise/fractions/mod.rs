@@ -0,0 +1,382 @@
+use colored::*;
+use std::fmt;
+
+use crate::ast::canonical::{gcd, lcm};
+use crate::ast::rational::Rational;
+use crate::input::*;
+use crate::random::Random;
+use crate::random_range_filter;
+
+#[derive(Clone, Copy)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    fn symbol(self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "·",
+            Operation::Divide => ":",
+        }
+    }
+}
+
+// The fractions of the excercise are never negative, their parts are counted in `u32`
+// like the answers of the student.
+fn new_fraction(top: u32, bottom: u32) -> Option<Rational> {
+    Rational::new(i64::from(top), i64::from(bottom))
+}
+
+fn top_of(fraction: Rational) -> u32 {
+    u32::try_from(fraction.numerator()).expect("Fractions of the excercise are not negative")
+}
+
+fn bottom_of(fraction: Rational) -> u32 {
+    u32::try_from(fraction.denominator()).expect("Denominators are positive")
+}
+
+/// Number like `2 1/3`, `fraction` is always less than one.
+#[derive(Clone, Copy)]
+pub struct MixedNumber {
+    whole: u32,
+    fraction: Rational,
+}
+
+impl MixedNumber {
+    fn to_fraction(self) -> Rational {
+        new_fraction(
+            self.whole * bottom_of(self.fraction) + top_of(self.fraction),
+            bottom_of(self.fraction),
+        )
+        .unwrap()
+    }
+
+    fn from_fraction(fraction: Rational) -> Self {
+        let whole = top_of(fraction) / bottom_of(fraction);
+        let rest = new_fraction(top_of(fraction) % bottom_of(fraction), bottom_of(fraction));
+        Self {
+            whole,
+            fraction: rest.unwrap(),
+        }
+    }
+}
+
+impl fmt::Display for MixedNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.whole, self.fraction.is_zero()) {
+            (0, _) => write!(f, "{}", self.fraction),
+            (whole, true) => write!(f, "{whole}"),
+            (whole, false) => write!(f, "{whole} {}", self.fraction),
+        }
+    }
+}
+
+/*
+ * Represents the excercise:
+ *      `lhs` `operation` `rhs` = ?
+ */
+pub struct FractionArithmetic {
+    lhs: MixedNumber,
+    rhs: MixedNumber,
+    operation: Operation,
+}
+
+fn generate_mixed_number(rng: &mut Random, mixed_chance: f64) -> MixedNumber {
+    let bottom = rng.random_range(2..=12);
+    let top = random_range_filter(rng, 1..bottom, |top| gcd(*top as u64, bottom as u64) == 1);
+    let whole = if rng.random_bool(mixed_chance) {
+        rng.random_range(1..=5)
+    } else {
+        0
+    };
+    MixedNumber {
+        whole,
+        fraction: new_fraction(top, bottom).unwrap(),
+    }
+}
+
+pub fn generate_fraction_arithmetic(rng: &mut Random, mixed_chance: f64) -> FractionArithmetic {
+    let operation = match rng.random_range(0..4) {
+        0 => Operation::Add,
+        1 => Operation::Subtract,
+        2 => Operation::Multiply,
+        _ => Operation::Divide,
+    };
+    let mut lhs = generate_mixed_number(rng, mixed_chance);
+    let mut rhs = generate_mixed_number(rng, mixed_chance);
+    // Negative results are not part of this excercise
+    if let Operation::Subtract = operation {
+        if lhs.to_fraction() < rhs.to_fraction() {
+            (lhs, rhs) = (rhs, lhs);
+        }
+    }
+    FractionArithmetic {
+        lhs,
+        rhs,
+        operation,
+    }
+}
+
+/// Asks for a single number of a step, returns the correct one so the solution can continue.
+fn check_number_step(
+    io: &mut dyn Io,
+    question: &str,
+    correct: u32,
+    mistakes: &mut u32,
+) -> Answer<u32> {
+    let answer: u32 = get_number(io, question)?;
+    if answer == correct {
+        writeln!(io, "{}", "Správně!".green());
+    } else {
+        writeln!(io, "{} Správně je {correct}.", "Špatně.".red());
+        *mistakes += 1;
+    }
+    Ok(correct)
+}
+
+fn convert_to_fraction(
+    io: &mut dyn Io,
+    number: MixedNumber,
+    mistakes: &mut u32,
+) -> Answer<Rational> {
+    let fraction = number.to_fraction();
+    if number.whole != 0 {
+        let bottom = bottom_of(fraction);
+        io.hints().offer(vec![
+            format!(
+                "Jeden celek je {bottom}/{bottom}, kolik dílů je v {} celých?",
+                number.whole
+            ),
+            format!(
+                "{} · {bottom} = {}, k tomu přičti čitatele {}.",
+                number.whole,
+                number.whole * bottom,
+                top_of(number.fraction)
+            ),
+        ]);
+        let question =
+            format!("Převeď {number} na zlomek se jmenovatelem {bottom}, jaký bude čitatel?");
+        check_number_step(io, &question, top_of(fraction), mistakes)?;
+    }
+    Ok(fraction)
+}
+
+/// Common denominator, expanded numerators and their sum (or difference).
+fn add_or_subtract(
+    io: &mut dyn Io,
+    lhs: Rational,
+    rhs: Rational,
+    operation: Operation,
+    mistakes: &mut u32,
+) -> Answer<(u32, u32)> {
+    let least = lcm(bottom_of(lhs) as u64, bottom_of(rhs) as u64) as u32;
+    let larger = bottom_of(lhs).max(bottom_of(rhs));
+    io.hints().offer(vec![
+        format!(
+            "Hledáš číslo, které je dělitelné {} i {}.",
+            bottom_of(lhs),
+            bottom_of(rhs)
+        ),
+        format!(
+            "Zkoušej násobky většího jmenovatele: {larger}, {}, {}, …",
+            2 * larger,
+            3 * larger
+        ),
+        format!("Nejmenší společný jmenovatel je {least}."),
+    ]);
+    let denominator = get_input(
+        io,
+        "Najdi společný jmenovatel",
+        Some("číslo, nejlépe nejmenší společný násobek jmenovatelů"),
+        |input| {
+            let number = input
+                .parse::<u32>()
+                .map_err(|_| format!("\"{input}\" není číslo."))?;
+            // The product of the denominators is always common, larger ones only make big numerators
+            let product = bottom_of(lhs) * bottom_of(rhs);
+            if number == 0 || number % bottom_of(lhs) != 0 || number % bottom_of(rhs) != 0 {
+                Err(format!(
+                    "{number} není násobek {} i {}.",
+                    bottom_of(lhs),
+                    bottom_of(rhs)
+                ))
+            } else if number > product {
+                Err(format!(
+                    "{number} je zbytečně velký, stačí součin jmenovatelů {product}."
+                ))
+            } else {
+                Ok(number)
+            }
+        },
+    )?;
+    if denominator == least {
+        writeln!(io, "{}", "Správně!".green());
+    } else {
+        writeln!(io, "Jde to, ale nejmenší společný jmenovatel je {least}.");
+    }
+
+    let mut expand = |fraction: Rational| {
+        let times = denominator / bottom_of(fraction);
+        io.hints().offer(vec![
+            format!(
+                "Kolikrát se vejde {} do {denominator}? Tolikrát zvětši i čitatele.",
+                bottom_of(fraction)
+            ),
+            format!("{denominator} : {} = {times}", bottom_of(fraction)),
+        ]);
+        let question = format!("Rozšiř {fraction} na jmenovatel {denominator}, jaký bude čitatel?");
+        check_number_step(io, &question, top_of(fraction) * times, mistakes)
+    };
+    let lhs_top = expand(lhs)?;
+    let rhs_top = expand(rhs)?;
+
+    let (top, question) = match operation {
+        Operation::Add => (lhs_top + rhs_top, "Sečti čitatele"),
+        _ => (lhs_top - rhs_top, "Odečti čitatele"),
+    };
+    io.hints().offer(vec![
+        "Jmenovatel zůstává stejný, počítáš jen s čitateli.".to_string()
+    ]);
+    check_number_step(io, question, top, mistakes)?;
+    Ok((top, denominator))
+}
+
+fn multiply(
+    io: &mut dyn Io,
+    lhs: Rational,
+    rhs: Rational,
+    mistakes: &mut u32,
+) -> Answer<(u32, u32)> {
+    io.hints()
+        .offer(vec![format!("{} · {} = ?", top_of(lhs), top_of(rhs))]);
+    let top = check_number_step(io, "Vynásob čitatele", top_of(lhs) * top_of(rhs), mistakes)?;
+    io.hints()
+        .offer(vec![format!("{} · {} = ?", bottom_of(lhs), bottom_of(rhs))]);
+    let bottom = check_number_step(
+        io,
+        "Vynásob jmenovatele",
+        bottom_of(lhs) * bottom_of(rhs),
+        mistakes,
+    )?;
+    Ok((top, bottom))
+}
+
+fn reduce(io: &mut dyn Io, top: u32, bottom: u32, mistakes: &mut u32) -> Answer<()> {
+    let result = new_fraction(top, bottom).unwrap();
+    let divisor = gcd(top as u64, bottom as u64);
+    io.hints().offer(vec![
+        "Vyděl čitatele i jmenovatele jejich největším společným dělitelem.".to_string(),
+        format!("Největší společný dělitel {top} a {bottom} je {divisor}."),
+    ]);
+    let question = if (top_of(result), bottom_of(result)) == (top, bottom) {
+        format!("Dá se {top}/{bottom} ještě zkrátit? Zapiš ho v základním tvaru")
+    } else {
+        format!("Zkrať {top}/{bottom} na základní tvar")
+    };
+    let (answer_top, answer_bottom) = get_fraction(io, &question)?;
+    if (answer_top, answer_bottom) == (top_of(result), bottom_of(result)) {
+        writeln!(io, "{}", "Správně!".green());
+    } else if new_fraction(answer_top, answer_bottom) == Some(result) {
+        writeln!(io, "{} Jde to zkrátit až na {result}.", "Skoro.".yellow());
+        *mistakes += 1;
+    } else {
+        writeln!(io, "{} Správně je {result}.", "Špatně.".red());
+        *mistakes += 1;
+    }
+    Ok(())
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: FractionArithmetic) -> Answer<bool> {
+    let FractionArithmetic {
+        lhs,
+        rhs,
+        operation,
+    } = excercise;
+    writeln!(io, "Vypočítej:");
+    writeln!(io, "{lhs} {} {rhs}", operation.symbol());
+    writeln!(io);
+
+    let mut mistakes = 0;
+    let lhs = convert_to_fraction(io, lhs, &mut mistakes)?;
+    let rhs = convert_to_fraction(io, rhs, &mut mistakes)?;
+    let expected = match operation {
+        Operation::Add => lhs.add(rhs),
+        Operation::Subtract => lhs.sub(rhs),
+        Operation::Multiply => lhs.mul(rhs),
+        Operation::Divide => lhs.div(rhs),
+    }
+    .expect("Generated fractions are small and the result is not negative");
+
+    let (top, bottom) = match operation {
+        Operation::Add | Operation::Subtract => {
+            add_or_subtract(io, lhs, rhs, operation, &mut mistakes)
+        }
+        Operation::Multiply => multiply(io, lhs, rhs, &mut mistakes),
+        Operation::Divide => {
+            io.hints().offer(vec![
+                "Převrácená hodnota má prohozeného čitatele a jmenovatele.".to_string(),
+            ]);
+            let question = format!("Dělení {rhs} je násobení převrácenou hodnotou, jaká je?");
+            let (answer_top, answer_bottom) = get_fraction(io, &question)?;
+            let reciprocal = new_fraction(bottom_of(rhs), top_of(rhs)).unwrap();
+            if new_fraction(answer_top, answer_bottom) == Some(reciprocal) {
+                writeln!(io, "{}", "Správně!".green());
+            } else {
+                writeln!(io, "{} Správně je {reciprocal}.", "Špatně.".red());
+                mistakes += 1;
+            }
+            multiply(io, lhs, reciprocal, &mut mistakes)
+        }
+    }?;
+
+    reduce(io, top, bottom, &mut mistakes)?;
+    if top_of(expected) > bottom_of(expected) && bottom_of(expected) != 1 {
+        let mixed = MixedNumber::from_fraction(expected);
+        io.hints().offer(vec![format!(
+            "Kolikrát se vejde {} do {}?",
+            bottom_of(expected),
+            top_of(expected)
+        )]);
+        let question = format!("Kolik celých je v {expected}?");
+        check_number_step(io, &question, mixed.whole, &mut mistakes)?;
+        writeln!(io, "{expected} = {mixed}");
+    }
+
+    writeln!(io);
+    writeln!(io, "Výsledek: = {expected}");
+    Ok(mistakes == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    fn fraction(top: u32, bottom: u32) -> MixedNumber {
+        MixedNumber {
+            whole: 0,
+            fraction: new_fraction(top, bottom).unwrap(),
+        }
+    }
+
+    #[test]
+    fn common_denominator_is_not_too_large() {
+        let excercise = FractionArithmetic {
+            lhs: fraction(11, 12),
+            rhs: fraction(7, 12),
+            operation: Operation::Add,
+        };
+        let mut io = ScriptedIo::new(&["4294967292", "144", "132", "84", "216", "3/2", "1"]);
+        assert_eq!(solve_excercise(&mut io, excercise), Ok(true));
+        assert!(io.output().contains("4294967292 je zbytečně velký"));
+        assert!(io
+            .output()
+            .contains("Jde to, ale nejmenší společný jmenovatel je 12."));
+        assert!(io.output().contains("Výsledek: = 3/2"));
+    }
+}
@@ -0,0 +1,307 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, CanonicalTerm};
+use crate::ast::equation::{Equation, EquationStep, LinearSolution, Operation, Transformation};
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{random_range_filter, TermConfig};
+
+const MAX_MISTAKES: u32 = 3;
+const MAX_LINES: u32 = 20;
+
+/*
+ * Represents the excercise:
+ *      `equation`, solved for `var`, e.g.
+ *      3·(x - 2) = x + 5   =>   x = 11/2
+ *
+ * After multiplying out it is `a·var + b = c·var + d`.
+ */
+pub struct LinearEquation {
+    equation: Equation,
+    var: char,
+    solution: CanonicalTerm,
+    coefficients: [i64; 4],
+}
+
+/// `a·var + b`, without the zero terms.
+pub fn linear(a: i64, b: i64, var: char) -> Expr {
+    let var = Expr::Variable { symbol: var };
+    Expr::linear_combination(vec![(a, vec![var]), (b, vec![])])
+        .expect("Generated coefficients are small")
+}
+
+fn nonzero(value: &i64) -> bool {
+    *value != 0
+}
+
+/// The solution is `p/q`, `fractional_chance` is the chance of `q ≠ 1`.
+pub fn generate_linear_equation(
+    rng: &mut Random,
+    config: &TermConfig,
+    fractional_chance: f64,
+) -> LinearEquation {
+    let var = config.pick_variable(rng);
+    let (p, q) = if rng.random_bool(fractional_chance) {
+        let q = rng.random_range(2..=5i64);
+        let p = random_range_filter(rng, -12..=12i64, |p| {
+            *p != 0 && gcd(p.unsigned_abs(), q as u64) == 1
+        });
+        (p, q)
+    } else {
+        (rng.random_range(-10..=10), 1)
+    };
+
+    // a·x + b = c·x + d  has the solution  (d - b)/(a - c) = p/q
+    let k = random_range_filter(rng, -3..=3, nonzero);
+    let has_parentheses = rng.random_bool(0.5);
+    let (equation, [a, b, c, d]) = loop {
+        if has_parentheses {
+            // m·(n·x + o) = c·x + d
+            let m = random_range_filter(rng, -4..=4, |m: &i64| m.abs() >= 2);
+            let n = random_range_filter(rng, -4..=4, nonzero);
+            let o = config.pick_nonzero_coefficient(rng);
+            let (a, b) = (m * n, m * o);
+            let c = a - q * k;
+            let d = b + p * k;
+            let lhs = Expr::Multiplication(vec![Expr::integer(m).unwrap(), linear(n, o, var)]);
+            let equation = Equation {
+                lhs,
+                rhs: linear(c, d, var),
+            };
+            break (equation, [a, b, c, d]);
+        }
+
+        let c = config.pick_coefficient(rng);
+        let a = c + q * k;
+        let b = config.pick_coefficient(rng);
+        let d = b + p * k;
+        if a != 0 && (b != 0 || d != 0) {
+            let equation = Equation {
+                lhs: linear(a, b, var),
+                rhs: linear(c, d, var),
+            };
+            break (equation, [a, b, c, d]);
+        }
+    };
+
+    let Some(LinearSolution::Single(solution)) = equation.solve_linear(var) else {
+        panic!("Generated equation has exactly one solution");
+    };
+    LinearEquation {
+        equation,
+        var,
+        solution,
+        coefficients: [a, b, c, d],
+    }
+}
+
+/// `| -c·x` for a positive `c`, `| +c·x` for a negative one.
+fn remove_term(coefficient: i64, factors: Vec<Expr>) -> Transformation {
+    let operation = if coefficient > 0 {
+        Operation::Subtract
+    } else {
+        Operation::Add
+    };
+    let operand = Expr::from_coefficient(coefficient.abs(), factors);
+    Transformation {
+        operation,
+        operand: operand.expect("Generated coefficients are small"),
+    }
+}
+
+fn print_model_solution(io: &mut dyn Io, excercise: &LinearEquation) {
+    let LinearEquation {
+        equation,
+        var,
+        coefficients: [a, b, c, _],
+        ..
+    } = excercise;
+
+    writeln!(io, "{equation}");
+    let mut current = equation.simplify();
+    if current != *equation {
+        writeln!(io, "{current}      (roznásobení závorek)");
+    }
+
+    let mut transformations = vec![];
+    if *c != 0 {
+        transformations.push(remove_term(*c, vec![Expr::Variable { symbol: *var }]));
+    }
+    if *b != 0 {
+        transformations.push(remove_term(*b, vec![]));
+    }
+    if a - c != 1 {
+        transformations.push(Transformation {
+            operation: Operation::Divide,
+            operand: Expr::integer(a - c).unwrap(),
+        });
+    }
+    for transformation in transformations {
+        current = current.apply(&transformation);
+        writeln!(io, "{current}      {transformation}");
+    }
+}
+
+/// `x = 11/2` or `11/2 = x` with the value already simplified.
+fn is_final(equation: &Equation, var: char, solution: &CanonicalTerm) -> bool {
+    equation.as_solved().is_some_and(|(symbol, value)| {
+        symbol == var
+            && CanonicalTerm::from_expr(value).as_ref() == Some(solution)
+            && value.simplify().steps.is_empty()
+    })
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: LinearEquation) -> Answer<bool> {
+    let LinearEquation {
+        equation,
+        var,
+        solution,
+        ..
+    } = &excercise;
+    writeln!(io, "Vyřeš rovnici s neznámou {var}:");
+    writeln!(io, "{equation}");
+    writeln!(io);
+    writeln!(
+        io,
+        "Piš buď úpravy obou stran (např. | -3), nebo rovnou novou rovnici."
+    );
+
+    io.hints().offer(vec![
+        "Nejdřív roznásob závorky a sečti podobné členy.".to_string(),
+        format!("Převeď členy s {var} na jednu stranu a čísla na druhou."),
+        format!("Nakonec vyděl obě strany číslem, které je u {var}."),
+        format!(
+            "Výsledek je {var} = {}, zkus se k němu dopracovat.",
+            solution.to_expr(NegativeExponents::AsFraction)
+        ),
+    ]);
+    let mut current = equation.clone();
+    let mut mistakes = 0;
+    for _ in 0..MAX_LINES {
+        if mistakes >= MAX_MISTAKES {
+            break;
+        }
+
+        let next = match get_equation_step(io, "Další krok")? {
+            EquationStep::Transformation(transformation) => {
+                if let Some(problem) = transformation.problem() {
+                    writeln!(io, "{}", problem.red());
+                    mistakes += 1;
+                    continue;
+                }
+                let next = current.apply(&transformation);
+                writeln!(io, "{next}");
+                next
+            }
+            EquationStep::Equation(next) => match current.is_equivalent(&next) {
+                Some(true) => next,
+                Some(false) => {
+                    writeln!(
+                        io,
+                        "{}",
+                        "Tahle rovnice má jiné řešení než ta předchozí.".red()
+                    );
+                    mistakes += 1;
+                    continue;
+                }
+                None => {
+                    writeln!(io, "{}", "Tohle není lineární rovnice.".red());
+                    mistakes += 1;
+                    continue;
+                }
+            },
+        };
+
+        if is_final(&next, *var, solution) {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+        current = next;
+    }
+
+    writeln!(io);
+    writeln!(io, "Tohle je řešení:");
+    print_model_solution(io, &excercise);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    /// 3·(x - 2) = x + 5, multiplied out 3·x - 6 = x + 5.
+    fn excercise() -> LinearEquation {
+        let equation = Equation::parse("3(x - 2) = x + 5").unwrap();
+        let Some(LinearSolution::Single(solution)) = equation.solve_linear('x') else {
+            panic!("The equation has one solution");
+        };
+        LinearEquation {
+            equation,
+            var: 'x',
+            solution,
+            coefficients: [3, -6, 1, 5],
+        }
+    }
+
+    fn is_final_answer(answer: &str) -> bool {
+        let excercise = excercise();
+        is_final(
+            &Equation::parse(answer).unwrap(),
+            excercise.var,
+            &excercise.solution,
+        )
+    }
+
+    #[test]
+    fn solution_is_simplified_and_solved_for_the_variable() {
+        assert!(is_final_answer("x = 11/2"));
+        assert!(is_final_answer("11/2 = x"));
+        assert!(!is_final_answer("x = 22/4"));
+        assert!(!is_final_answer("2x = 11"));
+        assert!(!is_final_answer("x = 5"));
+    }
+
+    #[test]
+    fn steps_lead_to_the_solution() {
+        let mut io = ScriptedIo::new(&["3x - 6 = x + 5", "| -x", "| +6", "| :2"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(true)));
+        let output = io.output();
+        assert!(output.contains("2·x = 11"));
+        assert!(output.contains("x = 11/2"));
+        assert!(output.contains("Správně!"));
+    }
+
+    #[test]
+    fn mistakes_end_with_the_model_solution() {
+        let mut io = ScriptedIo::new(&["2x = 12", "1/x = 2", "| :0"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert!(output.contains("Tahle rovnice má jiné řešení než ta předchozí."));
+        assert!(output.contains("Tohle není lineární rovnice."));
+        assert!(output.contains("Tohle je řešení:"));
+        assert!(!output.contains("Správně!"));
+    }
+
+    #[test]
+    fn coefficients_describe_the_generated_equation() {
+        let config = TermConfig {
+            variables: vec!['x', 'y'],
+            ..TermConfig::default()
+        };
+        for seed in 0..50 {
+            let excercise = generate_linear_equation(&mut Random::from_seed(seed), &config, 0.5);
+            let [a, b, c, d] = excercise.coefficients;
+            let var = excercise.var;
+            let multiplied_out = Equation {
+                lhs: linear(a, b, var),
+                rhs: linear(c, d, var),
+            };
+            let Some(LinearSolution::Single(solution)) = multiplied_out.solve_linear(var) else {
+                panic!("The coefficients give one solution");
+            };
+            assert_eq!(solution, excercise.solution);
+        }
+    }
+}
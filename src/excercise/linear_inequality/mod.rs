@@ -0,0 +1,393 @@
+use colored::*;
+
+use crate::ast::canonical::gcd;
+use crate::ast::equation::{Operation, Transformation};
+use crate::ast::inequality::{Inequality, InequalityStep, Relation};
+use crate::ast::interval::IntervalUnion;
+use crate::ast::rational::Rational;
+use crate::ast::Expr;
+use crate::excercise::linear_equation::linear;
+use crate::input::*;
+use crate::random::Random;
+use crate::{random_range_filter, TermConfig};
+
+const MAX_MISTAKES: u32 = 3;
+const MAX_LINES: u32 = 20;
+const MAX_ATTEMPTS: u32 = 3;
+const RELATIONS: [Relation; 4] = [
+    Relation::Less,
+    Relation::LessOrEqual,
+    Relation::Greater,
+    Relation::GreaterOrEqual,
+];
+
+/*
+ * Represents the excercise:
+ *      `inequality`, solved for `var`, e.g.
+ *      3 - 2·x ≥ x - 9   =>   x ≤ 4   =>   K = (-∞; 4⟩
+ *
+ * After multiplying out it is `a·var + b relation c·var + d`.
+ */
+pub struct LinearInequality {
+    inequality: Inequality,
+    var: char,
+    solution: IntervalUnion,
+    coefficients: [i64; 4],
+}
+
+fn nonzero(value: &i64) -> bool {
+    *value != 0
+}
+
+/// The boundary is `p/q`, `fractional_chance` is the chance of `q ≠ 1`.
+/// Half of the inequalities end with dividing by a negative number.
+pub fn generate_linear_inequality(
+    rng: &mut Random,
+    config: &TermConfig,
+    fractional_chance: f64,
+) -> LinearInequality {
+    let var = config.pick_variable(rng);
+    let (p, q) = if rng.random_bool(fractional_chance) {
+        let q = rng.random_range(2..=5i64);
+        let p = random_range_filter(rng, -12..=12i64, |p| {
+            *p != 0 && gcd(p.unsigned_abs(), q as u64) == 1
+        });
+        (p, q)
+    } else {
+        (rng.random_range(-10..=10), 1)
+    };
+    let relation = RELATIONS[rng.random_range(0..RELATIONS.len())];
+
+    // a·x + b  relation  c·x + d  has the boundary  (d - b)/(a - c) = p/q
+    let k = random_range_filter(rng, 1..=3, nonzero) * if rng.random_bool(0.5) { -1 } else { 1 };
+    let has_parentheses = rng.random_bool(0.4);
+    let (lhs, rhs, [a, b, c, d]) = loop {
+        if has_parentheses {
+            // m·(n·x + o)  relation  c·x + d
+            let m = random_range_filter(rng, -4..=4, |m: &i64| m.abs() >= 2);
+            let n = random_range_filter(rng, -4..=4, nonzero);
+            let o = config.pick_nonzero_coefficient(rng);
+            let (a, b) = (m * n, m * o);
+            let c = a - q * k;
+            let d = b + p * k;
+            let lhs = Expr::Multiplication(vec![Expr::integer(m).unwrap(), linear(n, o, var)]);
+            break (lhs, linear(c, d, var), [a, b, c, d]);
+        }
+
+        let c = config.pick_coefficient(rng);
+        let a = c + q * k;
+        let b = config.pick_coefficient(rng);
+        let d = b + p * k;
+        if a != 0 && (b != 0 || d != 0) {
+            break (linear(a, b, var), linear(c, d, var), [a, b, c, d]);
+        }
+    };
+
+    let inequality = Inequality { lhs, relation, rhs };
+    let solution = inequality
+        .solve_linear(var)
+        .expect("Generated inequality is linear");
+    LinearInequality {
+        inequality,
+        var,
+        solution,
+        coefficients: [a, b, c, d],
+    }
+}
+
+/// `| -c·x` for a positive `c`, `| +c·x` for a negative one.
+fn remove_term(coefficient: i64, factors: Vec<Expr>) -> Transformation {
+    let operation = if coefficient > 0 {
+        Operation::Subtract
+    } else {
+        Operation::Add
+    };
+    let operand = Expr::from_coefficient(coefficient.abs(), factors);
+    Transformation {
+        operation,
+        operand: operand.expect("Generated coefficients are small"),
+    }
+}
+
+fn print_model_solution(io: &mut dyn Io, excercise: &LinearInequality) {
+    let LinearInequality {
+        inequality,
+        var,
+        solution,
+        coefficients: [a, b, c, _],
+    } = excercise;
+
+    writeln!(io, "{inequality}");
+    let mut current = inequality.simplify();
+    if current != *inequality {
+        writeln!(io, "{current}      (roznásobení závorek)");
+    }
+
+    let mut transformations = vec![];
+    if *c != 0 {
+        transformations.push(remove_term(*c, vec![Expr::Variable { symbol: *var }]));
+    }
+    if *b != 0 {
+        transformations.push(remove_term(*b, vec![]));
+    }
+    if a - c != 1 {
+        transformations.push(Transformation {
+            operation: Operation::Divide,
+            operand: Expr::integer(a - c).unwrap(),
+        });
+    }
+    for transformation in transformations {
+        current = current.apply(&transformation);
+        if Inequality::flips(&transformation) {
+            writeln!(
+                io,
+                "{current}      {transformation}   (záporné číslo otočí znaménko)"
+            );
+        } else {
+            writeln!(io, "{current}      {transformation}");
+        }
+    }
+    writeln!(io, "K = {solution}");
+}
+
+/// `x < 4` or `4 > x` with the value already simplified.
+fn is_final(inequality: &Inequality, var: char, solution: &IntervalUnion) -> bool {
+    inequality.as_solved().is_some_and(|(symbol, _, value)| {
+        symbol == var
+            && inequality.solve_linear(var).as_ref() == Some(solution)
+            && value.simplify().steps.is_empty()
+    })
+}
+
+/// Solves the inequality step by step, `false` when the student made too many mistakes.
+fn solve_steps(io: &mut dyn Io, excercise: &LinearInequality) -> Answer<bool> {
+    let LinearInequality {
+        inequality,
+        var,
+        solution,
+        ..
+    } = excercise;
+    io.hints().offer(vec![
+        "Upravuj stejně jako rovnici: roznásob závorky a sečti podobné členy.".to_string(),
+        format!("Převeď členy s {var} na jednu stranu a čísla na druhou."),
+        "Když násobíš nebo dělíš záporným číslem, otoč znaménko nerovnosti.".to_string(),
+    ]);
+    let mut current = inequality.clone();
+    let mut mistakes = 0;
+    for _ in 0..MAX_LINES {
+        if mistakes >= MAX_MISTAKES {
+            break;
+        }
+
+        let next = match get_inequality_step(io, "Další krok")? {
+            InequalityStep::Transformation(transformation) => {
+                if let Some(problem) = transformation.problem() {
+                    writeln!(io, "{}", problem.red());
+                    mistakes += 1;
+                    continue;
+                }
+                let next = current.apply(&transformation);
+                if Inequality::flips(&transformation) {
+                    writeln!(
+                        io,
+                        "{}",
+                        "Záporným číslem se znaménko nerovnosti otočí.".yellow()
+                    );
+                }
+                writeln!(io, "{next}");
+                next
+            }
+            InequalityStep::Inequality(next) => {
+                let expected = current.solve_linear(*var);
+                match next.solve_linear(*var) {
+                    None => {
+                        writeln!(io, "{}", "Tohle není lineární nerovnice.".red());
+                        mistakes += 1;
+                        continue;
+                    }
+                    found if found == expected => next,
+                    _ if next.with_flipped_relation().solve_linear(*var) == expected => {
+                        writeln!(io,
+                            "{}",
+                            "Nezapomeň, že násobení a dělení záporným číslem otočí znaménko nerovnosti."
+                                .red()
+                        );
+                        mistakes += 1;
+                        continue;
+                    }
+                    _ => {
+                        writeln!(
+                            io,
+                            "{}",
+                            "Tahle nerovnice má jiné řešení než ta předchozí.".red()
+                        );
+                        mistakes += 1;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if is_final(&next, *var, solution) {
+            return Ok(true);
+        }
+        current = next;
+    }
+    Ok(false)
+}
+
+/// Same end points as the solution, but a different bracket at one of them.
+fn has_wrong_brackets(answer: &IntervalUnion, solution: &IntervalUnion) -> bool {
+    let values = |union: &IntervalUnion| -> Vec<(Option<Rational>, Option<Rational>)> {
+        union
+            .intervals()
+            .iter()
+            .map(|interval| {
+                (
+                    interval.lower.map(|bound| bound.value),
+                    interval.upper.map(|bound| bound.value),
+                )
+            })
+            .collect()
+    };
+    values(answer) == values(solution)
+}
+
+fn solve_interval(io: &mut dyn Io, solution: &IntervalUnion) -> Answer<bool> {
+    io.hints().offer(vec![
+        "Pro < a > krajní bod do řešení nepatří, zapiš ho s kulatou závorkou.".to_string(),
+        "Pro ≤ a ≥ krajní bod do řešení patří, zapiš ho se špičatou závorkou ⟨ ⟩.".to_string(),
+        "Na straně nekonečna je vždy kulatá závorka.".to_string(),
+    ]);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_interval_union(io, "Zapiš řešení jako interval")?;
+        if answer == *solution {
+            return Ok(true);
+        }
+        if has_wrong_brackets(&answer, solution) {
+            writeln!(
+                io,
+                "{}",
+                "Krajní body sedí, ale pozor na závorky: patří krajní bod do řešení?".yellow()
+            );
+        } else {
+            writeln!(io, "{}", "Špatně.".red());
+        }
+    }
+    Ok(false)
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: LinearInequality) -> Answer<bool> {
+    let LinearInequality {
+        inequality,
+        var,
+        solution,
+        ..
+    } = &excercise;
+    writeln!(io, "Vyřeš nerovnici s neznámou {var}:");
+    writeln!(io, "{inequality}");
+    writeln!(io);
+    writeln!(
+        io,
+        "Piš buď úpravy obou stran (např. | -3), nebo rovnou novou nerovnici."
+    );
+    writeln!(
+        io,
+        "Až dojdeš k {var} < číslo (nebo >, ≤, ≥), zapíšeš řešení jako interval."
+    );
+
+    if solve_steps(io, &excercise)? {
+        writeln!(io, "{}", "Správně!".green().bold());
+        if solve_interval(io, solution)? {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+    }
+
+    writeln!(io);
+    writeln!(io, "Tohle je řešení:");
+    print_model_solution(io, &excercise);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    /// 3 - 2·x ≥ x - 9 with the solution x ≤ 4.
+    fn excercise() -> LinearInequality {
+        let inequality = Inequality::parse("3 - 2x >= x - 9").unwrap();
+        let solution = inequality.solve_linear('x').unwrap();
+        LinearInequality {
+            inequality,
+            var: 'x',
+            solution,
+            coefficients: [-2, 3, 1, -9],
+        }
+    }
+
+    fn is_final_answer(answer: &str) -> bool {
+        let excercise = excercise();
+        is_final(
+            &Inequality::parse(answer).unwrap(),
+            excercise.var,
+            &excercise.solution,
+        )
+    }
+
+    fn interval(input: &str) -> IntervalUnion {
+        IntervalUnion::parse(input).unwrap()
+    }
+
+    #[test]
+    fn solution_is_simplified_and_solved_for_the_variable() {
+        assert!(is_final_answer("x <= 4"));
+        assert!(is_final_answer("4 ≥ x"));
+        assert!(!is_final_answer("x <= 8/2"));
+        assert!(!is_final_answer("x >= 4"));
+        assert!(!is_final_answer("-3x >= -12"));
+    }
+
+    #[test]
+    fn brackets_are_wrong_only_with_the_same_end_points() {
+        let solution = excercise().solution;
+        assert_eq!(solution.to_string(), "(-∞; 4⟩");
+        assert!(has_wrong_brackets(&interval("(-∞; 4)"), &solution));
+        assert!(!has_wrong_brackets(&interval("(-∞; 3⟩"), &solution));
+        assert!(!has_wrong_brackets(&interval("⟨4; ∞)"), &solution));
+    }
+
+    #[test]
+    fn steps_and_interval_lead_to_the_solution() {
+        let mut io = ScriptedIo::new(&["| -x", "| -3", "| :(-3)", "(-inf; 4]"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(true)));
+        let output = io.output();
+        assert!(output.contains("Záporným číslem se znaménko nerovnosti otočí."));
+        assert!(output.contains("x ≤ 4"));
+        assert_eq!(output.matches("Správně!").count(), 2);
+    }
+
+    #[test]
+    fn wrong_steps_end_with_the_model_solution() {
+        let mut io = ScriptedIo::new(&["x >= 4", "1/x > 2", "x < 0"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert!(output.contains("Nezapomeň, že násobení a dělení záporným číslem"));
+        assert!(output.contains("Tohle není lineární nerovnice."));
+        assert!(output.contains("Tahle nerovnice má jiné řešení než ta předchozí."));
+        assert!(output.contains("(záporné číslo otočí znaménko)"));
+        assert!(output.contains("K = (-∞; 4⟩"));
+    }
+
+    #[test]
+    fn wrong_intervals_end_with_the_model_solution() {
+        let mut io = ScriptedIo::new(&["x <= 4", "(-∞; 4)", "⟨4; ∞)", "(-∞; 3⟩"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert_eq!(output.matches("Správně!").count(), 1);
+        assert_eq!(output.matches("Krajní body sedí").count(), 1);
+        assert_eq!(output.matches("Špatně.").count(), 2);
+        assert!(output.contains("Tohle je řešení:"));
+    }
+}
@@ -0,0 +1,416 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, CanonicalTerm};
+use crate::ast::equation::{Equation, EquationStep};
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::TermConfig;
+
+const MAX_MISTAKES: u32 = 3;
+const MAX_LINES: u32 = 30;
+
+/*
+ * Represents the excercise:
+ *      `rows` as a system of equations with the unknowns `vars`, e.g.
+ *      2·x + 3·y = 7
+ *      x - y = 1        =>   x = 2, y = 1
+ *
+ * Each row holds the coefficients of `vars` followed by the right side.
+ */
+pub struct LinearSystem {
+    rows: Vec<Vec<i64>>,
+    vars: Vec<char>,
+    solution: Vec<CanonicalTerm>,
+}
+
+fn determinant(matrix: &[Vec<i64>]) -> i64 {
+    if matrix.len() == 1 {
+        return matrix[0][0];
+    }
+    (0..matrix.len())
+        .map(|col| {
+            let minor: Vec<Vec<i64>> = matrix[1..]
+                .iter()
+                .map(|row| [&row[..col], &row[col + 1..matrix.len()]].concat())
+                .collect();
+            let sign = if col % 2 == 0 { 1 } else { -1 };
+            sign * matrix[0][col] * determinant(&minor)
+        })
+        .sum()
+}
+
+/// Cramer's rule, the solutions are `(numerator, denominator)`.
+fn solve_rows(rows: &[Vec<i64>]) -> Option<Vec<(i64, i64)>> {
+    let size = rows.len();
+    let matrix: Vec<Vec<i64>> = rows.iter().map(|row| row[..size].to_vec()).collect();
+    let det = determinant(&matrix);
+    if det == 0 {
+        return None;
+    }
+    let solution = (0..size)
+        .map(|col| {
+            let mut replaced = matrix.clone();
+            for (row, original) in replaced.iter_mut().zip(rows) {
+                row[col] = original[size];
+            }
+            (determinant(&replaced), det)
+        })
+        .collect();
+    Some(solution)
+}
+
+fn to_term((numerator, denominator): (i64, i64)) -> CanonicalTerm {
+    let fraction = Expr::Division {
+        lhs: Expr::integer(numerator).unwrap().into(),
+        rhs: Expr::integer(denominator).unwrap().into(),
+    };
+    CanonicalTerm::from_expr(&fraction).expect("Denominator is not zero")
+}
+
+/// `size` equations, the unknowns are the first `size` variables of `config` and
+/// the coefficients are picked by it. `fractional_chance` is the chance of a non-integer solution.
+pub fn generate_linear_system(
+    rng: &mut Random,
+    config: &TermConfig,
+    size: usize,
+    fractional_chance: f64,
+) -> LinearSystem {
+    let vars = config.variables[..size].to_vec();
+    let is_fractional = rng.random_bool(fractional_chance);
+    let coefficient = |rng: &mut Random| config.pick_coefficient(rng);
+    let rows = loop {
+        let mut rows: Vec<Vec<i64>> = (0..size)
+            .map(|_| (0..size).map(|_| coefficient(rng)).collect())
+            .collect();
+        let zeros = rows.iter().flatten().filter(|c| **c == 0).count();
+        if zeros > size - 2 {
+            continue;
+        }
+
+        if is_fractional {
+            for row in &mut rows {
+                row.push(rng.random_range(-12..=12));
+            }
+            let Some(solution) = solve_rows(&rows) else {
+                continue;
+            };
+            // Small denominators only, so the numbers stay nice
+            let is_nice = solution.iter().all(|(top, bottom)| {
+                let bottom = bottom / gcd(top.unsigned_abs(), bottom.unsigned_abs()) as i64;
+                bottom.abs() <= 6
+            });
+            let is_integer = solution.iter().all(|(top, bottom)| top % bottom == 0);
+            if is_nice && !is_integer {
+                break rows;
+            }
+        } else {
+            if determinant(&rows) == 0 {
+                continue;
+            }
+            let values: Vec<i64> = (0..size).map(|_| rng.random_range(-6..=6)).collect();
+            for row in &mut rows {
+                let rhs = row.iter().zip(&values).map(|(c, v)| c * v).sum();
+                row.push(rhs);
+            }
+            break rows;
+        }
+    };
+
+    let solution = solve_rows(&rows).expect("Determinant is not zero");
+    LinearSystem {
+        rows,
+        vars,
+        solution: solution.into_iter().map(to_term).collect(),
+    }
+}
+
+fn row_to_equation(row: &[i64], vars: &[char]) -> Equation {
+    let terms = row
+        .iter()
+        .zip(vars)
+        .map(|(c, var)| (*c, vec![Expr::Variable { symbol: *var }]))
+        .collect();
+    let lhs = Expr::linear_combination(terms).expect("Generated coefficients are small");
+    let rhs = Expr::integer(row[vars.len()]).expect("Generated coefficients are small");
+    Equation { lhs, rhs }
+}
+
+fn reduce_row(row: Vec<i64>) -> Vec<i64> {
+    let divisor = row.iter().fold(0, |acc, c| gcd(acc, c.unsigned_abs())) as i64;
+    if divisor <= 1 {
+        return row;
+    }
+    row.into_iter().map(|c| c / divisor).collect()
+}
+
+/// `3·(2) - 2·(1)`, the equation numbers in parentheses.
+fn combination_label(a: i64, lower: usize, b: i64, upper: usize) -> String {
+    let with_coefficient = |coefficient: i64, label: usize| match coefficient.abs() {
+        1 => format!("({label})"),
+        abs => format!("{abs}·({label})"),
+    };
+    let sign = if a < 0 { "-" } else { "" };
+    let operator = if b < 0 { "+" } else { "-" };
+    format!(
+        "{sign}{} {operator} {}",
+        with_coefficient(a, lower),
+        with_coefficient(b, upper)
+    )
+}
+
+/// Elimination without fractions, `a·(j) - b·(k)` removes the k-th unknown from the j-th equation.
+fn print_model_solution(io: &mut dyn Io, excercise: &LinearSystem) {
+    let LinearSystem {
+        rows,
+        vars,
+        solution,
+    } = excercise;
+
+    let mut rows = rows.clone();
+    let mut labels: Vec<usize> = (1..=rows.len()).collect();
+    let mut next_label = rows.len() + 1;
+    for (idx, row) in rows.iter().enumerate() {
+        writeln!(io, "({}) {}", idx + 1, row_to_equation(row, vars));
+    }
+
+    for col in 0..vars.len() {
+        let Some(pivot) = (col..rows.len()).find(|&idx| rows[idx][col] != 0) else {
+            continue;
+        };
+        rows.swap(col, pivot);
+        labels.swap(col, pivot);
+        for idx in col + 1..rows.len() {
+            let (mut a, mut b) = (rows[col][col], rows[idx][col]);
+            if b == 0 {
+                continue;
+            }
+            if a < 0 {
+                (a, b) = (-a, -b);
+            }
+            let new_row = rows[idx]
+                .iter()
+                .zip(&rows[col])
+                .map(|(lower, upper)| a * lower - b * upper)
+                .collect();
+            rows[idx] = reduce_row(new_row);
+            writeln!(
+                io,
+                "({next_label}) = {}:   {}",
+                combination_label(a, labels[idx], b, labels[col]),
+                row_to_equation(&rows[idx], vars)
+            );
+            labels[idx] = next_label;
+            next_label += 1;
+        }
+    }
+
+    writeln!(io, "Dosazením odspodu:");
+    for (var, value) in vars.iter().zip(solution).rev() {
+        writeln!(
+            io,
+            "{var} = {}",
+            value.to_expr(NegativeExponents::AsFraction)
+        );
+    }
+}
+
+/// Whether the student already wrote `x = 2` and so on for every unknown.
+fn is_solved(equations: &[Equation], excercise: &LinearSystem) -> bool {
+    let LinearSystem { vars, solution, .. } = excercise;
+    vars.iter().zip(solution).all(|(var, value)| {
+        equations.iter().any(|equation| {
+            equation.as_solved().is_some_and(|(symbol, found)| {
+                symbol == *var
+                    && CanonicalTerm::from_expr(found).as_ref() == Some(value)
+                    && found.simplify().steps.is_empty()
+            })
+        })
+    })
+}
+
+/*
+    Whether `next` is a combination of at most two of `equations`, the solved unknowns
+    like `y = 3` can be substituted on top of that. A result like `x = 2` has to come
+    from a single equation, so the elimination before it is written down and checked.
+*/
+fn follows_in_one_step(next: &Equation, equations: &[Equation]) -> Option<bool> {
+    let max_used = if next.as_solved().is_some() { 1 } else { 2 };
+    let (solved, others): (Vec<Equation>, Vec<Equation>) = equations
+        .iter()
+        .cloned()
+        .partition(|equation| equation.as_solved().is_some());
+    if next.follows_from(&solved)? {
+        return Some(true);
+    }
+    for first in 0..others.len() {
+        for second in first..others.len().min(first + max_used) {
+            let mut used = solved.clone();
+            used.push(others[first].clone());
+            used.push(others[second].clone());
+            if next.follows_from(&used)? {
+                return Some(true);
+            }
+        }
+    }
+    Some(false)
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: LinearSystem) -> Answer<bool> {
+    let LinearSystem {
+        rows,
+        vars,
+        solution,
+    } = &excercise;
+    let vars_list = vars
+        .iter()
+        .map(char::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    writeln!(io, "Vyřeš soustavu rovnic s neznámými {vars_list}:");
+    let mut equations: Vec<Equation> = rows.iter().map(|row| row_to_equation(row, vars)).collect();
+    for (idx, equation) in equations.iter().enumerate() {
+        writeln!(io, "({}) {equation}", idx + 1);
+    }
+    writeln!(io);
+    writeln!(
+        io,
+        "Dosazovací i sčítací metoda jsou povolené. Piš rovnice, které ze soustavy plynou,"
+    );
+    writeln!(io, "úpravy jako | :3 se použijí na poslední rovnici.");
+
+    let values: Vec<(char, Expr)> = vars
+        .iter()
+        .zip(solution)
+        .map(|(var, value)| (*var, value.to_expr(NegativeExponents::AsFraction)))
+        .collect();
+    let (first, last) = (vars[0], vars[vars.len() - 1]);
+    io.hints().offer(vec![
+        format!("Vynásob rovnice tak, aby u {first} byla ve dvou rovnicích opačná čísla."),
+        format!("Sečti je, {first} vypadne a zbyde rovnice s méně neznámými."),
+        format!("Až budeš znát {last}, dosaď ho zpátky do jedné z rovnic."),
+        format!("Vyjde {last} = {}.", values[values.len() - 1].1),
+    ]);
+    let mut mistakes = 0;
+    for _ in 0..MAX_LINES {
+        if mistakes >= MAX_MISTAKES {
+            break;
+        }
+
+        let next = match get_equation_step(io, "Další krok")? {
+            EquationStep::Transformation(transformation) => {
+                if let Some(problem) = transformation.problem() {
+                    writeln!(io, "{}", problem.red());
+                    mistakes += 1;
+                    continue;
+                }
+                let last = equations.last().expect("The system has equations");
+                last.apply(&transformation)
+            }
+            EquationStep::Equation(next) => {
+                let mut used_vars = next.lhs.variable_occurrences();
+                used_vars.extend(next.rhs.variable_occurrences());
+                if !used_vars.iter().all(|var| vars.contains(var)) {
+                    writeln!(io, "{}", "V rovnici smí být jen neznámé ze soustavy.".red());
+                    mistakes += 1;
+                    continue;
+                }
+                match follows_in_one_step(&next, &equations) {
+                    Some(true) => next,
+                    Some(false) => {
+                        // An equation true for the solution could be just guessed
+                        let message = match next.holds_for(&values) {
+                            Some(true) if next.as_solved().is_some() => {
+                                "Výsledek platí, ale napiš nejdřív rovnici, ze které vyjde."
+                            }
+                            Some(true) => {
+                                "Rovnice platí, ale v jednom kroku kombinuj nejvýš dvě rovnice."
+                            }
+                            _ => "Tahle rovnice ze soustavy neplyne.",
+                        };
+                        writeln!(io, "{}", message.red());
+                        mistakes += 1;
+                        continue;
+                    }
+                    None => {
+                        writeln!(
+                            io,
+                            "{}",
+                            "S touhle rovnicí neumím počítat, napiš ji jednodušeji.".red()
+                        );
+                        mistakes += 1;
+                        continue;
+                    }
+                }
+            }
+        };
+        writeln!(io, "({}) {next}", equations.len() + 1);
+        equations.push(next);
+
+        if is_solved(&equations, &excercise) {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+    }
+
+    writeln!(io);
+    writeln!(io, "Tohle je řešení:");
+    print_model_solution(io, &excercise);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follows(next: &str, equations: &[&str]) -> Option<bool> {
+        let equations: Vec<Equation> = equations
+            .iter()
+            .map(|equation| Equation::parse(equation).unwrap())
+            .collect();
+        follows_in_one_step(&Equation::parse(next).unwrap(), &equations)
+    }
+
+    #[test]
+    fn steps_combine_at_most_two_equations() {
+        let system = ["x + y + z = 6", "x - y = 0", "y - z = 0"];
+        assert_eq!(follows("x - z = 0", &system), Some(true));
+        assert_eq!(follows("2x + z = 6", &system), Some(true));
+        // True for the solution, but it needs all three equations at once
+        assert_eq!(follows("x = 2", &system), Some(false));
+        assert_eq!(
+            follows("x = 2", &[&system[..], &["y = 2", "z = 2"]].concat()),
+            Some(true)
+        );
+        assert_eq!(follows("x + y = 5", &system), Some(false));
+
+        let system = ["x - 3y = 19", "-2x - y = -3"];
+        assert_eq!(follows("x = 4", &system), Some(false));
+        assert_eq!(follows("-7y = 35", &system), Some(true));
+        assert_eq!(
+            follows("y = -5", &[&system[..], &["-7y = 35"]].concat()),
+            Some(true)
+        );
+        assert_eq!(
+            follows("x = 4", &[&system[..], &["y = -5"]].concat()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn size_comes_from_the_excercise() {
+        let mut rng = Random::from_seed(3);
+        let config = TermConfig {
+            coefficients: 0..=3,
+            negative_coefficients: true,
+            ..TermConfig::default()
+        };
+        for size in [2, 3] {
+            let system = generate_linear_system(&mut rng, &config, size, 0.5);
+            assert_eq!(system.rows.len(), size);
+            assert_eq!(system.vars, config.variables[..size]);
+            let coefficients = system.rows.iter().flat_map(|row| &row[..size]);
+            assert!(coefficients.clone().all(|c| (-3..=3).contains(c)));
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use colored::*;
+use std::ptr;
+
+use crate::ast::Expr;
+use crate::input::*;
+use crate::random::Random;
+
+const MAX_ATTEMPTS: u32 = 3;
+const MAX_MISTAKES: u32 = 3;
+/// Every intermediate result stays below this, so that it can be computed by heart.
+const MAX_VALUE: i64 = 1000;
+const ORDER: &str =
+    "Pořadí: závorky, mocniny, násobení a dělení, sčítání a odčítání, zleva doprava.";
+
+/*
+ * Represents the excercise:
+ *      `expr` = `result`, e.g.
+ *      2·(3 - 5)^2 - 12/(1 + 3) = 5
+ *
+ * Sums and products have just two operands, `a + b + c` is `(a + b) + c`,
+ * so every operation is one step of the evaluation.
+ */
+pub struct OrderOfOperations {
+    expr: Expr,
+    result: i64,
+}
+
+/// Numbers, including negative ones, are already evaluated.
+fn value(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Number(number) => Some(i64::from(*number)),
+        Expr::UnaryMinus(inner) => match **inner {
+            Expr::Number(number) => Some(-i64::from(number)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Result of an operation whose operands are numbers, `None` for anything else
+/// or when the result is not a reasonable whole number.
+fn evaluate_step(expr: &Expr) -> Option<i64> {
+    let result = match expr {
+        Expr::Addition(terms) => match &terms[..] {
+            [a, b] => value(a)?.checked_add(value(b)?)?,
+            _ => return None,
+        },
+        Expr::Multiplication(factors) => match &factors[..] {
+            [a, b] => value(a)?.checked_mul(value(b)?)?,
+            _ => return None,
+        },
+        Expr::Division { lhs, rhs } => {
+            let (lhs, rhs) = (value(lhs)?, value(rhs)?);
+            if rhs == 0 || lhs % rhs != 0 {
+                return None;
+            }
+            lhs / rhs
+        }
+        Expr::Exp { base, exp } => {
+            let exp = u32::try_from(value(exp)?).ok()?;
+            value(base)?.checked_pow(exp)?
+        }
+        // `-(-3)`
+        Expr::UnaryMinus(inner) if value(inner).is_some() => -value(inner)?,
+        _ => return None,
+    };
+    (result.abs() <= MAX_VALUE).then_some(result)
+}
+
+/// Operations that can be evaluated right now, in the order they are written.
+fn next_steps<'a>(expr: &'a Expr, found: &mut Vec<&'a Expr>) {
+    if value(expr).is_some() {
+        return;
+    }
+    if evaluate_step(expr).is_some() {
+        found.push(expr);
+        return;
+    }
+    match expr {
+        Expr::Addition(exprs) | Expr::Multiplication(exprs) => {
+            for expr in exprs {
+                next_steps(expr, found);
+            }
+        }
+        Expr::Division { lhs: a, rhs: b } | Expr::Exp { base: a, exp: b } => {
+            next_steps(a, found);
+            next_steps(b, found);
+        }
+        Expr::UnaryMinus(inner) => next_steps(inner, found),
+        Expr::Root { radicand, .. } => next_steps(radicand, found),
+        Expr::Number(_) | Expr::Variable { .. } => {}
+    }
+}
+
+/// `expr` with the occurrence `target` replaced by `replacement`.
+fn replace(expr: &Expr, target: &Expr, replacement: &Expr) -> Expr {
+    if ptr::eq(expr, target) {
+        return replacement.clone();
+    }
+    let replace = |expr: &Expr| replace(expr, target, replacement);
+    match expr {
+        Expr::Addition(exprs) => Expr::Addition(exprs.iter().map(replace).collect()),
+        Expr::Multiplication(exprs) => Expr::Multiplication(exprs.iter().map(replace).collect()),
+        Expr::Division { lhs, rhs } => Expr::Division {
+            lhs: replace(lhs).into(),
+            rhs: replace(rhs).into(),
+        },
+        Expr::Exp { base, exp } => Expr::Exp {
+            base: replace(base).into(),
+            exp: replace(exp).into(),
+        },
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(replace(inner).into()),
+        Expr::Root { degree, radicand } => Expr::Root {
+            degree: *degree,
+            radicand: replace(radicand).into(),
+        },
+        Expr::Number(_) | Expr::Variable { .. } => expr.clone(),
+    }
+}
+
+/// Splits sums and products into pairs from the left, `a + b + c` becomes `(a + b) + c`.
+fn to_binary(expr: &Expr) -> Expr {
+    let pairs = |exprs: &[Expr], build: fn(Vec<Expr>) -> Expr| {
+        let mut exprs = exprs.iter().map(to_binary);
+        let first = exprs.next().expect("Sums and products are not empty");
+        exprs.fold(first, |acc, expr| build(vec![acc, expr]))
+    };
+    match expr {
+        Expr::Addition(exprs) => pairs(exprs, Expr::Addition),
+        Expr::Multiplication(exprs) => pairs(exprs, Expr::Multiplication),
+        Expr::Division { lhs, rhs } => Expr::Division {
+            lhs: to_binary(lhs).into(),
+            rhs: to_binary(rhs).into(),
+        },
+        Expr::Exp { base, exp } => Expr::Exp {
+            base: to_binary(base).into(),
+            exp: to_binary(exp).into(),
+        },
+        Expr::UnaryMinus(inner) => Expr::UnaryMinus(to_binary(inner).into()),
+        Expr::Root { degree, radicand } => Expr::Root {
+            degree: *degree,
+            radicand: to_binary(radicand).into(),
+        },
+        Expr::Number(_) | Expr::Variable { .. } => expr.clone(),
+    }
+}
+
+/// Evaluates the first possible step until only a number is left, `None` when some step fails.
+fn evaluate(expr: &Expr) -> Option<i64> {
+    let mut current = expr.clone();
+    while value(&current).is_none() {
+        let mut steps = vec![];
+        next_steps(&current, &mut steps);
+        let step = *steps.first()?;
+        let result = Expr::integer(evaluate_step(step)?)?;
+        current = replace(&current, step, &result);
+    }
+    value(&current)
+}
+
+fn number(rng: &mut Random) -> Expr {
+    Expr::Number(rng.random_range(2..=12))
+}
+
+/// Random operation tree, `depth` is how many operations can be nested.
+fn generate_node(rng: &mut Random, depth: u32) -> Expr {
+    if depth == 0 {
+        return number(rng);
+    }
+    let operand = |rng: &mut Random| {
+        if rng.random_bool(0.6) {
+            generate_node(rng, depth - 1)
+        } else {
+            number(rng)
+        }
+    };
+    match rng.random_range(0..10) {
+        0..=2 => Expr::Addition(vec![operand(rng), operand(rng)]),
+        3..=4 => Expr::Addition(vec![operand(rng), Expr::UnaryMinus(operand(rng).into())]),
+        5..=6 => Expr::Multiplication(vec![operand(rng), operand(rng)]),
+        7 => {
+            // The divisor is chosen later, so that the division has no remainder
+            let lhs = operand(rng);
+            let divisors: Vec<i64> = evaluate(&lhs)
+                .map(|value| (2..=9).filter(|d| value % d == 0).collect())
+                .unwrap_or_default();
+            if divisors.is_empty() {
+                return Expr::Multiplication(vec![lhs, number(rng)]);
+            }
+            let divisor = divisors[rng.random_range(0..divisors.len())];
+            Expr::Division {
+                lhs: lhs.into(),
+                rhs: Expr::integer(divisor).unwrap().into(),
+            }
+        }
+        8 => Expr::Exp {
+            base: if rng.random_bool(0.5) {
+                Expr::Number(rng.random_range(2..=5))
+            } else {
+                Expr::Addition(vec![number(rng), Expr::UnaryMinus(number(rng).into())])
+            }
+            .into(),
+            exp: Expr::Number(rng.random_range(2..=3)).into(),
+        },
+        _ => Expr::UnaryMinus(generate_node(rng, depth - 1).into()),
+    }
+}
+
+pub fn generate_order_of_operations(rng: &mut Random) -> OrderOfOperations {
+    loop {
+        let generated = generate_node(rng, 3);
+        // The student sees the printed form, so the steps follow how it is read back
+        let Ok(parsed) = Expr::parse(&generated.to_string()) else {
+            continue;
+        };
+        let expr = to_binary(&parsed);
+        let printed = expr.to_string();
+        let operations = printed.matches(['+', '-', '·', '/', '^']).count();
+        // Not just a chain of additions, the order has to matter
+        let has_priorities = printed.contains(['(', '^']) && printed.contains(['·', '/']);
+        if !(4..=8).contains(&operations) || !has_priorities {
+            continue;
+        }
+        if let Some(result) = evaluate(&expr) {
+            return OrderOfOperations { expr, result };
+        }
+    }
+}
+
+fn print_model_solution(io: &mut dyn Io, expr: &Expr) {
+    let mut current = expr.clone();
+    while value(&current).is_none() {
+        let mut steps = vec![];
+        next_steps(&current, &mut steps);
+        let step = steps[0];
+        let result = Expr::integer(evaluate_step(step).unwrap()).unwrap();
+        writeln!(io, "{}", current.highlighted(step));
+        current = replace(&current, step, &result);
+    }
+    writeln!(io, "{current}");
+}
+
+/// The order of operations, then which step is next.
+fn step_hints(steps: &[&Expr]) -> Vec<String> {
+    vec![ORDER.to_string(), format!("Na řadě je {}.", steps[0])]
+}
+
+/// The student picks each operation and computes it, `false` after too many mistakes.
+fn solve_by_steps(io: &mut dyn Io, expr: &Expr) -> Answer<bool> {
+    let mut current = expr.clone();
+    let mut mistakes = 0;
+    while value(&current).is_none() {
+        if mistakes >= MAX_MISTAKES {
+            return Ok(false);
+        }
+        writeln!(io, "{current}");
+        let mut steps = vec![];
+        next_steps(&current, &mut steps);
+
+        io.hints().offer(step_hints(&steps));
+        let chosen = to_binary(&get_expr(
+            io,
+            "Který výpočet je na řadě? Opiš ho, např. 3·4",
+        )?);
+        let Some(step) = steps.iter().find(|step| ***step == chosen) else {
+            writeln!(io, "{}", "Tohle teď na řadě není.".red());
+            writeln!(io, "{ORDER}");
+            mistakes += 1;
+            continue;
+        };
+
+        writeln!(io, "{}", current.highlighted(step));
+        let expected = evaluate_step(step).expect("Steps can be evaluated");
+        io.hints().offer(vec![]);
+        let answer: i64 = get_number(io, &format!("Kolik je {step}?"))?;
+        if answer != expected {
+            writeln!(io, "{}", format!("Špatně, {step} = {expected}.").red());
+            mistakes += 1;
+        }
+        let result = Expr::integer(expected).expect("Intermediate results are small");
+        current = replace(&current, step, &result);
+    }
+    writeln!(io, "{current}");
+    Ok(mistakes == 0)
+}
+
+pub fn solve_excercise(
+    io: &mut dyn Io,
+    excercise: OrderOfOperations,
+    step_mode: bool,
+) -> Answer<bool> {
+    let OrderOfOperations { expr, result } = &excercise;
+    writeln!(io, "Vypočítej:");
+    writeln!(io, "{expr}");
+    writeln!(io);
+
+    if step_mode {
+        if solve_by_steps(io, expr)? {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+    } else {
+        let mut steps = vec![];
+        next_steps(expr, &mut steps);
+        io.hints().offer(step_hints(&steps));
+        for _ in 0..MAX_ATTEMPTS {
+            let answer: i64 = get_number(io, "Výsledek")?;
+            if answer == *result {
+                writeln!(io, "{}", "Správně!".green().bold());
+                return Ok(true);
+            }
+            writeln!(io, "{}", "Špatně.".red());
+        }
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    print_model_solution(io, expr);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    fn parse(input: &str) -> Expr {
+        to_binary(&Expr::parse(input).unwrap())
+    }
+
+    fn excercise(input: &str) -> OrderOfOperations {
+        let expr = parse(input);
+        let result = evaluate(&expr).unwrap();
+        OrderOfOperations { expr, result }
+    }
+
+    fn steps_of(expr: &Expr) -> Vec<String> {
+        let mut steps = vec![];
+        next_steps(expr, &mut steps);
+        steps.iter().map(|step| step.to_string()).collect()
+    }
+
+    #[test]
+    fn single_steps_are_evaluated() {
+        let step = |input: &str| evaluate_step(&parse(input));
+        assert_eq!(step("2^3"), Some(8));
+        assert_eq!(step("12/4"), Some(3));
+        assert_eq!(step("-(-3)"), Some(3));
+        assert_eq!(step("7/2"), None);
+        assert_eq!(step("5/0"), None);
+        assert_eq!(step("40·40"), None);
+        assert_eq!(step("(1 + 2)·3"), None);
+    }
+
+    #[test]
+    fn next_steps_follow_the_order_of_operations() {
+        let expr = parse("2·(3 - 5)^2 - 12/(1 + 3)");
+        assert_eq!(steps_of(&expr), ["3 - 5", "1 + 3"]);
+        assert_eq!(steps_of(&parse("2 + 3·4")), ["3·4"]);
+        assert_eq!(evaluate(&expr), Some(5));
+    }
+
+    #[test]
+    fn sums_are_split_into_pairs_from_the_left() {
+        assert_eq!(steps_of(&parse("1 + 2 + 3")), ["1 + 2"]);
+        assert_eq!(steps_of(&parse("2·3·4")), ["2·3"]);
+    }
+
+    #[test]
+    fn only_the_chosen_occurrence_is_replaced() {
+        let expr = parse("2·3 + 2·3");
+        let mut steps = vec![];
+        next_steps(&expr, &mut steps);
+        let replaced = replace(&expr, steps[1], &Expr::Number(6));
+        assert_eq!(replaced.to_string(), "2·3 + 6");
+    }
+
+    #[test]
+    fn negative_factors_are_in_parentheses() {
+        let expr = parse("(11·9 + 10)·(-(-4))");
+        assert_eq!(expr.to_string(), "(11·9 + 10)·(-(-4))");
+        assert_eq!(parse("2·(-3)").to_string(), "2·(-3)");
+    }
+
+    #[test]
+    fn result_can_be_corrected() {
+        let mut io = ScriptedIo::new(&["20", "14"]);
+        let result = solve_excercise(&mut io, excercise("2 + 3·4"), false);
+        assert_eq!(result, Ok(true));
+        assert!(io.output().contains("Špatně."));
+    }
+
+    #[test]
+    fn step_mode_asks_for_the_next_operation() {
+        let answers = ["3·4", "12", "2 + 12", "14"];
+        let mut io = ScriptedIo::new(&answers);
+        let result = solve_excercise(&mut io, excercise("2 + 3·4"), true);
+        assert_eq!(result, Ok(true));
+
+        let answers = ["2 + 3", "3·4", "12", "2 + 12", "15"];
+        let mut io = ScriptedIo::new(&answers);
+        let result = solve_excercise(&mut io, excercise("2 + 3·4"), true);
+        assert_eq!(result, Ok(false));
+        let output = io.output();
+        assert!(output.contains("Tohle teď na řadě není."));
+        assert!(output.contains("Špatně, 2 + 12 = 14."));
+        assert!(output.contains("Tohle je řešení:"));
+    }
+}
@@ -0,0 +1,421 @@
+use colored::*;
+
+use crate::ast::canonical::gcd;
+use crate::input::number::format_decimal;
+use crate::input::*;
+use crate::random::Random;
+
+const MAX_ATTEMPTS: u32 = 3;
+/// Answers are rounded by the student at most to hundredths, e.g. haléře.
+const TOLERANCE: f64 = 0.005;
+
+/// Czech noun in the forms used after numbers: 1 koruna, 2 koruny, 5 korun, 2,5 koruny.
+struct Noun {
+    one: &'static str,
+    few: &'static str,
+    many: &'static str,
+    decimal: &'static str,
+}
+
+const KORUNA: Noun = Noun {
+    one: "koruna",
+    few: "koruny",
+    many: "korun",
+    decimal: "koruny",
+};
+const ROHLIK: Noun = Noun {
+    one: "rohlík",
+    few: "rohlíky",
+    many: "rohlíků",
+    decimal: "rohlíku",
+};
+const DELNIK: Noun = Noun {
+    one: "dělník",
+    few: "dělníci",
+    many: "dělníků",
+    decimal: "dělníka",
+};
+const DEN: Noun = Noun {
+    one: "den",
+    few: "dny",
+    many: "dní",
+    decimal: "dne",
+};
+
+impl Noun {
+    fn form(&self, count: f64) -> &'static str {
+        if count.fract() != 0.0 {
+            return self.decimal;
+        }
+        match count.abs() as u64 {
+            1 => self.one,
+            2..=4 => self.few,
+            _ => self.many,
+        }
+    }
+
+    /// `5 korun`, `2,5 koruny`
+    fn with_number(&self, count: f64) -> String {
+        format!("{} {}", format_decimal(count, 2), self.form(count))
+    }
+}
+
+/*
+ * Represents the excercise:
+ *      `text`, whose answer is `answer` in `units[0]`, e.g.
+ *      Bunda stála 1 200 korun a je zlevněná o 25 %. Kolik stojí po slevě?   =>   900 Kč
+ */
+pub struct WordProblem {
+    text: String,
+    answer: f64,
+    /// The first one is used in the question and in the solution.
+    units: &'static [&'static str],
+    solution: String,
+    /// From a nudge to almost the whole solution.
+    hints: Vec<String>,
+}
+
+const CROWNS: &[&str] = &["Kč", "kč", "korun", "koruny", "koruna"];
+const PERCENT: &[&str] = &["%", "procent", "procenta"];
+const DAYS: &[&str] = &["dní", "dny", "den", "dnů"];
+
+fn discount(rng: &mut Random) -> WordProblem {
+    let (item, verb, adjective) = [
+        ("Bunda", "stála", "zlevněná"),
+        ("Kolo", "stálo", "zlevněné"),
+        ("Batoh", "stál", "zlevněný"),
+    ][rng.random_range(0..3)];
+    // Multiples of 20 with a discount divisible by 5 give whole crowns
+    let price = 20.0 * f64::from(rng.random_range(5..=150));
+    let percent = [10.0, 15.0, 20.0, 25.0, 30.0, 40.0, 50.0][rng.random_range(0..7)];
+    let result = price * (100.0 - percent) / 100.0;
+    WordProblem {
+        text: format!(
+            "{item} {verb} {}. Ve výprodeji je {adjective} o {} %. Kolik stojí po slevě?",
+            KORUNA.with_number(price),
+            format_decimal(percent, 2)
+        ),
+        answer: result,
+        units: CROWNS,
+        solution: format!(
+            "{} · (100 % - {} %) = {} · {} = {} Kč",
+            format_decimal(price, 2),
+            format_decimal(percent, 2),
+            format_decimal(price, 2),
+            format_decimal((100.0 - percent) / 100.0, 2),
+            format_decimal(result, 2)
+        ),
+        hints: vec![
+            format!(
+                "Po slevě zbude 100 % - {} % původní ceny.",
+                format_decimal(percent, 2)
+            ),
+            format!(
+                "{} % je {} jako desetinné číslo, tím vynásob cenu.",
+                format_decimal(100.0 - percent, 2),
+                format_decimal((100.0 - percent) / 100.0, 2)
+            ),
+        ],
+    }
+}
+
+fn vat(rng: &mut Random) -> WordProblem {
+    let price = 100.0 * f64::from(rng.random_range(1..=60));
+    let with_vat = price * 1.21;
+    if rng.random_bool(0.5) {
+        WordProblem {
+            text: format!(
+                "Cena bez DPH je {}. Jaká je cena s DPH 21 %?",
+                KORUNA.with_number(price)
+            ),
+            answer: with_vat,
+            units: CROWNS,
+            solution: format!(
+                "{} · 1,21 = {} Kč",
+                format_decimal(price, 2),
+                format_decimal(with_vat, 2)
+            ),
+            hints: vec![
+                "Cena s DPH je 100 % + 21 % = 121 % ceny bez DPH.".to_string(),
+                "Vynásob cenu bez DPH číslem 1,21.".to_string(),
+            ],
+        }
+    } else {
+        WordProblem {
+            text: format!(
+                "Cena s DPH 21 % je {}. Jaká je cena bez DPH?",
+                KORUNA.with_number(with_vat)
+            ),
+            answer: price,
+            units: CROWNS,
+            solution: format!(
+                "{} : 1,21 = {} Kč",
+                format_decimal(with_vat, 2),
+                format_decimal(price, 2)
+            ),
+            hints: vec![
+                "Cena s DPH je 121 % ceny bez DPH, hledáš těch 100 %.".to_string(),
+                "Vyděl cenu s DPH číslem 1,21.".to_string(),
+            ],
+        }
+    }
+}
+
+fn percent_of(rng: &mut Random) -> WordProblem {
+    // Multiples of 20 and percentages divisible by 5 give whole crowns
+    let whole = [200.0, 400.0, 500.0, 800.0, 1000.0, 2000.0][rng.random_range(0..6)];
+    let percent = f64::from(rng.random_range(1..=19)) * 5.0;
+    let part = whole * percent / 100.0;
+    WordProblem {
+        text: format!(
+            "Třída vybrala na výlet {}, zatím se utratilo {}. Kolik procent peněz se utratilo?",
+            KORUNA.with_number(whole),
+            KORUNA.with_number(part)
+        ),
+        answer: percent,
+        units: PERCENT,
+        solution: format!(
+            "{} : {} · 100 % = {} %",
+            format_decimal(part, 2),
+            format_decimal(whole, 2),
+            format_decimal(percent, 2)
+        ),
+        hints: vec![
+            format!(
+                "Celých {} je 100 %, kolik je 1 %?",
+                KORUNA.with_number(whole)
+            ),
+            format!(
+                "1 % je {} Kč, kolikrát se vejde do {} Kč?",
+                format_decimal(whole / 100.0, 2),
+                format_decimal(part, 2)
+            ),
+        ],
+    }
+}
+
+fn ratio(rng: &mut Random) -> WordProblem {
+    let (a, b) = loop {
+        let (a, b) = (rng.random_range(1..=7), rng.random_range(1..=7));
+        if a != b && gcd(a, b) == 1 {
+            break (a as f64, b as f64);
+        }
+    };
+    let total = (a + b) * 10.0 * f64::from(rng.random_range(2..=30));
+    let first = total * a / (a + b);
+    WordProblem {
+        text: format!(
+            "Sourozenci si rozdělí {} v poměru {} : {}. Kolik dostane první z nich?",
+            KORUNA.with_number(total),
+            format_decimal(a, 0),
+            format_decimal(b, 0)
+        ),
+        answer: first,
+        units: CROWNS,
+        solution: format!(
+            "{} : ({} + {}) = {} Kč na jeden díl, {} · {} = {} Kč",
+            format_decimal(total, 2),
+            format_decimal(a, 0),
+            format_decimal(b, 0),
+            format_decimal(total / (a + b), 2),
+            format_decimal(a, 0),
+            format_decimal(total / (a + b), 2),
+            format_decimal(first, 2)
+        ),
+        hints: vec![
+            format!(
+                "Kolik dílů je dohromady? {} + {}",
+                format_decimal(a, 0),
+                format_decimal(b, 0)
+            ),
+            format!(
+                "Jeden díl je {} : {} Kč, první dostane {} dílů.",
+                format_decimal(total, 2),
+                format_decimal(a + b, 0),
+                format_decimal(a, 0)
+            ),
+        ],
+    }
+}
+
+fn direct_proportion(rng: &mut Random) -> WordProblem {
+    let unit_price = f64::from(rng.random_range(2..=9));
+    let count = f64::from(rng.random_range(2..=12));
+    let asked = loop {
+        let asked = f64::from(rng.random_range(1..=25));
+        if asked != count {
+            break asked;
+        }
+    };
+    let price = unit_price * count;
+    let result = unit_price * asked;
+    WordProblem {
+        text: format!(
+            "{} stojí {}. Kolik stojí {}?",
+            capitalize(&ROHLIK.with_number(count)),
+            KORUNA.with_number(price),
+            ROHLIK.with_number(asked)
+        ),
+        answer: result,
+        units: CROWNS,
+        solution: format!(
+            "Přímá úměrnost: {} : {} · {} = {} Kč",
+            format_decimal(price, 2),
+            format_decimal(count, 0),
+            format_decimal(asked, 0),
+            format_decimal(result, 2)
+        ),
+        hints: vec![
+            "Kolikrát víc rohlíků, tolikrát víc korun.".to_string(),
+            format!(
+                "Kolik stojí jeden rohlík? {} : {}",
+                format_decimal(price, 2),
+                format_decimal(count, 0)
+            ),
+        ],
+    }
+}
+
+fn inverse_proportion(rng: &mut Random) -> WordProblem {
+    let (workers, days, asked) = loop {
+        let workers = f64::from(rng.random_range(2..=12));
+        let days = f64::from(rng.random_range(2..=20));
+        let asked = f64::from(rng.random_range(1..=15));
+        if asked != workers && (workers * days / asked).fract() == 0.0 {
+            break (workers, days, asked);
+        }
+    };
+    let result = workers * days / asked;
+    WordProblem {
+        text: format!(
+            "{} postaví plot za {}. Za kolik dní postaví stejný plot {}?",
+            capitalize(&DELNIK.with_number(workers)),
+            DEN.with_number(days),
+            DELNIK.with_number(asked)
+        ),
+        answer: result,
+        units: DAYS,
+        solution: format!(
+            "Nepřímá úměrnost: {} · {} : {} = {}",
+            format_decimal(workers, 0),
+            format_decimal(days, 0),
+            format_decimal(asked, 0),
+            DEN.with_number(result)
+        ),
+        hints: vec![
+            "Kolikrát víc dělníků, tolikrát méně dní.".to_string(),
+            format!(
+                "Za kolik dní by plot postavil jeden dělník? {} · {}",
+                format_decimal(workers, 0),
+                format_decimal(days, 0)
+            ),
+        ],
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or(String::new(), |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+pub fn generate_word_problem(rng: &mut Random) -> WordProblem {
+    match rng.random_range(0..6) {
+        0 => discount(rng),
+        1 => vat(rng),
+        2 => percent_of(rng),
+        3 => ratio(rng),
+        4 => direct_proportion(rng),
+        _ => inverse_proportion(rng),
+    }
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: WordProblem) -> Answer<bool> {
+    let WordProblem {
+        text,
+        answer,
+        units,
+        solution,
+        hints,
+    } = excercise;
+    writeln!(io, "{text}");
+    writeln!(io);
+
+    io.hints().offer(hints);
+    for _ in 0..MAX_ATTEMPTS {
+        let guess = get_quantity(io, "Výsledek", units)?;
+        if (guess - answer).abs() <= TOLERANCE {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+        writeln!(io, "{}", "Špatně.".red());
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    writeln!(io, "{solution}");
+    writeln!(io, "Výsledek: {} {}", format_decimal(answer, 2), units[0]);
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    fn excercise() -> WordProblem {
+        WordProblem {
+            text: "Bunda stála 1200 korun. Ve výprodeji je zlevněná o 25 %. Kolik stojí po slevě?"
+                .to_string(),
+            answer: 900.0,
+            units: CROWNS,
+            solution: "1200 · (100 % - 25 %) = 1200 · 0,75 = 900 Kč".to_string(),
+            hints: vec![],
+        }
+    }
+
+    #[test]
+    fn nouns_agree_with_numbers() {
+        assert_eq!(KORUNA.with_number(1.0), "1 koruna");
+        assert_eq!(KORUNA.with_number(3.0), "3 koruny");
+        assert_eq!(KORUNA.with_number(0.0), "0 korun");
+        assert_eq!(KORUNA.with_number(12500.0), "12 500 korun");
+        assert_eq!(KORUNA.with_number(2.5), "2,5 koruny");
+        assert_eq!(DEN.with_number(4.0), "4 dny");
+        assert_eq!(DELNIK.with_number(2.0), "2 dělníci");
+        assert_eq!(ROHLIK.with_number(1.5), "1,5 rohlíku");
+        assert_eq!(capitalize("rohlík"), "Rohlík");
+    }
+
+    #[test]
+    fn answers_are_at_most_in_hundredths() {
+        for seed in 0..200 {
+            let problem = generate_word_problem(&mut Random::from_seed(seed));
+            let hundredths = problem.answer * 100.0;
+            assert!((hundredths - hundredths.round()).abs() < 1e-6);
+            assert!(problem
+                .solution
+                .contains(&format_decimal(problem.answer, 2)));
+        }
+    }
+
+    #[test]
+    fn answer_can_have_a_unit_and_a_decimal_comma() {
+        let mut io = ScriptedIo::new(&["900 kg", "899,99 Kč", "900,004 korun"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(true)));
+        let output = io.output();
+        assert!(output.contains("Jednotka \"kg\" sem nepatří."));
+        assert_eq!(output.matches("Špatně.").count(), 1);
+        assert!(output.contains("Správně!"));
+    }
+
+    #[test]
+    fn wrong_answers_end_with_the_solution() {
+        let mut io = ScriptedIo::new(&["800", "1 000 korun", "950 Kč"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert_eq!(output.matches("Špatně.").count(), 3);
+        assert!(output.contains("1200 · 0,75 = 900 Kč"));
+        assert!(output.contains("Výsledek: 900 Kč"));
+    }
+}
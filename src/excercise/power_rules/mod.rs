@@ -0,0 +1,213 @@
+use colored::*;
+
+use crate::ast::canonical::CanonicalTerm;
+use crate::ast::mistakes::diagnose;
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{generate_term, negate_random_exponents, TermConfig};
+
+const MAX_ATTEMPTS: u32 = 3;
+
+/*
+ * Represents the excercise:
+ *      simplify `assignment` into a single term, e.g.
+ *      (2·a^3·b^2)^3·a/(4·b^2) = 2·a^10·b^4
+ */
+pub struct PowerRules {
+    assignment: Expr,
+    solution: CanonicalTerm,
+    /// How the solution is printed
+    policy: NegativeExponents,
+}
+
+pub fn generate_power_rules(
+    rng: &mut Random,
+    config: &TermConfig,
+    negative_exponents: bool,
+) -> PowerRules {
+    let policy = if negative_exponents {
+        NegativeExponents::Keep
+    } else {
+        NegativeExponents::AsFraction
+    };
+    let generate = |rng: &mut Random| {
+        let term = generate_term(rng, config);
+        if negative_exponents {
+            negate_random_exponents(rng, term)
+        } else {
+            term
+        }
+    };
+
+    loop {
+        let sign = if rng.random_bool(0.3) { -1 } else { 1 };
+        let base = generate(rng).multiply_by_number(sign);
+        if base.variable_occurrences().is_empty() {
+            continue;
+        }
+        let exponent = rng.random_range(2..=3);
+        let multiplier = generate(rng);
+        let Ok(divisor) = generate(rng).pow(rng.random_range(1..=2), policy) else {
+            continue;
+        };
+
+        let assignment = Expr::Division {
+            lhs: Expr::Multiplication(vec![base.pow_simple(exponent), multiplier]).into(),
+            rhs: divisor.into(),
+        };
+        // Without a repeated variable there is nothing to simplify
+        if !has_repeated_variables(&assignment) {
+            continue;
+        }
+        // The numbers can overflow, in that case just try another assignment
+        if let Some(solution) = CanonicalTerm::from_expr(&assignment) {
+            return PowerRules {
+                assignment,
+                solution,
+                policy,
+            };
+        }
+    }
+}
+
+fn has_repeated_variables(expr: &Expr) -> bool {
+    let mut variables = expr.variable_occurrences();
+    let count = variables.len();
+    variables.sort();
+    variables.dedup();
+    variables.len() != count
+}
+
+enum Verdict {
+    Correct,
+    NotSimplified,
+    Wrong,
+}
+
+fn check_answer(answer: &Expr, solution: &CanonicalTerm) -> Verdict {
+    if CanonicalTerm::from_expr(answer).as_ref() != Some(solution) {
+        return Verdict::Wrong;
+    }
+
+    if has_repeated_variables(answer) {
+        Verdict::NotSimplified
+    } else {
+        Verdict::Correct
+    }
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: PowerRules) -> Answer<bool> {
+    let PowerRules {
+        assignment,
+        solution,
+        policy,
+    } = excercise;
+    writeln!(
+        io,
+        "Zjednodušte na jeden člen, každá proměnná v něm smí být jen jednou:"
+    );
+    writeln!(io, "{assignment}");
+    writeln!(io);
+
+    io.hints().offer(vec![
+        "Nejdřív umocni závorku: (x·y)^n = x^n·y^n a (x^a)^n = x^(a·n).".to_string(),
+        "Pak sečti exponenty stejných proměnných v čitateli: x^a·x^b = x^(a+b).".to_string(),
+        "Nakonec odečti exponenty jmenovatele: x^a/x^b = x^(a-b), čísla vyděl.".to_string(),
+        format!("Koeficient výsledku je {}.", solution.coefficient()),
+    ]);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_expr(io, "Zadej výsledek")?;
+        match check_answer(&answer, &solution) {
+            Verdict::Correct => {
+                writeln!(io, "{}", "Správně!".green().bold());
+                return Ok(true);
+            }
+            Verdict::NotSimplified => writeln!(
+                io,
+                "{}",
+                "Rovná se to zadání, ale některá proměnná je tam víckrát. Zjednoduš to ještě."
+                    .yellow()
+            ),
+            Verdict::Wrong => {
+                writeln!(io, "{}", "Tohle se zadání nerovná.".red());
+                if let Some(mistake) = diagnose(&answer, &assignment) {
+                    writeln!(io, "{}", mistake.explanation().yellow());
+                }
+            }
+        }
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    writeln!(io, "= {}", solution.to_expr(policy));
+    writeln!(io);
+    writeln!(io, "Postup:");
+    writeln!(io, "{}", assignment.simplify());
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    /// (2·a^3·b^2)^3·a/(4·b^2) = 2·a^10·b^4
+    fn excercise() -> PowerRules {
+        let assignment = Expr::parse("(2a^3b^2)^3·a/(4b^2)").unwrap();
+        let solution = CanonicalTerm::from_expr(&assignment).unwrap();
+        PowerRules {
+            assignment,
+            solution,
+            policy: NegativeExponents::AsFraction,
+        }
+    }
+
+    fn verdict(answer: &str) -> Verdict {
+        check_answer(&Expr::parse(answer).unwrap(), &excercise().solution)
+    }
+
+    #[test]
+    fn answers_are_checked() {
+        assert!(matches!(verdict("2a^10b^4"), Verdict::Correct));
+        assert!(matches!(verdict("2b^4a^10"), Verdict::Correct));
+        assert!(matches!(verdict("2a^9·a·b^4"), Verdict::NotSimplified));
+        assert!(matches!(verdict("8a^10b^6/(4b^2)"), Verdict::NotSimplified));
+        assert!(matches!(verdict("2a^9b^4"), Verdict::Wrong));
+        assert!(matches!(verdict("8a^10b^4"), Verdict::Wrong));
+    }
+
+    #[test]
+    fn simplified_answer_is_correct() {
+        let mut io = ScriptedIo::new(&["2a^10b^4"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(true)));
+        assert!(io.output().contains("Správně!"));
+    }
+
+    #[test]
+    fn wrong_answers_end_with_the_solution() {
+        let mut io = ScriptedIo::new(&["8a^10b^6/(4b^2)", "2a^9b^4", "6a^10b^4"]);
+        assert!(matches!(solve_excercise(&mut io, excercise()), Ok(false)));
+        let output = io.output();
+        assert!(output.contains("některá proměnná je tam víckrát"));
+        assert_eq!(output.matches("Tohle se zadání nerovná.").count(), 2);
+        assert!(output.contains("= 2·a^10·b^4"));
+        assert!(output.contains("Postup:"));
+        assert!(!output.contains("Správně!"));
+    }
+
+    #[test]
+    fn generated_assignments_need_simplifying() {
+        for negative_exponents in [false, true] {
+            for seed in 0..50 {
+                let rng = &mut Random::from_seed(seed);
+                let excercise =
+                    generate_power_rules(rng, &TermConfig::default(), negative_exponents);
+                assert!(has_repeated_variables(&excercise.assignment));
+                assert_eq!(
+                    CanonicalTerm::from_expr(&excercise.assignment),
+                    Some(excercise.solution)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, lcm};
+use crate::input::*;
+use crate::random::Random;
+
+const MAX_MISTAKES: u32 = 3;
+const MAX_ATTEMPTS: u32 = 3;
+const SMALL_PRIMES: [u64; 6] = [2, 3, 5, 7, 11, 13];
+
+/*
+ * Represents the excercise:
+ *      360 = 2^3·3^2·5                 (Factorize)
+ *      D(84, 120) = 12                 (Gcd)
+ *      n(12, 18, 30) = 180             (Lcm)
+ */
+pub enum PrimeExcercise {
+    Factorize(u64),
+    Gcd { numbers: Vec<u64>, method: Method },
+    Lcm(Vec<u64>),
+}
+
+#[derive(Clone, Copy)]
+pub enum Method {
+    PrimeFactors,
+    /// Only for two numbers.
+    Euclid,
+}
+
+fn is_prime(number: u64) -> bool {
+    number >= 2
+        && (2..)
+            .take_while(|d| d * d <= number)
+            .all(|d| !number.is_multiple_of(d))
+}
+
+/// The primes with their exponents, `360` is `[(2, 3), (3, 2), (5, 1)]`.
+fn factorize(mut number: u64) -> Vec<(u64, u32)> {
+    let mut factors = vec![];
+    let mut prime = 2;
+    while number > 1 {
+        if prime * prime > number {
+            factors.push((number, 1));
+            break;
+        }
+        let mut exp = 0;
+        while number.is_multiple_of(prime) {
+            number /= prime;
+            exp += 1;
+        }
+        if exp > 0 {
+            factors.push((prime, exp));
+        }
+        prime += 1;
+    }
+    factors
+}
+
+/// `2^3·3^2·5`
+fn factorization_to_string(factors: &[(u64, u32)]) -> String {
+    factors
+        .iter()
+        .map(|(prime, exp)| match exp {
+            1 => prime.to_string(),
+            exp => format!("{prime}^{exp}"),
+        })
+        .collect::<Vec<_>>()
+        .join("·")
+}
+
+/// Product of a few small primes, mostly with repeated factors, in `range`.
+fn number_with_factors(rng: &mut Random, range: std::ops::RangeInclusive<u64>) -> u64 {
+    loop {
+        let mut number = 1;
+        for _ in 0..rng.random_range(2..=6) {
+            // The smaller primes are more likely
+            let idx = rng
+                .random_range(0..SMALL_PRIMES.len())
+                .min(rng.random_range(0..SMALL_PRIMES.len()));
+            number *= SMALL_PRIMES[idx];
+        }
+        if range.contains(&number) && factorize(number).len() >= 2 {
+            return number;
+        }
+    }
+}
+
+fn format_numbers(numbers: &[u64]) -> String {
+    numbers
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn generate_factorization(rng: &mut Random) -> PrimeExcercise {
+    PrimeExcercise::Factorize(number_with_factors(rng, 24..=2000))
+}
+
+/// 2 or 3 numbers with a common part, so the result is neither 1 nor one of them.
+pub fn generate_gcd_lcm(rng: &mut Random) -> PrimeExcercise {
+    let count = if rng.random_bool(0.3) { 3 } else { 2 };
+    let numbers = loop {
+        let common = number_with_factors(rng, 2..=60);
+        let numbers: Vec<u64> = (0..count)
+            .map(|_| common * number_with_factors(rng, 2..=30))
+            .collect();
+        let divisor = numbers.iter().fold(0, |acc, n| gcd(acc, *n));
+        let multiple = numbers.iter().fold(1, |acc, n| lcm(acc, *n));
+        let is_interesting = !numbers.contains(&divisor) && !numbers.contains(&multiple);
+        let mut unique = numbers.clone();
+        unique.sort();
+        unique.dedup();
+        if is_interesting && unique.len() == count && numbers.iter().all(|n| *n <= 1000) {
+            break numbers;
+        }
+    };
+
+    if rng.random_bool(0.5) {
+        PrimeExcercise::Lcm(numbers)
+    } else {
+        let method = if count == 2 && rng.random_bool(0.5) {
+            Method::Euclid
+        } else {
+            Method::PrimeFactors
+        };
+        PrimeExcercise::Gcd { numbers, method }
+    }
+}
+
+/// The column method, `number | prime` on every line.
+fn print_column(io: &mut dyn Io, rows: &[(u64, u64)], last: u64) {
+    let width = rows.first().map_or(last, |(n, _)| *n).to_string().len();
+    for (number, prime) in rows {
+        writeln!(io, "{number:>width$} | {prime}");
+    }
+    writeln!(io, "{last:>width$} | {}", "?".bold().cyan());
+}
+
+/// Factorization with every division typed in, `false` when the student made too many mistakes.
+fn factorize_by_steps(io: &mut dyn Io, number: u64) -> Answer<bool> {
+    let mut rows: Vec<(u64, u64)> = vec![];
+    let mut current = number;
+    let mut mistakes = 0;
+    while current > 1 {
+        if mistakes >= MAX_MISTAKES {
+            return Ok(false);
+        }
+        print_column(io, &rows, current);
+        io.hints().offer(vec![
+            "Zkoušej prvočísla od nejmenšího: 2, 3, 5, 7, 11, …".to_string(),
+            format!("{current} je dělitelné {}.", factorize(current)[0].0),
+        ]);
+        let prime: u64 = get_number(io, &format!("Prvočíslo, kterým jde {current} vydělit"))?;
+        if !is_prime(prime) {
+            writeln!(io, "{}", format!("{prime} není prvočíslo.").red());
+            mistakes += 1;
+            continue;
+        }
+        if !current.is_multiple_of(prime) {
+            writeln!(io, "{}", format!("{current} není dělitelné {prime}.").red());
+            mistakes += 1;
+            continue;
+        }
+
+        let quotient = current / prime;
+        io.hints()
+            .offer(vec![format!("Kolikrát se vejde {prime} do {current}?")]);
+        loop {
+            let answer: u64 = get_number(io, &format!("{current} : {prime} = ?"))?;
+            if answer == quotient {
+                break;
+            }
+            writeln!(io, "{}", "Špatně.".red());
+            mistakes += 1;
+            if mistakes >= MAX_MISTAKES {
+                return Ok(false);
+            }
+        }
+        rows.push((current, prime));
+        current = quotient;
+    }
+
+    let width = number.to_string().len();
+    for (number, prime) in &rows {
+        writeln!(io, "{number:>width$} | {prime}");
+    }
+    writeln!(io, "{:>width$} |", 1);
+    Ok(true)
+}
+
+fn print_factorization_solution(io: &mut dyn Io, number: u64) {
+    let mut current = number;
+    let width = number.to_string().len();
+    for (prime, exp) in factorize(number) {
+        for _ in 0..exp {
+            writeln!(io, "{current:>width$} | {prime}");
+            current /= prime;
+        }
+    }
+    writeln!(io, "{:>width$} |", 1);
+    writeln!(
+        io,
+        "{number} = {}",
+        factorization_to_string(&factorize(number))
+    );
+}
+
+/// One step of the Euclidean algorithm per line, `false` when the student made too many mistakes.
+fn euclid_by_steps(io: &mut dyn Io, a: u64, b: u64) -> Answer<bool> {
+    let (mut a, mut b) = (a.max(b), a.min(b));
+    let mut mistakes = 0;
+    while b != 0 {
+        let remainder = a % b;
+        io.hints().offer(vec![
+            format!("Kolikrát se vejde {b} do {a}? Co zbyde?"),
+            format!("{a} = {}·{b} + ?", a / b),
+        ]);
+        loop {
+            let answer: u64 = get_number(io, &format!("Zbytek po dělení {a} : {b}"))?;
+            if answer == remainder {
+                break;
+            }
+            writeln!(io, "{}", "Špatně.".red());
+            mistakes += 1;
+            if mistakes >= MAX_MISTAKES {
+                return Ok(false);
+            }
+        }
+        writeln!(io, "{a} = {}·{b} + {remainder}", a / b);
+        (a, b) = (b, remainder);
+    }
+    writeln!(io, "Poslední nenulový zbytek je {a}.");
+    Ok(true)
+}
+
+fn print_euclid_solution(io: &mut dyn Io, a: u64, b: u64) {
+    let (mut a, mut b) = (a.max(b), a.min(b));
+    while b != 0 {
+        writeln!(io, "{a} = {}·{b} + {}", a / b, a % b);
+        (a, b) = (b, a % b);
+    }
+    writeln!(io, "Poslední nenulový zbytek je {a}.");
+}
+
+/// Asks for the final number, `hint` is shown after the first mistake.
+fn ask_result(io: &mut dyn Io, question: &str, expected: u64, hint: &str) -> Answer<bool> {
+    io.hints().offer(vec![
+        hint.to_string(),
+        format!("{} = ?", factorization_to_string(&factorize(expected))),
+    ]);
+    for attempt in 0..MAX_ATTEMPTS {
+        let answer: u64 = get_number(io, question)?;
+        if answer == expected {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+        writeln!(io, "{}", "Špatně.".red());
+        if attempt == 0 {
+            writeln!(io, "{}", hint.yellow());
+        }
+    }
+    Ok(false)
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: PrimeExcercise) -> Answer<bool> {
+    match excercise {
+        PrimeExcercise::Factorize(number) => {
+            writeln!(
+                io,
+                "Rozlož číslo {number} na prvočísla (dělením pod sebou):"
+            );
+            if factorize_by_steps(io, number)? {
+                writeln!(
+                    io,
+                    "{number} = {}",
+                    factorization_to_string(&factorize(number))
+                );
+                writeln!(io, "{}", "Správně!".green().bold());
+                return Ok(true);
+            }
+            writeln!(io, "Tohle je řešení:");
+            print_factorization_solution(io, number);
+            Ok(false)
+        }
+        PrimeExcercise::Gcd { numbers, method } => {
+            let expected = numbers.iter().fold(0, |acc, n| gcd(acc, *n));
+            let label = format!("D({})", format_numbers(&numbers));
+            let is_done = match method {
+                Method::PrimeFactors => {
+                    writeln!(
+                        io,
+                        "Najdi největší společný dělitel {label} pomocí rozkladu na prvočísla."
+                    );
+                    factorize_all_by_steps(io, &numbers)
+                }
+                Method::Euclid => {
+                    writeln!(
+                        io,
+                        "Najdi největší společný dělitel {label} Eukleidovým algoritmem:"
+                    );
+                    writeln!(io, "větší číslo vydělíš menším a pak dělíš dělitele zbytkem, dokud zbytek není 0.");
+                    euclid_by_steps(io, numbers[0], numbers[1])
+                }
+            }?;
+            let hint = match method {
+                Method::PrimeFactors => {
+                    "Největší společný dělitel je součin společných prvočísel s nejmenšími exponenty."
+                }
+                Method::Euclid => "Největší společný dělitel je poslední nenulový zbytek.",
+            };
+            if is_done && ask_result(io, &format!("{label} = ?"), expected, hint)? {
+                return Ok(true);
+            }
+            writeln!(io, "Tohle je řešení:");
+            match method {
+                Method::PrimeFactors => print_factorizations(io, &numbers),
+                Method::Euclid => print_euclid_solution(io, numbers[0], numbers[1]),
+            }
+            writeln!(
+                io,
+                "{label} = {} = {expected}",
+                factorization_to_string(&factorize(expected))
+            );
+            Ok(false)
+        }
+        PrimeExcercise::Lcm(numbers) => {
+            let expected = numbers.iter().fold(1, |acc, n| lcm(acc, *n));
+            let label = format!("n({})", format_numbers(&numbers));
+            writeln!(
+                io,
+                "Najdi nejmenší společný násobek {label} pomocí rozkladu na prvočísla."
+            );
+            let hint = "Nejmenší společný násobek je součin všech prvočísel z rozkladů s největšími exponenty.";
+            if factorize_all_by_steps(io, &numbers)?
+                && ask_result(io, &format!("{label} = ?"), expected, hint)?
+            {
+                return Ok(true);
+            }
+            writeln!(io, "Tohle je řešení:");
+            print_factorizations(io, &numbers);
+            writeln!(
+                io,
+                "{label} = {} = {expected}",
+                factorization_to_string(&factorize(expected))
+            );
+            Ok(false)
+        }
+    }
+}
+
+fn factorize_all_by_steps(io: &mut dyn Io, numbers: &[u64]) -> Answer<bool> {
+    for number in numbers {
+        writeln!(io);
+        writeln!(io, "Rozklad čísla {number}:");
+        if !factorize_by_steps(io, *number)? {
+            return Ok(false);
+        }
+        writeln!(
+            io,
+            "{number} = {}",
+            factorization_to_string(&factorize(*number))
+        );
+    }
+    writeln!(io);
+    print_factorizations(io, numbers);
+    Ok(true)
+}
+
+fn print_factorizations(io: &mut dyn Io, numbers: &[u64]) {
+    for number in numbers {
+        writeln!(
+            io,
+            "{number} = {}",
+            factorization_to_string(&factorize(*number))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm_numbers_are_different() {
+        for seed in 0..500 {
+            let numbers = match generate_gcd_lcm(&mut Random::from_seed(seed)) {
+                PrimeExcercise::Gcd { numbers, .. } | PrimeExcercise::Lcm(numbers) => numbers,
+                PrimeExcercise::Factorize(_) => unreachable!(),
+            };
+            let mut unique = numbers.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), numbers.len(), "{numbers:?}");
+        }
+    }
+}
@@ -0,0 +1,630 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, CanonicalFraction, CanonicalTerm};
+use crate::ast::equation::Equation;
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{random_range_filter, ExtractDiffSquares, TermConfig};
+
+const MAX_ATTEMPTS: u32 = 3;
+/// Roots typed by the student are compared numerically, they may contain square roots.
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RootKind {
+    TwoInteger,
+    Double,
+    NoReal,
+    Irrational,
+}
+
+impl RootKind {
+    pub const ALL: [RootKind; 4] = [
+        RootKind::TwoInteger,
+        RootKind::Double,
+        RootKind::NoReal,
+        RootKind::Irrational,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            RootKind::TwoInteger => "Dva celočíselné kořeny",
+            RootKind::Double => "Jeden dvojnásobný kořen",
+            RootKind::NoReal => "Žádný reálný kořen",
+            RootKind::Irrational => "Kořeny s odmocninou",
+        }
+    }
+}
+
+/// `None` when every excercise should have a random kind of roots.
+pub fn pick_root_kind(io: &mut dyn Io) -> Answer<Option<RootKind>> {
+    for (idx, kind) in RootKind::ALL.iter().enumerate() {
+        writeln!(io, "{}) {}", idx + 1, kind.name());
+    }
+    writeln!(io, "{}) Náhodně", RootKind::ALL.len() + 1);
+    let choice = get_number_in_range(
+        io,
+        "Jaké kořeny mají rovnice mít?",
+        1..=RootKind::ALL.len() + 1,
+    )?;
+    Ok(RootKind::ALL.get(choice - 1).copied())
+}
+
+/*
+ * Represents the excercise:
+ *      `equation` with the unknown `var`, which is `a·var^2 + b·var + c = 0` after moving
+ *      everything to the left side, e.g.
+ *      x^2 = 5·x - 6   =>   x = 2, x = 3
+ */
+pub struct QuadraticEquation {
+    equation: Equation,
+    var: char,
+    coefficients: [i64; 3],
+    roots: Vec<Expr>,
+}
+
+fn quadratic(coefficients: [i64; 3], var: char) -> Expr {
+    let var = Expr::Variable { symbol: var };
+    let square = Expr::Exp {
+        base: var.clone().into(),
+        exp: Expr::Number(2).into(),
+    };
+    let [a, b, c] = coefficients;
+    Expr::linear_combination(vec![(a, vec![square]), (b, vec![var]), (c, vec![])])
+        .expect("Generated coefficients are small")
+}
+
+fn is_square(number: i64) -> bool {
+    number >= 0 && (number as f64).sqrt().round().powi(2) == number as f64
+}
+
+/// The roots, or the number at `x` when they are not whole, are picked by `config`.
+fn random_coefficients(rng: &mut Random, config: &TermConfig, kind: RootKind) -> [i64; 3] {
+    let leading = |rng: &mut Random| [1, 1, 1, 1, 2, -1][rng.random_range(0..6)];
+    match kind {
+        RootKind::TwoInteger => {
+            let a = leading(rng);
+            let r1 = config.pick_coefficient(rng);
+            let r2 = match rng.random_range(0..4) {
+                // x^2 - 9 = (x - 3)·(x + 3)
+                0 if r1 != 0 => -r1,
+                // x^2 - 3·x = x·(x - 3)
+                1 if r1 != 0 => 0,
+                _ => loop {
+                    let r2 = config.pick_coefficient(rng);
+                    if r2 != r1 {
+                        break r2;
+                    }
+                },
+            };
+            [a, -a * (r1 + r2), a * r1 * r2]
+        }
+        RootKind::Double => {
+            // (x - r)^2
+            let a = leading(rng);
+            let r = config.pick_nonzero_coefficient(rng);
+            [a, -2 * a * r, a * r * r]
+        }
+        RootKind::NoReal => {
+            let b = config.pick_coefficient(rng);
+            let c = b * b / 4 + rng.random_range(1..=10);
+            [1, b, c]
+        }
+        RootKind::Irrational => loop {
+            let a = [1, 1, 1, 2][rng.random_range(0..4)];
+            let b = config.pick_coefficient(rng);
+            let c = random_range_filter(rng, -10..=10i64, |c| *c != 0);
+            let discriminant = b * b - 4 * a * c;
+            if discriminant > 0 && !is_square(discriminant) {
+                break [a, b, c];
+            }
+        },
+    }
+}
+
+fn integer_fraction(top: i64, bottom: i64) -> Expr {
+    let fraction = Expr::Division {
+        lhs: Expr::integer(top).unwrap().into(),
+        rhs: Expr::integer(bottom).unwrap().into(),
+    };
+    CanonicalTerm::from_expr(&fraction)
+        .expect("Denominator is not zero")
+        .to_expr(NegativeExponents::AsFraction)
+}
+
+/// `√72` is `6·√2`, gives `(6, 2)`.
+fn extract_square(number: i64) -> (i64, i64) {
+    let mut outside = 1;
+    let mut inside = number;
+    let mut factor = 2;
+    while factor * factor <= inside {
+        if inside % (factor * factor) == 0 {
+            inside /= factor * factor;
+            outside *= factor;
+        } else {
+            factor += 1;
+        }
+    }
+    (outside, inside)
+}
+
+/// The roots `(-b ± √D)/(2a)`, simplified.
+fn roots_of([a, b, c]: [i64; 3]) -> Vec<Expr> {
+    let discriminant = b * b - 4 * a * c;
+    if discriminant < 0 {
+        return vec![];
+    }
+    if is_square(discriminant) {
+        let sqrt = (discriminant as f64).sqrt().round() as i64;
+        let mut roots = vec![integer_fraction(-b - sqrt, 2 * a)];
+        if sqrt != 0 {
+            roots.push(integer_fraction(-b + sqrt, 2 * a));
+        }
+        return roots;
+    }
+
+    let (outside, inside) = extract_square(discriminant);
+    let divisor = [b, outside, 2 * a]
+        .iter()
+        .fold(0, |acc, n| gcd(acc, n.unsigned_abs())) as i64;
+    let divisor = divisor * (2 * a).signum();
+    let (b, outside, denominator) = (b / divisor, outside / divisor, 2 * a / divisor);
+    let sqrt = Expr::Root {
+        degree: 2,
+        radicand: Expr::integer(inside).unwrap().into(),
+    };
+    [-1, 1]
+        .into_iter()
+        .map(|sign| {
+            let numerator =
+                Expr::linear_combination(vec![(-b, vec![]), (sign * outside, vec![sqrt.clone()])])
+                    .unwrap();
+            if denominator == 1 {
+                numerator
+            } else {
+                Expr::Division {
+                    lhs: numerator.into(),
+                    rhs: Expr::integer(denominator).unwrap().into(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// `kind` is `None` for a random kind of roots.
+pub fn generate_quadratic_equation(
+    rng: &mut Random,
+    config: &TermConfig,
+    kind: Option<RootKind>,
+) -> QuadraticEquation {
+    let kind = kind.unwrap_or_else(|| RootKind::ALL[rng.random_range(0..RootKind::ALL.len())]);
+    let var = config.pick_variable(rng);
+    let coefficients = random_coefficients(rng, config, kind);
+    let [a, b, c] = coefficients;
+
+    let equation = match rng.random_range(0..3) {
+        // a·x^2 + b·x = -c
+        0 if c != 0 => Equation {
+            lhs: quadratic([a, b, 0], var),
+            rhs: quadratic([0, 0, -c], var),
+        },
+        // a·x^2 = -b·x - c
+        1 if b != 0 => Equation {
+            lhs: quadratic([a, 0, 0], var),
+            rhs: quadratic([0, -b, -c], var),
+        },
+        _ => Equation {
+            lhs: quadratic(coefficients, var),
+            rhs: Expr::zero(),
+        },
+    };
+
+    QuadraticEquation {
+        equation,
+        var,
+        coefficients,
+        roots: roots_of(coefficients),
+    }
+}
+
+/// `x^2 - 9` and `x^2 - 6·x + 9` are the formulas `a^2 - b^2` and `(a ± b)^2`,
+/// up to the number at `x^2`.
+enum Formula {
+    DiffSquares(ExtractDiffSquares),
+    /// The square of `a + b` when `true`, of `a - b` otherwise.
+    BinomialSquare(ExtractDiffSquares, bool),
+}
+
+fn formula(excercise: &QuadraticEquation) -> Option<Formula> {
+    let [a, b, c] = excercise.coefficients;
+    let squares = |number: i64| {
+        let var = Expr::Variable {
+            symbol: excercise.var,
+        };
+        ExtractDiffSquares {
+            a: var.clone(),
+            b: Expr::integer(number).unwrap(),
+            a2: Expr::Exp {
+                base: var.into(),
+                exp: Expr::Number(2).into(),
+            },
+            b2: Expr::integer(number * number).unwrap(),
+        }
+    };
+    if b == 0 && c != 0 && c % a == 0 && is_square(-c / a) {
+        let root = (-c / a) as f64;
+        return Some(Formula::DiffSquares(squares(root.sqrt().round() as i64)));
+    }
+    // x^2 + 2·r·x + r^2 = (x + r)^2
+    if b != 0 && b * b == 4 * a * c && b % (2 * a) == 0 {
+        let r = b / (2 * a);
+        return Some(Formula::BinomialSquare(squares(r.abs()), r > 0));
+    }
+    None
+}
+
+/// `a·(x - r1)·(x - r2)`, `None` when the roots are not rational.
+fn factored(excercise: &QuadraticEquation) -> Option<Expr> {
+    let QuadraticEquation {
+        var,
+        coefficients: [a, _, _],
+        roots,
+        ..
+    } = excercise;
+    if roots.is_empty() {
+        return None;
+    }
+    let factors = match formula(excercise) {
+        Some(Formula::DiffSquares(squares)) => {
+            vec![squares.binomial(false), squares.binomial(true)]
+        }
+        Some(Formula::BinomialSquare(squares, is_sum)) => vec![Expr::Exp {
+            base: squares.binomial(is_sum).into(),
+            exp: Expr::Number(2).into(),
+        }],
+        None => {
+            let var = Expr::Variable { symbol: *var };
+            let factors = roots
+                .iter()
+                .map(|root| {
+                    let root = CanonicalTerm::from_expr(root)?.negate();
+                    let factor = Expr::Addition(vec![
+                        var.clone(),
+                        root.to_expr(NegativeExponents::AsFraction),
+                    ]);
+                    Some(factor.simplify().result().clone())
+                })
+                .collect::<Option<Vec<_>>>()?;
+            match &factors[..] {
+                // (x - r)^2 with a fraction r
+                [factor] => vec![Expr::Exp {
+                    base: factor.clone().into(),
+                    exp: Expr::Number(2).into(),
+                }],
+                _ => factors,
+            }
+        }
+    };
+    Expr::from_coefficient(*a, factors)
+}
+
+enum Method {
+    Factoring,
+    Discriminant,
+    CompletingSquare,
+}
+
+fn pick_method(io: &mut dyn Io) -> Answer<Method> {
+    writeln!(io, "1) Rozkladem na součin");
+    writeln!(io, "2) Pomocí diskriminantu");
+    writeln!(io, "3) Doplněním na čtverec");
+    Ok(
+        match get_number_in_range(io, "Jak budeš rovnici řešit?", 1..=3)? {
+            1 => Method::Factoring,
+            2 => Method::Discriminant,
+            _ => Method::CompletingSquare,
+        },
+    )
+}
+
+/// `lhs - rhs` of the equation, everything moved to the left side.
+fn left_side(equation: &Equation) -> Expr {
+    Expr::Addition(vec![
+        equation.lhs.clone(),
+        Expr::UnaryMinus(equation.rhs.clone().into()),
+    ])
+}
+
+/// Factors with the unknown, `(x - 3)^2` counts as two of them.
+fn count_factors(expr: &Expr) -> u32 {
+    match expr {
+        Expr::Multiplication(factors) => factors.iter().map(count_factors).sum(),
+        Expr::UnaryMinus(inner) => count_factors(inner),
+        Expr::Division { lhs, rhs } if rhs.variable_occurrences().is_empty() => count_factors(lhs),
+        Expr::Exp { base, exp } => match **exp {
+            Expr::Number(exp) => exp * count_factors(base),
+            _ => 1,
+        },
+        expr => u32::from(!expr.variable_occurrences().is_empty()),
+    }
+}
+
+/// `2·(x - 1)·(x + 3)` or `(x - 2)^2`, but not `1·(x^2 + 2·x - 3)`.
+fn is_product(expr: &Expr) -> bool {
+    count_factors(expr) >= 2
+}
+
+/// Each method returns `true` when the student managed its step.
+fn do_factoring(io: &mut dyn Io, excercise: &QuadraticEquation) -> Answer<bool> {
+    let Some(solution) = factored(excercise) else {
+        writeln!(
+            io,
+            "{}",
+            "Tahle rovnice se na součin s celými čísly rozložit nedá, zkus diskriminant.".yellow()
+        );
+        return do_discriminant(io, excercise);
+    };
+    let expected = CanonicalFraction::from_expr(&left_side(&excercise.equation))
+        .expect("Generated equation is a polynomial");
+
+    let [a, b, c] = excercise.coefficients;
+    let var = excercise.var;
+    let mut hints = vec![format!(
+        "V základním tvaru je to {} = 0.",
+        quadratic([a, b, c], var)
+    )];
+    if a != 1 && formula(excercise).is_some() {
+        hints.push(format!("Nejdřív vytkni {a}."));
+    }
+    match formula(excercise) {
+        Some(Formula::DiffSquares(squares)) => hints.push(format!(
+            "{} = ({})^2 - ({})^2, teď stačí vzorec a^2 - b^2 = (a - b)·(a + b).",
+            squares.difference(),
+            squares.a,
+            squares.b
+        )),
+        Some(Formula::BinomialSquare(squares, is_sum)) => hints.push(format!(
+            "{} je vzorec (a ± b)^2 = a^2 ± 2·a·b + b^2 pro a = {} a b = {}.",
+            squares.square_of_binomial(is_sum),
+            squares.a,
+            squares.b
+        )),
+        None => hints.extend([
+            format!(
+                "Hledej kořeny, jejich součet je {} a součin {}.",
+                integer_fraction(-b, a),
+                integer_fraction(c, a)
+            ),
+            format!("Rozklad má tvar ({var} - {var}1)·({var} - {var}2), krát číslo u {var}^2."),
+        ]),
+    }
+    io.hints().offer(hints);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_expr(
+            io,
+            "Převeď vše na levou stranu a zadej ji rozloženou na součin",
+        )?;
+        let Some(fraction) = CanonicalFraction::from_expr(&answer) else {
+            writeln!(io, "{}", "Tohle není mnohočlen.".red());
+            continue;
+        };
+        let is_multiple = fraction.denominator().as_constant().is_some()
+            && fraction
+                .numerator()
+                .is_constant_multiple_of(expected.numerator());
+        if !is_multiple {
+            writeln!(io, "{}", "Tohle se levé straně nerovná.".red());
+        } else if !is_product(&answer) {
+            writeln!(io, "{}", "Rovná se to, ale ještě to není součin.".yellow());
+        } else {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+    }
+    writeln!(io, "Rozklad je: {solution} = 0");
+    Ok(false)
+}
+
+fn do_discriminant(io: &mut dyn Io, excercise: &QuadraticEquation) -> Answer<bool> {
+    let [a, b, c] = excercise.coefficients;
+    writeln!(
+        io,
+        "Rovnice v základním tvaru: {} = 0",
+        quadratic(excercise.coefficients, excercise.var)
+    );
+    let discriminant = b * b - 4 * a * c;
+    io.hints().offer(vec![
+        format!("a = {a}, b = {b}, c = {c}"),
+        format!("D = ({b})^2 - 4·({a})·({c})"),
+    ]);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer: i64 = get_number(io, "Kolik je diskriminant D = b^2 - 4ac?")?;
+        if answer == discriminant {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+        writeln!(io, "{}", "Špatně.".red());
+    }
+    writeln!(io, "D = ({b})^2 - 4·({a})·({c}) = {discriminant}");
+    Ok(false)
+}
+
+fn do_completing_square(io: &mut dyn Io, excercise: &QuadraticEquation) -> Answer<bool> {
+    let QuadraticEquation { equation, var, .. } = excercise;
+    let [a, b, c] = excercise.coefficients;
+    io.hints().offer(vec![
+        format!("Vyděl rovnici číslem u {var}^2, aby tam byla jednička."),
+        format!("K oběma stranám přičti druhou mocninu poloviny čísla u {var}."),
+        format!("Polovina čísla u {var} je {}.", integer_fraction(b, 2 * a)),
+    ]);
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_input(
+            io,
+            "Uprav rovnici na tvar (x + p)^2 = q",
+            Some("rovnici, např. (x - 3)^2 = 5"),
+            |input| Equation::parse(input).map_err(|err| err.to_string()),
+        )?;
+        let is_square = matches!(&answer.lhs, Expr::Exp { exp, .. } if **exp == Expr::Number(2));
+        let is_equivalent = answer.is_equivalent(equation) == Some(true);
+        if !is_equivalent {
+            writeln!(io, "{}", "Tahle rovnice není s původní ekvivalentní.".red());
+        } else if !is_square || !answer.rhs.variable_occurrences().is_empty() {
+            writeln!(
+                io,
+                "{}",
+                "Je to ekvivalentní, ale vlevo má být druhá mocnina a vpravo číslo.".yellow()
+            );
+        } else {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+    }
+
+    // (x + b/(2a))^2 = (b^2 - 4ac)/(4a^2)
+    let shift = integer_fraction(b, 2 * a);
+    let base = Expr::Addition(vec![Expr::Variable { symbol: *var }, shift]);
+    let square = Expr::Exp {
+        base: base.simplify().result().clone().into(),
+        exp: Expr::Number(2).into(),
+    };
+    let rhs = integer_fraction(b * b - 4 * a * c, 4 * a * a);
+    writeln!(io, "Doplněno na čtverec: {}", Equation { lhs: square, rhs });
+    Ok(false)
+}
+
+/// `2, 3` or `x1 = 2, x2 = 3`, an empty list for "nemá řešení".
+fn parse_roots(input: &str) -> Result<Vec<f64>, String> {
+    let lowercase = input.to_lowercase();
+    if ["nemá", "nema", "žádné", "zadne", "∅"]
+        .iter()
+        .any(|word| lowercase.contains(word))
+    {
+        return Ok(vec![]);
+    }
+    input
+        .split([',', ';'])
+        .map(|part| {
+            let value = part.rsplit('=').next().unwrap();
+            let expr = Expr::parse(value).map_err(|err| err.to_string())?;
+            expr.approximate()
+                .ok_or_else(|| format!("\"{}\" není reálné číslo.", value.trim()))
+        })
+        .collect()
+}
+
+fn are_same_roots(answer: &[f64], roots: &[f64]) -> bool {
+    let mut answer = answer.to_vec();
+    answer.sort_by(f64::total_cmp);
+    answer.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+    answer.len() == roots.len()
+        && answer
+            .iter()
+            .zip(roots)
+            .all(|(a, b)| (a - b).abs() < TOLERANCE)
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: QuadraticEquation) -> Answer<bool> {
+    let QuadraticEquation {
+        equation,
+        var,
+        roots,
+        ..
+    } = &excercise;
+    writeln!(io, "Vyřeš kvadratickou rovnici s neznámou {var}:");
+    writeln!(io, "{equation}");
+    writeln!(io);
+
+    let is_method_done = match pick_method(io)? {
+        Method::Factoring => do_factoring(io, &excercise)?,
+        Method::Discriminant => do_discriminant(io, &excercise)?,
+        Method::CompletingSquare => do_completing_square(io, &excercise)?,
+    };
+    writeln!(io);
+
+    let [a, b, c] = excercise.coefficients;
+    io.hints().offer(vec![
+        format!("{var}1,2 = (-b ± √D) / (2a)"),
+        format!(
+            "D = {}, když je záporný, rovnice nemá řešení.",
+            b * b - 4 * a * c
+        ),
+    ]);
+    let mut values: Vec<f64> = roots
+        .iter()
+        .map(|root| root.approximate().expect("Roots are real numbers"))
+        .collect();
+    values.sort_by(f64::total_cmp);
+    let mut is_solved = false;
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_input(
+            io,
+            "Zadej všechny kořeny",
+            Some("kořeny oddělené čárkou, např. 2, -1/3, 1 + √5, nebo \"nemá řešení\""),
+            parse_roots,
+        )?;
+        if are_same_roots(&answer, &values) {
+            writeln!(io, "{}", "Správně!".green().bold());
+            is_solved = true;
+            break;
+        }
+        writeln!(io, "{}", "Špatně.".red());
+    }
+    if is_solved {
+        return Ok(is_method_done);
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    writeln!(io, "D = {}", b * b - 4 * a * c);
+    if let Some(factored) = factored(&excercise) {
+        writeln!(io, "{factored} = 0");
+    }
+    if roots.is_empty() {
+        writeln!(
+            io,
+            "Diskriminant je záporný, rovnice nemá žádné reálné řešení."
+        );
+    }
+    for (idx, root) in roots.iter().enumerate() {
+        writeln!(io, "{var}{} = {root}", idx + 1);
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excercise(coefficients: [i64; 3]) -> QuadraticEquation {
+        QuadraticEquation {
+            equation: Equation {
+                lhs: quadratic(coefficients, 'x'),
+                rhs: Expr::zero(),
+            },
+            var: 'x',
+            coefficients,
+            roots: roots_of(coefficients),
+        }
+    }
+
+    #[test]
+    fn product_needs_two_factors() {
+        let is_product = |input: &str| is_product(&Expr::parse(input).unwrap());
+        assert!(is_product("(x - 2)·(x - 3)"));
+        assert!(is_product("-2·(x + 1)^2"));
+        assert!(is_product("x·(x - 3)/2"));
+        assert!(!is_product("1·(x^2 - 5x + 6)"));
+        assert!(!is_product("(x^2 - 5x + 6)^1"));
+        assert!(!is_product("2·(x^2 - 9)"));
+    }
+
+    #[test]
+    fn formulas_give_the_factors() {
+        let factored = |coefficients| factored(&excercise(coefficients)).unwrap().to_string();
+        assert_eq!(factored([1, 0, -9]), "(x - 3)·(x + 3)");
+        assert_eq!(factored([2, 0, -8]), "2·(x - 2)·(x + 2)");
+        assert_eq!(factored([1, -6, 9]), "(x - 3)^2");
+        assert_eq!(factored([-1, -4, -4]), "-(x + 2)^2");
+        assert_eq!(factored([1, -5, 6]), "(x - 2)·(x - 3)");
+    }
+}
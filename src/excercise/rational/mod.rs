@@ -0,0 +1,413 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, CanonicalFraction, CanonicalPolynomial, CanonicalTerm};
+use crate::ast::equivalence::{compare_at_points, Confidence, Equivalence};
+use crate::ast::mistakes::diagnose;
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{ExtractDiffSquares, TermConfig};
+
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Condition `lhs ≠ rhs` under which the expression makes sense.
+struct Condition {
+    lhs: Expr,
+    rhs: Expr,
+}
+
+impl Condition {
+    /// The expression which must not be zero, `lhs - rhs`.
+    fn nonzero_expr(&self) -> Expr {
+        Expr::Addition(vec![
+            self.lhs.clone(),
+            Expr::UnaryMinus(self.rhs.clone().into()),
+        ])
+    }
+
+    /// `lhs - rhs` as a polynomial, `None` when there are variables in a denominator.
+    fn polynomial(&self) -> Option<CanonicalPolynomial> {
+        let fraction = CanonicalFraction::from_expr(&self.nonzero_expr())?;
+        // `x/2 ≠ 3` is fine, `1/x ≠ 3` is something else
+        fraction.denominator().as_constant()?;
+        Some(fraction.numerator().clone())
+    }
+}
+
+/*
+ * Represents the excercise:
+ *      `assignment` = `solution`, if all the `conditions` hold, e.g.
+ *      (a^2 - b^2)/(a + b) = a - b, if a ≠ -b
+ */
+pub struct RationalExpression {
+    assignment: Expr,
+    solution: Expr,
+    conditions: Vec<Condition>,
+    /// For the simplification, the conditions have their own.
+    hints: Vec<String>,
+}
+
+fn has_variables(expr: &Expr) -> bool {
+    !expr.variable_occurrences().is_empty()
+}
+
+/// Condition for `a + b ≠ 0` or `a - b ≠ 0`, written with the variables on the left side.
+fn binomial_condition(a: &Expr, b: &Expr, is_sum: bool) -> Condition {
+    let (lhs, rhs) = if has_variables(a) { (a, b) } else { (b, a) };
+    let rhs = if is_sum {
+        rhs.multiply_by_number(-1)
+    } else {
+        rhs.clone()
+    };
+    Condition {
+        lhs: lhs.clone(),
+        rhs,
+    }
+}
+
+/// `a ± b` can not be factored any further: only `a` has variables and the coefficients
+/// are coprime, or `b` has other variables than `a`.
+fn is_simple_binomial(a: &Expr, b: &Expr) -> bool {
+    let (Some(a_term), Some(b_term)) = (CanonicalTerm::from_expr(a), CanonicalTerm::from_expr(b))
+    else {
+        return false;
+    };
+    let a_vars = a.variable_occurrences();
+    let is_shared = b
+        .variable_occurrences()
+        .iter()
+        .any(|var| a_vars.contains(var));
+    let divisor = gcd(
+        a_term.coefficient().numerator().unsigned_abs(),
+        b_term.coefficient().numerator().unsigned_abs(),
+    );
+    !a_vars.is_empty() && !is_shared && divisor == 1
+}
+
+pub fn generate_rational_expression(rng: &mut Random, config: &TermConfig) -> RationalExpression {
+    let squares = loop {
+        let squares = ExtractDiffSquares::generate_random(rng, config, 0.0, false);
+        if is_simple_binomial(&squares.a, &squares.b) {
+            break squares;
+        }
+    };
+    // `1·x^1` is written as `x`
+    let tidy = |term: Expr| {
+        CanonicalTerm::from_expr(&term)
+            .expect("Generated terms are small")
+            .to_expr(NegativeExponents::AsFraction)
+    };
+    let ExtractDiffSquares { a, b, a2, b2 } = squares;
+    let squares = ExtractDiffSquares {
+        a: tidy(a),
+        b: tidy(b),
+        a2: tidy(a2),
+        b2: tidy(b2),
+    };
+    let ExtractDiffSquares { a, b, a2, b2 } = &squares;
+    let diff_squares = squares.difference();
+    let division = |lhs: Expr, rhs: Expr| Expr::Division {
+        lhs: lhs.into(),
+        rhs: rhs.into(),
+    };
+
+    // The binomial which gets cancelled, the other one stays in the solution
+    let is_sum = rng.random_bool(0.5);
+    let cancelled = squares.binomial(is_sum);
+    let remaining = squares.binomial(!is_sum);
+    let cancelled_condition = binomial_condition(a, b, is_sum);
+    let hints = vec![
+        "Rozlož čitatel i jmenovatel podle vzorců a^2 - b^2 a (a ± b)^2.".to_string(),
+        format!("{a2} = ({a})^2 a {b2} = ({b})^2"),
+        format!("Závorka ({cancelled}) je v čitateli i ve jmenovateli, tu zkrať."),
+    ];
+
+    let (assignment, solution, mut conditions) = match rng.random_range(0..3) {
+        0 => (
+            division(diff_squares, cancelled),
+            remaining,
+            vec![cancelled_condition],
+        ),
+        1 => (
+            division(squares.square_of_binomial(is_sum), diff_squares),
+            division(cancelled, remaining),
+            vec![cancelled_condition, binomial_condition(a, b, !is_sum)],
+        ),
+        _ => (
+            division(diff_squares, squares.square_of_binomial(is_sum)),
+            division(remaining, cancelled),
+            vec![cancelled_condition],
+        ),
+    };
+    // `a^2 + 4` is never zero, so it does not need a condition
+    conditions.retain(|condition| {
+        let polynomial = condition
+            .polynomial()
+            .expect("Generated conditions are polynomials");
+        !polynomial.has_no_real_root()
+    });
+
+    RationalExpression {
+        assignment,
+        solution,
+        conditions,
+        hints,
+    }
+}
+
+enum Verdict {
+    Correct,
+    NotSimplified,
+    Wrong,
+}
+
+fn check_simplified(answer: &Expr, solution: &Expr) -> Verdict {
+    let answer = CanonicalFraction::from_expr(answer);
+    let solution = CanonicalFraction::from_expr(solution).expect("Solution is a valid fraction");
+    let Some(answer) = answer else {
+        return Verdict::Wrong;
+    };
+    if answer.is_equal(&solution) != Some(true) {
+        return Verdict::Wrong;
+    }
+
+    let is_larger = answer.numerator().degree() > solution.numerator().degree()
+        || answer.denominator().degree() > solution.denominator().degree();
+    if is_larger {
+        Verdict::NotSimplified
+    } else {
+        Verdict::Correct
+    }
+}
+
+fn parse_condition(input: &str) -> Result<Condition, String> {
+    let separator = ["≠", "!=", "=/=", "<>"]
+        .into_iter()
+        .find(|separator| input.contains(separator))
+        .ok_or_else(|| format!("V \"{input}\" chybí ≠ (můžeš psát i !=)"))?;
+    let (lhs, rhs) = input.split_once(separator).unwrap();
+    let lhs = Expr::parse(lhs).map_err(|err| err.to_string())?;
+    let rhs = Expr::parse(rhs).map_err(|err| err.to_string())?;
+    Ok(Condition { lhs, rhs })
+}
+
+fn get_conditions(io: &mut dyn Io, question: &str) -> Answer<Vec<Condition>> {
+    get_input(
+        io,
+        question,
+        Some("podmínky oddělené čárkou, např. x ≠ 3, a ≠ -b, nebo nic, když žádné nejsou"),
+        |input| {
+            if input.is_empty() {
+                return Ok(vec![]);
+            }
+            input
+                .split([',', ';'])
+                .map(|part| parse_condition(part.trim()))
+                .collect::<Result<Vec<_>, _>>()
+        },
+    )
+}
+
+/*
+    What `polynomial ≠ 0` means: every variable of the common factor on its own
+    and the rest, e.g. `x ≠ 0` and `3·x^2 + 2 ≠ 0` for `3·x^4 + 2·x^2 ≠ 0`.
+    The parts which are never zero are left out, like `3·x^2 + 2` here.
+*/
+fn condition_parts(polynomial: &CanonicalPolynomial) -> Vec<CanonicalPolynomial> {
+    let mut parts = vec![];
+    let mut rest = polynomial.clone();
+    if let Some(factor) = polynomial.common_factor() {
+        let mut vars = factor
+            .to_expr(NegativeExponents::Keep)
+            .variable_occurrences();
+        vars.sort();
+        vars.dedup();
+        parts.extend(vars.into_iter().map(|var| {
+            let var = CanonicalTerm::from_expr(&Expr::Variable { symbol: var });
+            CanonicalPolynomial::from_term(var.expect("Variable is a monomial"))
+        }));
+        rest = polynomial.div_term(&factor).expect("Factor is not zero");
+    }
+    if rest.degree() > 0 && !rest.has_no_real_root() {
+        parts.push(rest);
+    }
+    parts
+}
+
+/// Index of every stated part used in a product which is `part`, up to a constant.
+fn find_product(stated: &[CanonicalPolynomial], part: &CanonicalPolynomial) -> Option<Vec<usize>> {
+    // There are only a few stated conditions, so every combination can be tried
+    let count = stated.len().min(10);
+    let mut masks: Vec<u32> = (1..1 << count).collect();
+    masks.sort_by_key(|mask| mask.count_ones());
+    masks.into_iter().find_map(|mask| {
+        let used: Vec<usize> = (0..count).filter(|idx| mask & (1 << idx) != 0).collect();
+        let product = used.iter().try_fold(
+            CanonicalPolynomial::from_term(CanonicalTerm::from_expr(&Expr::Number(1))?),
+            |acc, idx| acc.mul(&stated[*idx]),
+        )?;
+        product.is_constant_multiple_of(part).then_some(used)
+    })
+}
+
+fn check_conditions(io: &mut dyn Io, stated: &[Condition], required: &[Condition]) -> bool {
+    // `x ≠ 2, x ≠ -2` together give `x^2 ≠ 4`, so the parts of all the stated
+    // conditions are put together, each remembers its condition
+    let mut stated_parts = vec![];
+    let mut part_owners = vec![];
+    for (idx, condition) in stated.iter().enumerate() {
+        let parts = condition
+            .polynomial()
+            .map(|polynomial| condition_parts(&polynomial))
+            .unwrap_or_default();
+        part_owners.extend(parts.iter().map(|_| idx));
+        stated_parts.extend(parts);
+    }
+
+    let mut is_used = vec![false; stated_parts.len()];
+    let mut is_correct = true;
+    for condition in required {
+        let polynomial = condition
+            .polynomial()
+            .expect("Generated conditions are polynomials");
+        let mut is_covered = true;
+        for part in condition_parts(&polynomial) {
+            match find_product(&stated_parts, &part) {
+                Some(used) => used.into_iter().for_each(|idx| is_used[idx] = true),
+                None => is_covered = false,
+            }
+        }
+        if !is_covered {
+            is_correct = false;
+            let Condition { lhs, rhs } = condition;
+            writeln!(io, "{} {lhs} ≠ {rhs}.", "Chybí podmínka".red());
+        }
+    }
+    for (idx, condition) in stated.iter().enumerate() {
+        let mut parts = part_owners
+            .iter()
+            .zip(&is_used)
+            .filter(|(owner, _)| **owner == idx);
+        let is_needed = parts.clone().next().is_some() && parts.all(|(_, is_used)| *is_used);
+        if !is_needed {
+            is_correct = false;
+            let Condition { lhs, rhs } = condition;
+            writeln!(io, "{} {lhs} ≠ {rhs} tu není potřeba.", "Podmínka".red());
+        }
+    }
+    is_correct
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: RationalExpression) -> Answer<bool> {
+    let RationalExpression {
+        assignment,
+        solution,
+        conditions,
+        hints,
+    } = excercise;
+    writeln!(io, "Zjednoduš lomený výraz a urči, kdy má smysl:");
+    writeln!(io, "{assignment}");
+    writeln!(io);
+
+    io.hints().offer(hints);
+    let mut is_solved = false;
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_expr(io, "Zadej zjednodušený výraz")?;
+        match check_simplified(&answer, &solution) {
+            Verdict::Correct => {
+                writeln!(io, "{}", "Správně!".green().bold());
+                is_solved = true;
+                break;
+            }
+            Verdict::NotSimplified => writeln!(
+                io,
+                "{}",
+                "Rovná se to zadání, ale ještě se to dá zkrátit. Rozlož čitatel i jmenovatel."
+                    .yellow()
+            ),
+            Verdict::Wrong => {
+                writeln!(io, "{}", "Tohle se zadání nerovná.".red());
+                if let Some(mistake) = diagnose(&answer, &assignment) {
+                    writeln!(io, "{}", mistake.explanation().yellow());
+                }
+                // Seeded for every answer, a repeated answer is shown the same values
+                let mut rng = Random::from_seed(0);
+                let points = compare_at_points(&assignment, &answer, Confidence::NORMAL, &mut rng);
+                if let Equivalence::Different { point, lhs, rhs } = points {
+                    writeln!(
+                        io,
+                        "Například pro {point} má zadání hodnotu {lhs}, ale tvůj výraz {rhs}."
+                    );
+                }
+            }
+        }
+    }
+    if !is_solved {
+        writeln!(io, "Zjednodušený výraz je: {solution}");
+    }
+    writeln!(io);
+
+    io.hints().offer(vec![
+        "Jmenovatel nesmí být nula, rozlož ho na součin závorek.".to_string(),
+        "Každá závorka ve jmenovateli zadání dává jednu podmínku.".to_string(),
+        "Závorka, která nikdy není nula, jako a^2 + 4, podmínku nedává.".to_string(),
+    ]);
+    let stated = get_conditions(io, "Zadej podmínky, kdy má výraz smysl")?;
+    let are_conditions_correct = check_conditions(io, &stated, &conditions);
+    if are_conditions_correct {
+        writeln!(io, "{}", "Podmínky jsou správně!".green().bold());
+    }
+
+    writeln!(io);
+    writeln!(io, "Tohle je řešení:");
+    if conditions.is_empty() {
+        writeln!(
+            io,
+            "{assignment} = {solution},   jmenovatel nikdy není nula"
+        );
+    } else {
+        let conditions = conditions
+            .iter()
+            .map(|Condition { lhs, rhs }| format!("{lhs} ≠ {rhs}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(io, "{assignment} = {solution},   {conditions}");
+    }
+    Ok(is_solved && are_conditions_correct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    fn is_accepted(stated: &str, required: &str) -> bool {
+        let parse = |input: &str| {
+            input
+                .split(',')
+                .filter(|part| !part.is_empty())
+                .map(|part| parse_condition(part.trim()).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let mut io = ScriptedIo::new(&[]);
+        check_conditions(&mut io, &parse(stated), &parse(required))
+    }
+
+    #[test]
+    fn conditions_can_be_stated_factored() {
+        assert!(is_accepted("y ≠ 2, y ≠ -2", "y^2 ≠ 4"));
+        assert!(is_accepted("x ≠ 1/2, x ≠ -1/2", "4x^2 ≠ 1"));
+        assert!(is_accepted("2x ≠ 5", "x ≠ 5/2"));
+        assert!(is_accepted("x ≠ 0", "3x^4 + 2x^2 ≠ 0"));
+        assert!(is_accepted("x^2 ≠ 0", "3x^4 + 2x^2 ≠ 0"));
+        assert!(!is_accepted("x ≠ 2", "x^2 ≠ 4"));
+        assert!(!is_accepted("x ≠ 2, x ≠ -2, x ≠ 3", "x^2 ≠ 4"));
+    }
+
+    #[test]
+    fn never_zero_needs_no_condition() {
+        assert!(is_accepted("", "x^2 ≠ -4"));
+        assert!(!is_accepted("x^2 ≠ -4", "x^2 ≠ -4"));
+        assert!(!is_accepted("", "x^2 ≠ -y^2"));
+    }
+}
@@ -0,0 +1,365 @@
+use colored::*;
+
+use crate::ast::canonical::{gcd, CanonicalTerm};
+use crate::ast::equivalence::{compare_at_points, Confidence, Equivalence};
+use crate::ast::{Expr, NegativeExponents};
+use crate::input::*;
+use crate::random::Random;
+use crate::{generate_term, TermConfig};
+
+const MAX_ATTEMPTS: u32 = 3;
+const SQUARE_FREE: [i64; 9] = [2, 3, 5, 6, 7, 10, 11, 13, 15];
+const CUBE_FREE: [i64; 7] = [2, 3, 4, 5, 6, 7, 9];
+/// Answers which can not be compared exactly are compared numerically at this many points.
+const SAMPLE_POINTS: u32 = 5;
+
+#[derive(Clone, Copy)]
+enum Task {
+    /// `√72 = 6·√2`
+    Extract,
+    /// `3/√2 = 3·√2/2`
+    Rationalize,
+}
+
+/*
+ * Represents the excercise:
+ *      `assignment` = `solution`, e.g.
+ *      √(72·a^3) = 6·a·√(2·a)
+ *      2/(√3 - 1) = √3 + 1
+ */
+pub struct RootExcercise {
+    task: Task,
+    assignment: Expr,
+    solution: Expr,
+}
+
+fn sqrt(radicand: Expr) -> Expr {
+    Expr::Root {
+        degree: 2,
+        radicand: radicand.into(),
+    }
+}
+
+fn pick(rng: &mut Random, values: &[i64]) -> i64 {
+    values[rng.random_range(0..values.len())]
+}
+
+fn generate_extraction(rng: &mut Random, config: &TermConfig) -> RootExcercise {
+    let degree = if rng.random_bool(0.2) { 3 } else { 2 };
+    let (outside, inside) = if degree == 2 {
+        (rng.random_range(2..=6i64), pick(rng, &SQUARE_FREE))
+    } else {
+        (rng.random_range(2..=4i64), pick(rng, &CUBE_FREE))
+    };
+
+    // Only the variables come from the term, the number is picked above
+    let config = TermConfig {
+        coefficients: 1..=1,
+        exponents: 1..=2 * degree + 1,
+        negative_coefficients: false,
+        fractional_coefficients: false,
+        ..config.clone()
+    };
+    let radicand = Expr::Multiplication(vec![
+        Expr::integer(outside.pow(degree) * inside).unwrap(),
+        generate_term(rng, &config),
+    ]);
+    let radicand = CanonicalTerm::from_expr(&radicand)
+        .expect("Generated radicand is a monomial")
+        .to_expr(NegativeExponents::AsFraction);
+
+    let assignment = Expr::Root {
+        degree,
+        radicand: radicand.into(),
+    };
+    let solution = assignment.simplify().result().clone();
+    RootExcercise {
+        task: Task::Extract,
+        assignment,
+        solution,
+    }
+}
+
+fn division(lhs: Expr, rhs: i64) -> Expr {
+    if rhs == 1 {
+        return lhs;
+    }
+    Expr::Division {
+        lhs: lhs.into(),
+        rhs: Expr::integer(rhs).unwrap().into(),
+    }
+}
+
+fn generate_rationalization(rng: &mut Random) -> RootExcercise {
+    let numerator = rng.random_range(1..=12i64);
+    let radicand = pick(rng, &SQUARE_FREE);
+    let root = sqrt(Expr::integer(radicand).unwrap());
+
+    let (denominator, solution) = if rng.random_bool(0.5) {
+        // p/(k·√m) = p·√m/(k·m)
+        let k = rng.random_range(1..=4i64);
+        let divisor = gcd(numerator as u64, (k * radicand) as u64) as i64;
+        let top = Expr::linear_combination(vec![(numerator / divisor, vec![root.clone()])]);
+        let denominator = Expr::linear_combination(vec![(k, vec![root.clone()])]);
+        (
+            denominator.unwrap(),
+            division(top.unwrap(), k * radicand / divisor),
+        )
+    } else {
+        // p/(√m + s·q) = p·(√m - s·q)/(m - q^2)
+        let q = loop {
+            let q = rng.random_range(1..=4i64);
+            if q * q != radicand {
+                break q;
+            }
+        };
+        let sign = if rng.random_bool(0.5) { 1 } else { -1 };
+        let difference = radicand - q * q;
+        let divisor = gcd(numerator as u64, difference.unsigned_abs()) as i64 * difference.signum();
+        let top = Expr::linear_combination(vec![
+            (numerator / divisor, vec![root.clone()]),
+            (-sign * q * numerator / divisor, vec![]),
+        ]);
+        let denominator =
+            Expr::linear_combination(vec![(1, vec![root.clone()]), (sign * q, vec![])]);
+        (
+            denominator.unwrap(),
+            division(top.unwrap(), difference / divisor),
+        )
+    };
+
+    let assignment = Expr::Division {
+        lhs: Expr::integer(numerator).unwrap().into(),
+        rhs: denominator.into(),
+    };
+    RootExcercise {
+        task: Task::Rationalize,
+        assignment,
+        solution,
+    }
+}
+
+pub fn generate_root_excercise(rng: &mut Random, config: &TermConfig) -> RootExcercise {
+    if rng.random_bool(0.5) {
+        generate_extraction(rng, config)
+    } else {
+        generate_rationalization(rng)
+    }
+}
+
+/// Compares the values with every variable set to a random positive number, the roots
+/// of the variables do not have to be exact.
+fn approximately_equal(lhs: &Expr, rhs: &Expr, rng: &mut Random) -> bool {
+    let mut variables = lhs.variable_occurrences();
+    variables.extend(rhs.variable_occurrences());
+    variables.sort();
+    variables.dedup();
+    (0..SAMPLE_POINTS).all(|_| {
+        let (lhs, rhs) = variables
+            .iter()
+            .fold((lhs.clone(), rhs.clone()), |(lhs, rhs), var| {
+                let value = Expr::Number(rng.random_range(2..=12));
+                (lhs.substitute(*var, &value), rhs.substitute(*var, &value))
+            });
+        match (lhs.approximate(), rhs.approximate()) {
+            (Some(lhs), Some(rhs)) => (lhs - rhs).abs() <= 1e-9 * lhs.abs().max(1.0),
+            _ => false,
+        }
+    })
+}
+
+/// Every root in the expression, together with whether it is in a denominator.
+fn find_roots<'a>(expr: &'a Expr, in_denominator: bool, found: &mut Vec<(&'a Expr, bool)>) {
+    match expr {
+        Expr::Addition(exprs) | Expr::Multiplication(exprs) => {
+            for expr in exprs {
+                find_roots(expr, in_denominator, found);
+            }
+        }
+        Expr::Division { lhs, rhs } => {
+            find_roots(lhs, in_denominator, found);
+            find_roots(rhs, true, found);
+        }
+        Expr::UnaryMinus(inner) => find_roots(inner, in_denominator, found),
+        Expr::Exp { base, exp } => {
+            let is_negative = exp.as_integer().is_some_and(|exp| exp < 0);
+            find_roots(base, in_denominator || is_negative, found);
+            find_roots(exp, in_denominator, found);
+        }
+        Expr::Root {
+            degree: _,
+            radicand,
+        } => {
+            found.push((expr, in_denominator));
+            find_roots(radicand, in_denominator, found);
+        }
+        Expr::Number(_) | Expr::Variable { symbol: _ } => {}
+    }
+}
+
+enum Verdict {
+    Correct,
+    NotSimplified,
+    /// With a counter-example, if one was found.
+    Wrong(Equivalence),
+}
+
+fn check_answer(answer: &Expr, excercise: &RootExcercise) -> Verdict {
+    // The same points every time, so the same answer gets the same counter-example
+    let mut rng = Random::from_seed(0);
+    let exact = compare_at_points(&excercise.solution, answer, Confidence::NORMAL, &mut rng);
+    let is_equal = match exact {
+        Equivalence::Equal => true,
+        Equivalence::Different { .. } => false,
+        // Too large numbers or a root of a sum in a denominator
+        Equivalence::Unknown => approximately_equal(&excercise.solution, answer, &mut rng),
+    };
+    if !is_equal {
+        return Verdict::Wrong(exact);
+    }
+
+    let mut roots = vec![];
+    find_roots(answer, false, &mut roots);
+    let is_simplified = roots.iter().all(|(root, in_denominator)| {
+        let is_extracted = root.simplify().steps.is_empty();
+        match excercise.task {
+            Task::Extract => is_extracted,
+            Task::Rationalize => is_extracted && !in_denominator,
+        }
+    });
+    if is_simplified {
+        Verdict::Correct
+    } else {
+        Verdict::NotSimplified
+    }
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: RootExcercise) -> Answer<bool> {
+    let RootExcercise {
+        task,
+        assignment,
+        solution,
+    } = &excercise;
+    match task {
+        Task::Extract => writeln!(io, "Částečně odmocni, proměnné jsou nezáporné:"),
+        Task::Rationalize => writeln!(io, "Usměrni zlomek, ve jmenovateli nesmí zůstat odmocnina:"),
+    }
+    writeln!(io, "{assignment}");
+    writeln!(io);
+
+    io.hints().offer(match task {
+        Task::Extract => vec![
+            "Rozlož číslo pod odmocninou na součin, ve kterém je co největší mocnina.".to_string(),
+            "√(a^2·b) = a·√b, u proměnných vytkni sudé exponenty.".to_string(),
+        ],
+        Task::Rationalize => vec![
+            "Rozšiř zlomek tak, aby ve jmenovateli zmizela odmocnina.".to_string(),
+            "√a·√a = a, a u jmenovatele a ± √b rozšiř výrazem a ∓ √b.".to_string(),
+        ],
+    });
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = get_expr(io, "Zadej výsledek (odmocninu piš jako √ nebo sqrt)")?;
+        match check_answer(&answer, &excercise) {
+            Verdict::Correct => {
+                writeln!(io, "{}", "Správně!".green().bold());
+                return Ok(true);
+            }
+            Verdict::NotSimplified => match task {
+                Task::Extract => writeln!(
+                    io,
+                    "{}",
+                    "Rovná se to, ale z odmocniny jde ještě něco vytknout.".yellow()
+                ),
+                Task::Rationalize => writeln!(
+                    io,
+                    "{}",
+                    "Rovná se to, ale ve jmenovateli je pořád odmocnina.".yellow()
+                ),
+            },
+            Verdict::Wrong(exact) => {
+                writeln!(io, "{}", "Tohle se zadání nerovná.".red());
+                if let Equivalence::Different { point, lhs, rhs } = exact {
+                    writeln!(
+                        io,
+                        "Například pro {point} má zadání hodnotu {lhs}, ale tvůj výraz {rhs}."
+                    );
+                }
+            }
+        }
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    writeln!(io, "{assignment} = {solution}");
+    if let Task::Extract = task {
+        writeln!(io);
+        writeln!(io, "Postup:");
+        writeln!(io, "{}", assignment.simplify());
+    }
+    writeln!(io);
+    writeln!(
+        io,
+        "V LaTeXu: {} = {}",
+        assignment.to_latex(),
+        solution.to_latex()
+    );
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extraction(assignment: &str) -> RootExcercise {
+        let assignment = Expr::parse(assignment).unwrap();
+        let solution = assignment.simplify().result().clone();
+        RootExcercise {
+            task: Task::Extract,
+            assignment,
+            solution,
+        }
+    }
+
+    fn verdict(excercise: &RootExcercise, answer: &str) -> Verdict {
+        check_answer(&Expr::parse(answer).unwrap(), excercise)
+    }
+
+    #[test]
+    fn answers_with_roots_are_compared_exactly() {
+        let excercise = extraction("√(12a^2)");
+        assert!(matches!(verdict(&excercise, "2a√3"), Verdict::Correct));
+        assert!(matches!(
+            verdict(&excercise, "a√12"),
+            Verdict::NotSimplified
+        ));
+        assert!(matches!(
+            verdict(&excercise, "4√3"),
+            Verdict::Wrong(Equivalence::Different { .. })
+        ));
+        assert!(matches!(verdict(&excercise, "2√3"), Verdict::Wrong(_)));
+    }
+
+    #[test]
+    fn any_variables_can_be_used() {
+        let excercise = extraction("√(8x^3·y^2)");
+        assert!(matches!(
+            verdict(&excercise, "2x·y·√(2x)"),
+            Verdict::Correct
+        ));
+        assert!(matches!(verdict(&excercise, "2x·√(2x)"), Verdict::Wrong(_)));
+    }
+
+    #[test]
+    fn rationalized_answer_has_no_root_in_the_denominator() {
+        let excercise = RootExcercise {
+            task: Task::Rationalize,
+            assignment: Expr::parse("2/(√3 - 1)").unwrap(),
+            solution: Expr::parse("√3 + 1").unwrap(),
+        };
+        assert!(matches!(verdict(&excercise, "1 + √3"), Verdict::Correct));
+        assert!(matches!(
+            verdict(&excercise, "2/(√3 - 1)"),
+            Verdict::NotSimplified
+        ));
+        assert!(matches!(verdict(&excercise, "√3 - 1"), Verdict::Wrong(_)));
+    }
+}
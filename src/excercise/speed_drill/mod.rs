@@ -0,0 +1,156 @@
+use colored::*;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::input::*;
+use crate::random::Random;
+use crate::stats::SessionStats;
+
+const ROUND_DURATION: Duration = Duration::from_secs(60);
+/// More than anybody can solve in one round.
+const FACT_COUNT: usize = 200;
+
+#[derive(Clone, Copy)]
+enum Fact {
+    Multiplication(u32, u32),
+    Addition(u32, u32),
+    /// Dividend and divisor, the division has no remainder.
+    Division(u32, u32),
+}
+
+impl Fact {
+    fn result(self) -> u32 {
+        match self {
+            Fact::Multiplication(a, b) => a * b,
+            Fact::Addition(a, b) => a + b,
+            Fact::Division(a, b) => a / b,
+        }
+    }
+}
+
+impl Fact {
+    fn hint(self) -> String {
+        match self {
+            Fact::Multiplication(a, b) => format!("{a}·{b} = {a}·{} + {a}", b - 1),
+            Fact::Addition(a, b) => format!("{a} + {b} = {} + {}", a + b - b % 10, b % 10),
+            Fact::Division(a, b) => format!("Kolikrát se vejde {b} do {a}?"),
+        }
+    }
+}
+
+impl fmt::Display for Fact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fact::Multiplication(a, b) => write!(f, "{a}·{b}"),
+            Fact::Addition(a, b) => write!(f, "{a} + {b}"),
+            Fact::Division(a, b) => write!(f, "{a} : {b}"),
+        }
+    }
+}
+
+/*
+ * Represents the excercise:
+ *      as many of `facts` as the student manages in `ROUND_DURATION`, e.g.
+ *      7·8 = 56,   34 + 47 = 81,   42 : 6 = 7,   ...
+ */
+pub struct SpeedRound {
+    facts: Vec<Fact>,
+}
+
+fn generate_fact(rng: &mut Random) -> Fact {
+    match rng.random_range(0..3) {
+        0 => Fact::Multiplication(rng.random_range(2..=10), rng.random_range(2..=10)),
+        1 => Fact::Addition(rng.random_range(10..=99), rng.random_range(10..=99)),
+        _ => {
+            // Small divisors like in the written division
+            let divisor = rng.random_range(3..9);
+            Fact::Division(divisor * rng.random_range(2..=12), divisor)
+        }
+    }
+}
+
+pub fn generate_speed_round(rng: &mut Random) -> SpeedRound {
+    SpeedRound {
+        facts: (0..FACT_COUNT).map(|_| generate_fact(rng)).collect(),
+    }
+}
+
+pub fn solve_excercise(
+    io: &mut dyn Io,
+    round: SpeedRound,
+    stats: &mut SessionStats,
+) -> Answer<bool> {
+    writeln!(
+        io,
+        "Máš {} sekund, spočítej co nejvíc příkladů.",
+        ROUND_DURATION.as_secs()
+    );
+    wait_for_enter(io, "Odpočet začne po stisknutí enteru")?;
+
+    let deadline = Instant::now() + ROUND_DURATION;
+    let mut score = 0;
+    let mut answered = 0;
+    let mut mistakes = vec![];
+    let mut is_timed_out = false;
+    for fact in round.facts {
+        let remaining = deadline.saturating_duration_since(Instant::now()).as_secs();
+        io.hints().offer(vec![fact.hint()]);
+        let question = format!("{fact} = ?   (zbývá {remaining} s, skóre {score})");
+        let Some(answer) = get_input_until(io, &question, None, deadline, |input| {
+            input
+                .parse::<u32>()
+                .map_err(|_| format!("\"{input}\" není číslo."))
+        })?
+        else {
+            is_timed_out = true;
+            break;
+        };
+
+        answered += 1;
+        if answer == fact.result() {
+            score += 1;
+            writeln!(io, "{}", "✓".green().bold());
+        } else {
+            writeln!(io, "{}", format!("✗ {fact} = {}", fact.result()).red());
+            stats.record_wrong_fact(&fact.to_string());
+            mistakes.push(fact);
+        }
+    }
+
+    if is_timed_out {
+        writeln!(io, "{}", "Čas vypršel!".bold());
+        // Takes the answer which was being typed, so that it does not start the next round
+        wait_for_enter(io, "Pro vyhodnocení dej enter")?;
+    }
+    writeln!(io, "Skóre: {score} správně z {answered}.");
+    let is_solved = answered > 0 && mistakes.is_empty();
+    if !mistakes.is_empty() {
+        writeln!(io, "Špatně:");
+        for fact in mistakes {
+            writeln!(io, "  {fact} = {}", fact.result());
+        }
+    }
+    Ok(is_solved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::console::ScriptedIo;
+
+    #[test]
+    fn late_answer_does_not_start_the_next_round() {
+        let round = generate_speed_round(&mut Random::from_seed(1));
+        let first = round.facts[0].result().to_string();
+        let answers = ["", &first, ScriptedIo::TIMED_OUT, "4", "q"];
+        let mut io = ScriptedIo::new(&answers);
+        let mut stats = SessionStats::default();
+        let result = solve_excercise(&mut io, round, &mut stats);
+        assert_eq!(result, Ok(true));
+        let output = io.output();
+        assert!(output.contains("Čas vypršel!"));
+        assert!(output.contains("Skóre: 1 správně z 1."));
+        assert!(!output.contains("Zatím žádné chyby."));
+        assert_eq!(io.read_line().as_deref(), Some("q"));
+    }
+}
@@ -0,0 +1,182 @@
+use colored::*;
+
+use crate::ast::rational::Rational;
+use crate::input::number::format_decimal;
+use crate::input::*;
+use crate::random::Random;
+use crate::units::{Quantity, Unit, UNITS};
+
+const MAX_ATTEMPTS: u32 = 3;
+/// Enough for `mm³` in `dm³`, every generated result has at most this many decimal places.
+const DECIMALS: usize = 6;
+/// Converting over more orders of magnitude only tests counting zeros.
+const MAX_FACTOR: f64 = 1_000_000.0;
+
+/*
+ * Represents the excercise:
+ *      `value` `from` = ? `to`, e.g.
+ *      3,5 m² = ? cm²   =>   35 000 cm²
+ */
+pub struct UnitConversion {
+    value: f64,
+    from: Unit,
+    to: Unit,
+}
+
+impl UnitConversion {
+    fn answer(&self) -> f64 {
+        self.from.convert(self.value, self.to)
+    }
+
+    /// The factor between the larger and the smaller unit, e.g. `1 m² = 10 000 cm²`.
+    fn relation(&self) -> (Unit, f64, Unit) {
+        let factor = self.from.factor_to(self.to).to_f64();
+        if factor >= 1.0 {
+            (self.from, factor, self.to)
+        } else {
+            (self.to, 1.0 / factor, self.from)
+        }
+    }
+}
+
+/// `3,5` from `35` and `1` decimal place.
+fn decimal(digits: i64, places: u32) -> Rational {
+    Rational::new(digits, 10i64.pow(places)).expect("Non-zero denominator")
+}
+
+fn has_short_decimal_expansion(value: Rational) -> bool {
+    let shift = Rational::integer(10i64.pow(DECIMALS as u32));
+    value
+        .mul(shift)
+        .is_some_and(|shifted| shifted.denominator() == 1)
+}
+
+pub fn generate_unit_conversion(rng: &mut Random) -> UnitConversion {
+    loop {
+        let quantity = Quantity::ALL[rng.random_range(0..Quantity::ALL.len())];
+        let units: Vec<Unit> = UNITS
+            .iter()
+            .copied()
+            .filter(|unit| unit.quantity == quantity)
+            .collect();
+        let from = units[rng.random_range(0..units.len())];
+        let to = units[rng.random_range(0..units.len())];
+        let factor = from.factor_to(to);
+        if from == to || factor.to_f64() > MAX_FACTOR || factor.to_f64() < 1.0 / MAX_FACTOR {
+            continue;
+        }
+
+        let value = decimal(rng.random_range(1..=999), rng.random_range(0..=2));
+        let Some(result) = value.mul(factor) else {
+            continue;
+        };
+        if has_short_decimal_expansion(result) {
+            return UnitConversion {
+                value: value.to_f64(),
+                from,
+                to,
+            };
+        }
+    }
+}
+
+fn is_close(guess: f64, answer: f64) -> bool {
+    (guess - answer).abs() <= 1e-9 * answer.abs().max(1.0)
+}
+
+/// Explains the typical mistakes, `None` when the guess does not look like any of them.
+fn diagnose(excercise: &UnitConversion, guess: f64) -> Option<String> {
+    let UnitConversion { value, from, to } = *excercise;
+    let factor = from.factor_to(to).to_f64();
+    if is_close(guess, value / factor) {
+        return Some(if factor >= 1.0 {
+            "Převádíš na menší jednotku, takže čísla bude víc. Místo dělení se násobí.".to_string()
+        } else {
+            "Převádíš na větší jednotku, takže čísla bude méně. Místo násobení se dělí.".to_string()
+        });
+    }
+
+    let is_power = from.power > 1 && from.power == to.power;
+    if is_power {
+        let linear_factor = factor.powf(1.0 / f64::from(from.power));
+        let is_linear_guess =
+            is_close(guess, value * linear_factor) || is_close(guess, value / linear_factor);
+        if is_linear_guess {
+            let (larger, factor, smaller) = excercise.relation();
+            let length_factor = factor.powf(1.0 / f64::from(from.power)).round();
+            let what = if from.power == 2 { "plochy" } else { "objemu" };
+            let length = |unit: Unit| unit.symbol.trim_end_matches(['²', '³']).to_string();
+            return Some(format!(
+                "U jednotek {what} se převodní číslo umocňuje: 1 {} = {} {}, ale 1 {larger} = {}{} {smaller} = {} {smaller}.",
+                length(larger),
+                format_decimal(length_factor, 0),
+                length(smaller),
+                format_decimal(length_factor, 0),
+                if from.power == 2 { '²' } else { '³' },
+                format_decimal(factor, 0),
+            ));
+        }
+    }
+    None
+}
+
+fn print_solution(io: &mut dyn Io, excercise: &UnitConversion) {
+    let (larger, factor, smaller) = excercise.relation();
+    let UnitConversion { value, from, to } = *excercise;
+    writeln!(io, "1 {larger} = {} {smaller}", format_decimal(factor, 0));
+    let operation = if from == larger { "·" } else { ":" };
+    writeln!(
+        io,
+        "{} {from} = {} {operation} {} {to} = {} {to}",
+        format_decimal(value, DECIMALS),
+        format_decimal(value, DECIMALS),
+        format_decimal(factor, 0),
+        format_decimal(excercise.answer(), DECIMALS)
+    );
+}
+
+pub fn solve_excercise(io: &mut dyn Io, excercise: UnitConversion) -> Answer<bool> {
+    let UnitConversion { value, from, to } = excercise;
+    writeln!(io, "{} {from} = ? {to}", format_decimal(value, DECIMALS));
+    writeln!(io);
+
+    let (larger, factor, smaller) = excercise.relation();
+    let direction = if from == larger {
+        "Převádíš na menší jednotku, číslo bude větší."
+    } else {
+        "Převádíš na větší jednotku, číslo bude menší."
+    };
+    io.hints().offer(vec![
+        direction.to_string(),
+        format!("1 {larger} = {} {smaller}", format_decimal(factor, 0)),
+        format!(
+            "{} číslem {}.",
+            if from == larger { "Vynásob" } else { "Vyděl" },
+            format_decimal(factor, 0)
+        ),
+    ]);
+
+    let answer = excercise.answer();
+    for _ in 0..MAX_ATTEMPTS {
+        let guess = get_measurement(io, "Výsledek", to)?;
+        if is_close(guess, answer) {
+            writeln!(io, "{}", "Správně!".green().bold());
+            return Ok(true);
+        }
+        writeln!(io, "{}", "Špatně.".red());
+        match diagnose(&excercise, guess) {
+            Some(message) => writeln!(io, "{}", message.yellow()),
+            None => {
+                let message = format!(
+                    "Pamatuj: 1 {larger} = {} {smaller}",
+                    format_decimal(factor, 0)
+                );
+                writeln!(io, "{}", message.yellow());
+            }
+        }
+    }
+
+    writeln!(io, "Tohle je řešení:");
+    print_solution(io, &excercise);
+    Ok(false)
+}
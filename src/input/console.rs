@@ -0,0 +1,162 @@
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Instant;
+
+use super::Hints;
+
+/*
+    Where the questions go and the answers come from.
+    The excercises write to it with `writeln!(io, ...)`, so that a whole session
+    can be replayed from a script in the tests as well as played in the terminal.
+*/
+pub trait Io {
+    fn write_fmt(&mut self, args: fmt::Arguments);
+
+    /// Hints for the question being asked, the excercises offer them before asking.
+    /// A question without them offers none, the hints of the one before are dropped.
+    fn hints(&mut self) -> &mut Hints;
+
+    /// `None` at the end of the input.
+    fn read_line(&mut self) -> Option<String>;
+
+    /// Like `read_line`, but gives up at `deadline`.
+    fn read_line_until(&mut self, deadline: Instant) -> Result<Option<String>, TimedOut> {
+        let _ = deadline;
+        Ok(self.read_line())
+    }
+
+    /// Makes sure the question is visible before the student starts typing.
+    fn flush(&mut self) {}
+
+    fn prompt(&mut self, question: &str, expected: Option<&str>) {
+        self.hints().ask(question);
+        writeln!(self, "Prompt: {question}");
+        if let Some(expected) = expected {
+            write!(self, "(Očekávám {expected}): ");
+        }
+        self.flush();
+    }
+}
+
+/// The student did not answer in time.
+pub struct TimedOut;
+
+/// Standard input and output. The input is read on a separate thread so that a question can time out.
+pub struct TerminalIo {
+    lines: Receiver<String>,
+    hints: Hints,
+}
+
+impl TerminalIo {
+    pub fn new() -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            lines,
+            hints: Hints::default(),
+        }
+    }
+}
+
+impl Io for TerminalIo {
+    fn write_fmt(&mut self, args: fmt::Arguments) {
+        print!("{args}");
+    }
+
+    fn hints(&mut self) -> &mut Hints {
+        &mut self.hints
+    }
+
+    fn read_line(&mut self) -> Option<String> {
+        self.lines.recv().ok()
+    }
+
+    fn read_line_until(&mut self, deadline: Instant) -> Result<Option<String>, TimedOut> {
+        let timeout = deadline
+            .checked_duration_since(Instant::now())
+            .ok_or(TimedOut)?;
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(Some(line)),
+            Err(RecvTimeoutError::Timeout) => {
+                // A line typed after this is read by the next question, the caller asks
+                // for enter first so that it does not count as an answer
+                writeln!(self);
+                Err(TimedOut)
+            }
+            Err(RecvTimeoutError::Disconnected) => Ok(None),
+        }
+    }
+
+    fn flush(&mut self) {
+        // Nothing to do when the terminal is gone, the next read ends the session
+        let _ = io::stdout().flush();
+    }
+}
+
+/// Answers given in advance, everything printed is kept for the tests to check.
+#[cfg(test)]
+pub struct ScriptedIo {
+    lines: VecDeque<String>,
+    output: String,
+    hints: Hints,
+}
+
+#[cfg(test)]
+impl ScriptedIo {
+    /// A line of the script which makes the question time out instead of answering it.
+    pub const TIMED_OUT: &'static str = "<čas vypršel>";
+
+    pub fn new(lines: &[&str]) -> Self {
+        Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            output: String::new(),
+            hints: Hints::default(),
+        }
+    }
+
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+}
+
+#[cfg(test)]
+impl Io for ScriptedIo {
+    fn write_fmt(&mut self, args: fmt::Arguments) {
+        fmt::Write::write_fmt(&mut self.output, args).expect("Writing to a String never fails");
+    }
+
+    fn hints(&mut self) -> &mut Hints {
+        &mut self.hints
+    }
+
+    fn read_line(&mut self) -> Option<String> {
+        let line = self.lines.pop_front()?;
+        // The answers are echoed, so the output reads like the session in the terminal
+        writeln!(self, "{line}");
+        Some(line)
+    }
+
+    fn read_line_until(&mut self, deadline: Instant) -> Result<Option<String>, TimedOut> {
+        let _ = deadline;
+        if self.lines.front().map(String::as_str) == Some(Self::TIMED_OUT) {
+            self.lines.pop_front();
+            writeln!(self);
+            return Err(TimedOut);
+        }
+        Ok(self.read_line())
+    }
+}
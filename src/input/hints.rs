@@ -0,0 +1,73 @@
+/*
+    Hints for the question the student is answering, from the mildest to the strongest.
+    Every `hint` typed at a prompt shows the next one and costs a point of the score.
+*/
+#[derive(Default)]
+pub struct Hints {
+    levels: Vec<String>,
+    shown: usize,
+    used: u32,
+    /// The first question asked after the hints were offered, `None` before it is asked.
+    question: Option<String>,
+}
+
+impl Hints {
+    /// Replaces the hints for the next question, they stay while it is asked again.
+    pub fn offer(&mut self, levels: Vec<String>) {
+        self.levels = levels;
+        self.shown = 0;
+        self.question = None;
+    }
+
+    /// Called for every prompt, the hints of an earlier question are dropped.
+    pub fn ask(&mut self, question: &str) {
+        match &self.question {
+            None => self.question = Some(question.to_string()),
+            Some(asked) if asked != question => {
+                self.offer(vec![]);
+                self.question = Some(question.to_string());
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Whether there are some hints for the current question, shown or not.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// The next stronger hint, `None` when all of them were shown.
+    pub fn next(&mut self) -> Option<&str> {
+        let hint = self.levels.get(self.shown)?;
+        self.shown += 1;
+        self.used += 1;
+        Some(hint)
+    }
+
+    /// How many hints were shown since the last call. The offered hints are dropped.
+    pub fn take_used(&mut self) -> u32 {
+        self.offer(vec![]);
+        std::mem::take(&mut self.used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_belong_to_one_question() {
+        let mut hints = Hints::default();
+        hints.offer(vec!["první".to_string(), "druhá".to_string()]);
+        hints.ask("Kolik je 3·4?");
+        assert_eq!(hints.next(), Some("první"));
+        hints.ask("Kolik je 3·4?");
+        assert_eq!(hints.next(), Some("druhá"));
+        assert_eq!(hints.next(), None);
+        assert!(!hints.is_empty());
+
+        hints.ask("Je 12 dělitelné 4?");
+        assert!(hints.is_empty());
+        assert_eq!(hints.take_used(), 2);
+    }
+}
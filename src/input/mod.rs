@@ -1,76 +1,285 @@
-use crate::ast::Digit;
-use core::fmt;
-use std::io::{self, Write};
-use std::ops::RangeInclusive;
-use std::str::FromStr;
-
-pub fn get_number_in_range<Num: fmt::Display + std::str::FromStr + PartialOrd>(
-    question: &str,
-    range: RangeInclusive<Num>,
-) -> Num {
-    let expected_str = format!("číslo {}-{}", range.start(), range.end());
-    let parser_fn = |input: &str| match input.parse::<Num>() {
-        Ok(number) => {
-            if range.contains(&number) {
-                println!(
-                    "Číslo {number} není od {} do {}",
-                    range.start(),
-                    range.end()
-                );
-                Some(number)
-            } else {
-                None
-            }
-        }
-        Err(_) => todo!(),
-    };
-    get_input(question, Some(&expected_str), parser_fn)
-}
-
-pub fn wait_for_enter(prompt: &str) {
-    get_input(prompt, Some("stiskni enter"), |_| Some(()))
-}
-
-pub fn get_number<Num: FromStr>(question: &str) -> Num {
-    get_input(question, Some("číslo"), |input| {
-        input.parse::<Num>().ok()
-    })
-}
-
-pub fn get_digit(question: &str) -> Digit {
-    get_input(question, Some("číslo 0-9"), |input| {
-        if input.len() == 1 {
-            let chr = input.chars().next().unwrap();
-            chr.to_digit(10).or_else(|| {
-                println!("\"{chr}\" není číslo 0 až 9.");
-                None
-            })
-        } else if input.len() == 0 {
-            println!("Hej. Zadej alespoň něco >:(");
-            None
-        } else {
-            println!("To je nějak moc znaků na to, aby to byla jedna cifra :)");
-            None
-        }
-    })
-}
-
-pub fn get_input<Res, Parser: Fn(&str) -> Option<Res>>(
-    question: &str,
-    expected: Option<&str>,
-    parser_fn: Parser,
-) -> Res {
-    println!("Prompt: {question}");
-    if let Some(expected) = expected {
-        print!("(Očekávám {expected}): ");
-    }
-    io::stdout().flush().unwrap();
-
-    let mut line = String::new();
-    io::stdin()
-        .read_line(&mut line)
-        .expect("Něco se pokazilo :(");
-
-    let input = line.trim();
-    parser_fn(input).unwrap_or_else(|| get_input(question, expected, parser_fn))
-}
+pub mod console;
+mod hints;
+pub mod number;
+
+use crate::ast::equation::EquationStep;
+use crate::ast::inequality::InequalityStep;
+use crate::ast::interval::IntervalUnion;
+use crate::ast::{Digit, Expr};
+use crate::units::Unit;
+use colored::*;
+use core::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Instant;
+
+pub use console::Io;
+pub use hints::Hints;
+
+pub fn get_number_in_range<Num: fmt::Display + std::str::FromStr + PartialOrd>(
+    io: &mut dyn Io,
+    question: &str,
+    range: RangeInclusive<Num>,
+) -> Answer<Num> {
+    let expected_str = format!("číslo {}-{}", range.start(), range.end());
+    let parser_fn = |input: &str| match input.parse::<Num>() {
+        Ok(number) => {
+            if range.contains(&number) {
+                Ok(number)
+            } else {
+                Err(format!(
+                    "Číslo {number} není od {} do {}",
+                    range.start(),
+                    range.end()
+                ))
+            }
+        }
+        Err(_) => Err(format!("\"{input}\" není číslo.")),
+    };
+    get_input(io, question, Some(&expected_str), parser_fn)
+}
+
+pub fn wait_for_enter(io: &mut dyn Io, prompt: &str) -> Answer<()> {
+    get_input(io, prompt, Some("stiskni enter"), |_| Ok(()))
+}
+
+pub fn get_number<Num: FromStr>(io: &mut dyn Io, question: &str) -> Answer<Num> {
+    get_input(io, question, Some("číslo"), |input| {
+        input
+            .parse::<Num>()
+            .map_err(|_| format!("\"{input}\" není číslo."))
+    })
+}
+
+pub fn get_yes_no(io: &mut dyn Io, question: &str) -> Answer<bool> {
+    get_input(io, question, Some("a/n"), |input| {
+        match input.to_lowercase().as_str() {
+            "a" | "ano" => Ok(true),
+            "n" | "ne" => Ok(false),
+            _ => Err("Odpověz a (ano), nebo n (ne).".to_string()),
+        }
+    })
+}
+
+pub fn get_expr(io: &mut dyn Io, question: &str) -> Answer<Expr> {
+    get_input(io, question, Some("výraz, např. 2a^3b^-2"), |input| {
+        Expr::parse(input).map_err(|err| err.to_string())
+    })
+}
+
+pub fn get_equation_step(io: &mut dyn Io, question: &str) -> Answer<EquationStep> {
+    get_input(
+        io,
+        question,
+        Some("rovnici, např. 3x = 6, nebo úpravu, např. | -2x nebo | :3"),
+        |input| EquationStep::parse(input).map_err(|err| err.to_string()),
+    )
+}
+
+pub fn get_inequality_step(io: &mut dyn Io, question: &str) -> Answer<InequalityStep> {
+    get_input(
+        io,
+        question,
+        Some("nerovnici, např. 3x < 6, nebo úpravu, např. | -2x nebo | :(-3)"),
+        |input| InequalityStep::parse(input).map_err(|err| err.to_string()),
+    )
+}
+
+pub fn get_interval_union(io: &mut dyn Io, question: &str) -> Answer<IntervalUnion> {
+    get_input(
+        io,
+        question,
+        Some("interval, např. (-∞; 4) nebo ⟨2; 5)"),
+        IntervalUnion::parse,
+    )
+}
+
+/// Decimal number with one of the `units`, e.g. `12,5 kg`. The unit can be left out.
+pub fn get_quantity(io: &mut dyn Io, question: &str, units: &[&str]) -> Answer<f64> {
+    let expected = match units.first() {
+        Some(unit) => format!("číslo v {unit}, např. 12,5 {unit}"),
+        None => "číslo, např. 12,5".to_string(),
+    };
+    get_input(io, question, Some(&expected), |input| {
+        let Some((value, unit)) = number::parse_with_unit(input) else {
+            return Err(format!("\"{input}\" není číslo."));
+        };
+        if unit.is_empty() || units.contains(&unit) {
+            Ok(value)
+        } else {
+            Err(format!("Jednotka \"{unit}\" sem nepatří."))
+        }
+    })
+}
+
+/// Number in `unit`, the unit may be left out or written as `m2` instead of `m²`.
+pub fn get_measurement(io: &mut dyn Io, question: &str, unit: Unit) -> Answer<f64> {
+    let expected = format!("číslo v {unit}, např. 12,5 {unit}");
+    get_input(io, question, Some(&expected), |input| {
+        let Some((value, typed_unit)) = number::parse_with_unit(input) else {
+            return Err(format!("\"{input}\" není číslo."));
+        };
+        match Unit::parse(typed_unit) {
+            _ if typed_unit.is_empty() => Ok(value),
+            Some(typed) if typed == unit => Ok(value),
+            Some(typed) => Err(format!("Výsledek má být v {unit}, ne v {typed}.")),
+            None => Err(format!("Jednotku \"{typed_unit}\" neznám.")),
+        }
+    })
+}
+
+/// Fraction exactly as it was typed, e.g. `6/8` is `(6, 8)` and `3` is `(3, 1)`.
+pub fn get_fraction(io: &mut dyn Io, question: &str) -> Answer<(u32, u32)> {
+    get_input(io, question, Some("zlomek, např. 3/4"), |input| {
+        let (top, bottom) = input.split_once('/').unwrap_or((input, "1"));
+        match (top.trim().parse::<u32>(), bottom.trim().parse::<u32>()) {
+            (Ok(_), Ok(0)) => Err("Jmenovatel nemůže být 0.".to_string()),
+            (Ok(top), Ok(bottom)) => Ok((top, bottom)),
+            _ => Err(format!("\"{input}\" není zlomek.")),
+        }
+    })
+}
+
+pub fn get_digit(io: &mut dyn Io, question: &str) -> Answer<Digit> {
+    get_input(io, question, Some("číslo 0-9"), |input| {
+        if input.len() == 1 {
+            let chr = input.chars().next().unwrap();
+            chr.to_digit(10)
+                .ok_or_else(|| format!("\"{chr}\" není číslo 0 až 9."))
+        } else if input.is_empty() {
+            Err("Hej. Zadej alespoň něco >:(".to_string())
+        } else {
+            Err("To je nějak moc znaků na to, aby to byla jedna cifra :)".to_string())
+        }
+    })
+}
+
+/// Why a question was left without an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupt {
+    /// The student typed `q`.
+    Quit,
+    /// Ctrl-D, or the end of a script.
+    EndOfInput,
+    /// The student typed `skip`, the excercise stays unsolved.
+    Skip,
+    /// Too many answers in a row could not be understood.
+    TooManyInvalid,
+}
+
+pub type Answer<T> = Result<T, Interrupt>;
+
+/// How many answers in a row may be unreadable before the question is given up.
+const MAX_INVALID_ANSWERS: u32 = 5;
+
+/// Words that mean the same at every prompt.
+enum Command {
+    Quit,
+    Help,
+    Skip,
+    Hint,
+}
+
+impl Command {
+    fn parse(input: &str) -> Option<Command> {
+        match input.to_lowercase().as_str() {
+            "q" | "konec" => Some(Command::Quit),
+            "help" | "?" | "pomoc" => Some(Command::Help),
+            "skip" | "přeskočit" => Some(Command::Skip),
+            "hint" | "nápověda" => Some(Command::Hint),
+            _ => None,
+        }
+    }
+}
+
+fn print_help(io: &mut dyn Io, expected: Option<&str>) {
+    if let Some(expected) = expected {
+        writeln!(io, "Očekávám {expected}.");
+    }
+    writeln!(io, "Kdykoliv můžeš napsat:");
+    writeln!(io, "  hint  nápověda k otázce");
+    writeln!(io, "  skip  přeskočit tenhle příklad");
+    writeln!(io, "  q     konec");
+}
+
+/// The answer parsed, or `None` when the question should be asked again.
+fn answer_line<Res>(
+    io: &mut dyn Io,
+    line: Option<String>,
+    expected: Option<&str>,
+    parser_fn: impl Fn(&str) -> Result<Res, String>,
+    invalid_answers: &mut u32,
+) -> Option<Answer<Res>> {
+    let Some(line) = line else {
+        writeln!(io);
+        return Some(Err(Interrupt::EndOfInput));
+    };
+    let input = line.trim();
+    match Command::parse(input) {
+        Some(Command::Quit) => return Some(Err(Interrupt::Quit)),
+        Some(Command::Skip) => return Some(Err(Interrupt::Skip)),
+        Some(Command::Help) => {
+            print_help(io, expected);
+            return None;
+        }
+        Some(Command::Hint) => {
+            let hints = io.hints();
+            match hints.next().map(str::to_string) {
+                Some(hint) => writeln!(io, "{} {hint}", "Nápověda:".cyan()),
+                None if hints.is_empty() => writeln!(io, "K téhle otázce nápovědu nemám."),
+                None => writeln!(io, "Víc nápovědy už nemám, zkus to nebo napiš skip."),
+            }
+            return None;
+        }
+        None => {}
+    }
+    match parser_fn(input) {
+        Ok(result) => Some(Ok(result)),
+        Err(err) => {
+            writeln!(io, "{err}");
+            *invalid_answers += 1;
+            if *invalid_answers < MAX_INVALID_ANSWERS {
+                return None;
+            }
+            writeln!(io, "Odpovědi pořád nerozumím, jdeme dál. Napiš help, co umím.");
+            Some(Err(Interrupt::TooManyInvalid))
+        }
+    }
+}
+
+/// Asks until `parser_fn` accepts the answer, its error is printed before asking again.
+pub fn get_input<Res, Parser: Fn(&str) -> Result<Res, String>>(
+    io: &mut dyn Io,
+    question: &str,
+    expected: Option<&str>,
+    parser_fn: Parser,
+) -> Answer<Res> {
+    let mut invalid_answers = 0;
+    loop {
+        io.prompt(question, expected);
+        let line = io.read_line();
+        if let Some(answer) = answer_line(io, line, expected, &parser_fn, &mut invalid_answers) {
+            return answer;
+        }
+    }
+}
+
+/// Like `get_input`, but gives up at `deadline` and returns `None`.
+pub fn get_input_until<Res, Parser: Fn(&str) -> Result<Res, String>>(
+    io: &mut dyn Io,
+    question: &str,
+    expected: Option<&str>,
+    deadline: Instant,
+    parser_fn: Parser,
+) -> Answer<Option<Res>> {
+    let mut invalid_answers = 0;
+    loop {
+        io.prompt(question, expected);
+        let Ok(line) = io.read_line_until(deadline) else {
+            return Ok(None);
+        };
+        if let Some(answer) = answer_line(io, line, expected, &parser_fn, &mut invalid_answers) {
+            return answer.map(Some);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+/*
+    Numbers as they are written in Czech: `3,5` is three and a half
+    (`3.5` is accepted as well) and spaces separate thousands, e.g. `1 250,50`.
+*/
+
+/// `None` when the input is not a decimal number.
+pub fn parse_decimal(input: &str) -> Option<f64> {
+    let (is_negative, digits) = match input.trim().strip_prefix(['-', '−']) {
+        Some(rest) => (true, rest),
+        None => (false, input.trim()),
+    };
+    let normalized: String = digits
+        .chars()
+        .filter(|chr| !chr.is_whitespace())
+        .map(|chr| if chr == ',' { '.' } else { chr })
+        .collect();
+    let is_valid = !normalized.is_empty()
+        && normalized
+            .chars()
+            .all(|chr| chr.is_ascii_digit() || chr == '.')
+        && normalized.matches('.').count() <= 1
+        && normalized.chars().any(|chr| chr.is_ascii_digit());
+    if !is_valid {
+        return None;
+    }
+    let value: f64 = normalized.parse().ok()?;
+    Some(if is_negative { -value } else { value })
+}
+
+/// `12,5 kg` is `(12.5, "kg")`, the unit is empty when it is missing.
+pub fn parse_with_unit(input: &str) -> Option<(f64, &str)> {
+    let input = input.trim();
+    let unit_start = input
+        .char_indices()
+        .find(|(idx, chr)| {
+            let is_number_part = chr.is_ascii_digit()
+                || chr.is_whitespace()
+                || matches!(chr, ',' | '.')
+                || (*idx == 0 && matches!(chr, '-' | '−'));
+            !is_number_part
+        })
+        .map_or(input.len(), |(idx, _)| idx);
+    let value = parse_decimal(&input[..unit_start])?;
+    Some((value, input[unit_start..].trim()))
+}
+
+/// Decimal comma and no trailing zeros, `3.50` is `3,5`. Rounded to `decimals` places.
+/// Numbers with more than four digits have their thousands separated, `12 500`.
+pub fn format_decimal(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.decimals$}", value.abs());
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((formatted, ""));
+
+    let mut result = String::new();
+    if value < 0.0 && formatted.chars().any(|chr| chr != '0' && chr != '.') {
+        result.push('-');
+    }
+    for (idx, digit) in whole.chars().enumerate() {
+        let remaining = whole.len() - idx;
+        if idx != 0 && whole.len() > 4 && remaining % 3 == 0 {
+            result.push(' ');
+        }
+        result.push(digit);
+    }
+    if !fraction.is_empty() {
+        result.push(',');
+        result.push_str(fraction);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn czech_numbers_are_parsed() {
+        assert_eq!(parse_decimal("3,5"), Some(3.5));
+        assert_eq!(parse_decimal("3.5"), Some(3.5));
+        assert_eq!(parse_decimal(" 1 250,50 "), Some(1250.5));
+        assert_eq!(parse_decimal("−0,25"), Some(-0.25));
+        assert_eq!(parse_decimal(",5"), Some(0.5));
+        assert_eq!(parse_decimal("1,2,3"), None);
+        assert_eq!(parse_decimal("-"), None);
+        assert_eq!(parse_decimal("12a"), None);
+        assert_eq!(parse_with_unit("12,5 kg"), Some((12.5, "kg")));
+        assert_eq!(parse_with_unit("-3m²"), Some((-3.0, "m²")));
+    }
+
+    #[test]
+    fn numbers_are_formatted_the_czech_way() {
+        assert_eq!(format_decimal(3.50, 2), "3,5");
+        assert_eq!(format_decimal(2.0, 3), "2");
+        assert_eq!(format_decimal(1234.0, 0), "1234");
+        assert_eq!(format_decimal(12500.0, 0), "12 500");
+        assert_eq!(format_decimal(-1234567.891, 2), "-1 234 567,89");
+        assert_eq!(format_decimal(-0.001, 2), "0");
+    }
+}
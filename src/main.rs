@@ -1,19 +1,37 @@
 pub mod excercise {
+    pub mod common_factor;
+    pub mod divisibility;
     pub mod division;
+    pub mod fractions;
+    pub mod linear_equation;
+    pub mod linear_inequality;
+    pub mod linear_system;
+    pub mod order_of_operations;
+    pub mod percentages;
+    pub mod power_rules;
+    pub mod primes;
+    pub mod quadratic;
+    pub mod rational;
+    pub mod roots;
+    pub mod speed_drill;
+    pub mod unit_conversion;
 }
 mod ast;
+mod code;
 mod input;
+mod random;
+mod stats;
+mod units;
 
-use core::num;
-use std::{collections::BTreeMap, net::ToSocketAddrs, ops::Rem, rc::Rc};
+use std::{cmp::Ordering, collections::BTreeMap, ops::RangeInclusive};
 
+use ast::canonical::{gcd, CanonicalTerm};
 use ast::*;
-use input::{get_input, get_number, get_number_in_range, wait_for_enter};
-use rand::{
-    distr::uniform::{SampleRange, SampleUniform},
-    rngs::StdRng,
-    Rng, SeedableRng,
-};
+use code::{ExcerciseCode, SEED_COUNT};
+use input::console::TerminalIo;
+use input::{get_input, get_number_in_range, get_yes_no, wait_for_enter, Answer, Interrupt, Io};
+use random::{Random, SampleRange, Uniform};
+use stats::{SessionStats, POINTS_PER_EXCERCISE};
 
 /*
     a^2 - b^2 = (a - b) * (a + b)
@@ -26,17 +44,59 @@ struct ExtractDiffSquares {
 }
 
 impl ExtractDiffSquares {
-    fn generate_random(rnd: &mut StdRng, max_vars: u32, simple_chance: f64) -> Self {
-        let a = generate_term(rnd, max_vars);
-        let b = generate_term(rnd, max_vars);
-        let a2 = a.pow_random(rnd, 2, simple_chance);
-        let b2 = b.pow_random(rnd, 2, simple_chance);
-        Self { a, b, a2, b2 }
+    fn generate_random(
+        rnd: &mut Random,
+        config: &TermConfig,
+        simple_chance: f64,
+        negative_exponents: bool,
+    ) -> Self {
+        let mut a = generate_term(rnd, config);
+        let mut b = generate_term(rnd, config);
+        if negative_exponents {
+            a = negate_random_exponents(rnd, a);
+            b = negate_random_exponents(rnd, b);
+        }
+        let policy = NegativeExponents::Keep;
+        let a2 = a.pow_random(rnd, 2, simple_chance, policy);
+        let b2 = b.pow_random(rnd, 2, simple_chance, policy);
+        Self {
+            a,
+            b,
+            a2: a2.expect("Generated terms are never zero"),
+            b2: b2.expect("Generated terms are never zero"),
+        }
+    }
+
+    /// `a + b` or `a - b`
+    fn binomial(&self, is_sum: bool) -> Expr {
+        let b = self.b.clone().maybe_wrap_in_minus(!is_sum);
+        Expr::Addition(vec![self.a.clone(), b])
+    }
+
+    /// `a^2 - b^2`
+    fn difference(&self) -> Expr {
+        Expr::Addition(vec![
+            self.a2.clone(),
+            Expr::UnaryMinus(self.b2.clone().into()),
+        ])
+    }
+
+    /// `a^2 ± 2·a·b + b^2`, the square of `binomial(is_sum)`
+    fn square_of_binomial(&self, is_sum: bool) -> Expr {
+        let two_ab = Expr::Multiplication(vec![Expr::Number(2), self.a.clone(), self.b.clone()]);
+        let two_ab = CanonicalTerm::from_expr(&two_ab)
+            .expect("Generated terms are small")
+            .to_expr(NegativeExponents::AsFraction);
+        Expr::Addition(vec![
+            self.a2.clone(),
+            two_ab.maybe_wrap_in_minus(!is_sum),
+            self.b2.clone(),
+        ])
     }
 }
 
-fn random_range_filter<T: SampleUniform, R: SampleRange<T> + Clone, F: Fn(&T) -> bool>(
-    rnd: &mut StdRng,
+fn random_range_filter<T: Uniform, R: SampleRange<T> + Clone, F: Fn(&T) -> bool>(
+    rnd: &mut Random,
     range: R,
     is_ok_pred: F,
 ) -> T {
@@ -48,69 +108,267 @@ fn random_range_filter<T: SampleUniform, R: SampleRange<T> + Clone, F: Fn(&T) ->
     }
 }
 
-fn generate_term(rnd: &mut StdRng, max_vars: u32) -> Expr {
-    let variable_count = rnd.random_range(0..=max_vars);
+/// What the terms made by `generate_term` look like. The equations take the letters
+/// of their unknowns and the numbers which do not follow from the solution from it.
+#[derive(Clone, Debug)]
+pub struct TermConfig {
+    /// Letters the variables are picked from, a term has every variable at most once.
+    variables: Vec<char>,
+    /// At most this many variables, a term can also be just a number.
+    max_vars: u32,
+    coefficients: RangeInclusive<u32>,
+    exponents: RangeInclusive<u32>,
+    /// Largest sum of the exponents, no limit with `None`.
+    max_degree: Option<u32>,
+    /// Half of the coefficients are negative.
+    negative_coefficients: bool,
+    /// Some of the coefficients are fractions, e.g. `3·a^2/4`.
+    fractional_coefficients: bool,
+}
+
+impl Default for TermConfig {
+    fn default() -> Self {
+        Self {
+            // `l` looks too much like `1`
+            variables: ('a'..='z').filter(|chr| *chr != 'l').collect(),
+            max_vars: 2,
+            coefficients: 1..=20,
+            exponents: 1..=20,
+            max_degree: None,
+            negative_coefficients: false,
+            fractional_coefficients: false,
+        }
+    }
+}
+
+impl TermConfig {
+    /// One of `variables`, for the excercises with a single unknown.
+    fn pick_variable(&self, rnd: &mut Random) -> char {
+        self.variables[rnd.random_range(0..self.variables.len())]
+    }
+
+    /// A number from `coefficients`, negative for half of them with `negative_coefficients`.
+    fn pick_coefficient(&self, rnd: &mut Random) -> i64 {
+        let coefficient = i64::from(rnd.random_range(self.coefficients.clone()));
+        if self.negative_coefficients && rnd.random_bool(0.5) {
+            -coefficient
+        } else {
+            coefficient
+        }
+    }
+
+    /// `coefficients` must not be just zero.
+    fn pick_nonzero_coefficient(&self, rnd: &mut Random) -> i64 {
+        loop {
+            let coefficient = self.pick_coefficient(rnd);
+            if coefficient != 0 {
+                return coefficient;
+            }
+        }
+    }
+}
+
+fn generate_term(rnd: &mut Random, config: &TermConfig) -> Expr {
+    let first_var = *config
+        .variables
+        .iter()
+        .min()
+        .expect("Terms have some variables");
+    let last_var = *config
+        .variables
+        .iter()
+        .max()
+        .expect("Terms have some variables");
+    let variable_count = rnd.random_range(0..=config.max_vars);
+    let mut degree_left = config.max_degree.unwrap_or(u32::MAX);
     let mut var_exp_dict = BTreeMap::<char, u32>::new();
     for _ in 0..variable_count {
-        let new_var = random_range_filter(rnd, 'a'..='z', |chr| *chr != 'l');
-        let exp = rnd.random_range(1..=20);
-        var_exp_dict.insert(new_var, exp);
+        if degree_left < *config.exponents.start() {
+            break;
+        }
+        let new_var = random_range_filter(rnd, first_var..=last_var, |chr| {
+            config.variables.contains(chr)
+        });
+        let max_exp = (*config.exponents.end()).min(degree_left);
+        let exp = rnd.random_range(*config.exponents.start()..=max_exp);
+        // The same variable again only replaces its exponent
+        let replaced = var_exp_dict.insert(new_var, exp).unwrap_or(0);
+        degree_left = degree_left + replaced - exp;
     }
 
-    let mut variables = vec![Expr::Number(rnd.random_range(1..=20))];
+    let coefficient = rnd.random_range(config.coefficients.clone());
+    let mut variables = vec![Expr::Number(coefficient)];
     variables.extend(var_exp_dict.iter().map(|(var, exp)| Expr::Exp {
         base: Expr::Variable { symbol: *var }.into(),
         exp: Expr::Number(*exp).into(),
     }));
 
-    if variable_count == 0 {
+    let mut term = if variables.len() == 1 {
         variables[0].clone()
     } else {
         Expr::Multiplication(variables)
+    };
+    // `0/5` would be no fraction, and no denominator is coprime with zero anyway
+    if config.fractional_coefficients && coefficient != 0 && rnd.random_bool(0.3) {
+        let max_denominator = (*config.coefficients.end()).max(2);
+        let denominator = random_range_filter(rnd, 2..=max_denominator, |denominator| {
+            gcd(u64::from(coefficient), u64::from(*denominator)) == 1
+        });
+        term = Expr::Division {
+            lhs: term.into(),
+            rhs: Expr::Number(denominator).into(),
+        };
+    }
+    if config.negative_coefficients && rnd.random_bool(0.5) {
+        term = term.multiply_by_number(-1);
+    }
+    term
+}
+
+/// Flips the sign of some of the exponents, `3·a^2·b^5` can become `3·a^(-2)·b^5`.
+fn negate_random_exponents(rnd: &mut Random, term: Expr) -> Expr {
+    match term {
+        Expr::Multiplication(factors) => Expr::Multiplication(
+            factors
+                .into_iter()
+                .map(|factor| match factor {
+                    Expr::Exp { base, exp } if rnd.random_bool(0.5) => Expr::Exp {
+                        base,
+                        exp: exp.multiply_by_number(-1).into(),
+                    },
+                    factor => factor,
+                })
+                .collect(),
+        ),
+        Expr::Division { lhs, rhs } => Expr::Division {
+            lhs: negate_random_exponents(rnd, (*lhs).clone()).into(),
+            rhs,
+        },
+        term => term,
     }
 }
 
 impl Expr {
-    fn pow(&self, exponent: i32) -> Expr {
-        match self {
+    fn pow(&self, exponent: i32, policy: NegativeExponents) -> Result<Expr, PowError> {
+        let powered = self.pow_keep_negative(exponent)?;
+        Ok(match policy {
+            NegativeExponents::Keep => powered,
+            NegativeExponents::AsFraction => powered.negative_exponents_as_fraction(),
+        })
+    }
+
+    fn pow_keep_negative(&self, exponent: i32) -> Result<Expr, PowError> {
+        let powered = match self {
             Expr::Multiplication(exprs) => {
-                let new_exprs = exprs.iter().map(|e| e.pow(exponent)).collect();
+                let new_exprs = exprs
+                    .iter()
+                    .map(|e| e.pow_keep_negative(exponent))
+                    .collect::<Result<_, _>>()?;
                 Expr::Multiplication(new_exprs)
             }
             Expr::Division { lhs, rhs } => Expr::Division {
-                lhs: lhs.pow(exponent).into(),
-                rhs: rhs.pow(exponent).into(),
+                lhs: lhs.pow_keep_negative(exponent)?.into(),
+                rhs: rhs.pow_keep_negative(exponent)?.into(),
             },
-            Expr::Variable { symbol: _ } => Expr::Exp {
-                base: self.clone().into(),
-                exp: Expr::signed_number(exponent).into(),
+            Expr::Variable { symbol: _ } => match exponent {
+                0 => Expr::Number(1),
+                1 => self.clone(),
+                _ => self.pow_simple(exponent),
             },
             Expr::Exp { base, exp } => {
                 let new_exp = exp.multiply_by_number(exponent);
+                // (0^-1)^2 is 0^-2, the exponents are multiplied before 0 is checked
+                if let (Expr::Number(0), Some(new_exp)) = (&**base, new_exp.as_integer()) {
+                    let new_exp = i32::try_from(new_exp).map_err(|_| PowError::TooLarge)?;
+                    return base.pow_keep_negative(new_exp);
+                }
                 match new_exp {
                     Expr::Number(1) => (**base).clone(),
-                    Expr::Number(0) => Expr::Number(1), // WARNING: this could be undefined 0^0
+                    Expr::Number(0) => Expr::Number(1),
                     _ => Expr::Exp {
                         base: base.clone(),
                         exp: new_exp.into(),
                     },
                 }
             }
-            Expr::Number(0 | 1) => self.clone(),
+            Expr::Number(0) => match exponent.cmp(&0) {
+                Ordering::Equal => return Err(PowError::ZeroToZero),
+                Ordering::Less => return Err(PowError::ZeroToNegative),
+                Ordering::Greater => Expr::zero(),
+            },
+            Expr::Number(1) => self.clone(),
             Expr::Number(number) => {
-                let abs_exp = exponent.unsigned_abs();
                 if exponent.is_negative() {
-                    Expr::Division {
-                        lhs: Expr::Number(1).into(),
-                        rhs: Expr::Number(number.pow(abs_exp)).into(),
-                    }
+                    self.pow_simple(exponent)
                 } else {
-                    Expr::Number(number.pow(abs_exp))
+                    let powered = number.checked_pow(exponent.unsigned_abs());
+                    Expr::Number(powered.ok_or(PowError::TooLarge)?)
                 }
             }
-            Expr::Addition(_) => unimplemented!("Neumím umocnit sčítání"),
-            Expr::UnaryMinus(_) => unimplemented!("Neumím umocnit sčítání"),
-            // _ => unimplemented!("Neumím umocnit binární operace \"{self}\" :)"),
+            Expr::UnaryMinus(inner) => inner
+                .pow_keep_negative(exponent)?
+                .maybe_wrap_in_minus(exponent % 2 != 0),
+            Expr::Root { .. } => self.pow_simple(exponent),
+            Expr::Addition(_) => return Err(PowError::Sum),
+        };
+        Ok(powered)
+    }
+
+    /// Moves every power with a negative exponent into the denominator, `2·a^-3` becomes `2/a^3`.
+    fn negative_exponents_as_fraction(&self) -> Expr {
+        let (is_negative, top, bottom) = self.split_by_exponent_sign();
+        let top = Expr::product(top);
+        let expr = if bottom.is_empty() {
+            top
+        } else {
+            Expr::Division {
+                lhs: top.into(),
+                rhs: Expr::product(bottom).into(),
+            }
+        };
+        expr.maybe_wrap_in_minus(is_negative)
+    }
+
+    /// Splits the expression into (is negative, numerator factors, denominator factors).
+    fn split_by_exponent_sign(&self) -> (bool, Vec<Expr>, Vec<Expr>) {
+        match self {
+            Expr::Multiplication(exprs) => {
+                let mut result = (false, vec![], vec![]);
+                for expr in exprs {
+                    let (is_negative, top, bottom) = expr.split_by_exponent_sign();
+                    result.0 ^= is_negative;
+                    result.1.extend(top);
+                    result.2.extend(bottom);
+                }
+                result
+            }
+            Expr::Division { lhs, rhs } => {
+                let (lhs_negative, lhs_top, lhs_bottom) = lhs.split_by_exponent_sign();
+                let (rhs_negative, rhs_top, rhs_bottom) = rhs.split_by_exponent_sign();
+                let mut top = lhs_top;
+                top.extend(rhs_bottom);
+                let mut bottom = lhs_bottom;
+                bottom.extend(rhs_top);
+                (lhs_negative ^ rhs_negative, top, bottom)
+            }
+            Expr::UnaryMinus(inner) => {
+                let (is_negative, top, bottom) = inner.split_by_exponent_sign();
+                (!is_negative, top, bottom)
+            }
+            Expr::Exp { base, exp } if exp.as_integer().is_some_and(|exp| exp < 0) => {
+                let abs_exp = exp.as_integer().unwrap().unsigned_abs();
+                let denominator = match (&**base, abs_exp) {
+                    (base, 1) => base.clone(),
+                    (Expr::Number(number), _) => u32::try_from(abs_exp)
+                        .ok()
+                        .and_then(|abs_exp| number.checked_pow(abs_exp))
+                        .map(Expr::Number)
+                        .unwrap_or_else(|| base.pow_simple(abs_exp as i32)),
+                    (base, _) => base.pow_simple(abs_exp as i32),
+                };
+                (false, vec![], vec![denominator])
+            }
+            expr => (false, vec![expr.clone()], vec![]),
         }
     }
 
@@ -178,25 +436,46 @@ impl Expr {
         }
     }
 
-    fn pow_random(&self, rnd: &mut StdRng, exponent: i32, simple_chance: f64) -> Expr {
+    fn pow_random(
+        &self,
+        rnd: &mut Random,
+        exponent: i32,
+        simple_chance: f64,
+        policy: NegativeExponents,
+    ) -> Result<Expr, PowError> {
         if rnd.random_bool(simple_chance) {
-            self.pow_simple(exponent)
+            Ok(self.pow_simple(exponent))
         } else {
-            self.pow(exponent)
+            self.pow(exponent, policy)
         }
     }
 }
 
-fn do_diff_squares(assignment: ExtractDiffSquares) {
+/// The student compares their result with the solution, so they say themselves whether it was right.
+fn do_diff_squares(io: &mut dyn Io, assignment: ExtractDiffSquares) -> Answer<bool> {
     let ExtractDiffSquares { a, b, a2, b2 } = assignment;
-    println!("Rozložte na součin podle vzorce:");
-    println!("{a2} - {b2}");
-    println!();
+    writeln!(io, "Rozložte na součin podle vzorce:");
+    writeln!(io, "{a2} - {b2}");
+    writeln!(io);
+
+    io.hints().offer(vec![
+        format!("Co umocněné na druhou dá {a2}?"),
+        format!("Co umocněné na druhou dá {b2}?"),
+        format!("{a2} = ({a})^2 a {b2} = ({b})^2, teď stačí vzorec a^2 - b^2 = (a - b)·(a + b)."),
+    ]);
+    wait_for_enter(io, "Pro zobrazení řešení dej enter")?;
 
-    wait_for_enter("Pro zobrazení řešení dej enter");
+    writeln!(io, "Tohle je řešení:");
+    writeln!(io, "= ({a} - {b}) * ({a} + {b})");
+    writeln!(io);
 
-    println!("Tohle je řešení:");
-    println!("= ({a} - {b}) * ({a} + {b})");
+    writeln!(io, "Postup, proč {a2} je druhá mocnina {a}:");
+    writeln!(io, "{}", a.pow_simple(2).simplify());
+    writeln!(io);
+    writeln!(io, "Postup, proč {b2} je druhá mocnina {b}:");
+    writeln!(io, "{}", b.pow_simple(2).simplify());
+    writeln!(io);
+    get_yes_no(io, "Měl jsi to stejně?")
 }
 
 // struct SquareAss {
@@ -208,7 +487,7 @@ fn do_diff_squares(assignment: ExtractDiffSquares) {
 // }
 
 // impl SquareAss {
-//     fn generate_random(rnd: &mut StdRng, max_vars: u32, simple_chance: f64) -> Self {
+//     fn generate_random(rnd: &mut Random, max_vars: u32, simple_chance: f64) -> Self {
 //         let a = generate_term(rnd, max_vars);
 //         let b = generate_term(rnd, max_vars);
 //         let a2 = a.pow_random(rnd, 2, simple_chance);
@@ -235,13 +514,660 @@ fn do_diff_squares(assignment: ExtractDiffSquares) {
 
 // fn do_square()
 
+#[derive(Clone, Copy)]
+enum ExcerciseKind {
+    DiffSquares,
+    PowerRules,
+    Fractions,
+    Percentages,
+    UnitConversion,
+    OrderOfOperations,
+    SpeedDrill,
+    Divisibility,
+    PrimeFactorization,
+    GcdLcm,
+    RationalExpressions,
+    CommonFactor,
+    LinearEquations,
+    LinearInequalities,
+    LinearSystems,
+    LinearSystemsOfThree,
+    QuadraticEquations,
+    Roots,
+}
+
+/// What the student chose for all the excercises in the session.
+struct Settings {
+    negative_exponents: bool,
+    /// `None` for a random kind of roots in every excercise.
+    root_kind: Option<excercise::quadratic::RootKind>,
+    /// Whether the student picks every operation before computing it.
+    step_mode: bool,
+}
+
+impl Settings {
+    /// The settings as a number for the excercise code, 0 when they are all off.
+    fn to_params(&self) -> u64 {
+        let root_kind = self.root_kind.map_or(0, |root_kind| {
+            let all = excercise::quadratic::RootKind::ALL;
+            all.iter().position(|kind| *kind == root_kind).unwrap() as u64 + 1
+        });
+        u64::from(self.negative_exponents) | u64::from(self.step_mode) << 1 | root_kind << 2
+    }
+
+    fn from_params(params: u64) -> Option<Settings> {
+        let root_kind = match params >> 2 {
+            0 => None,
+            idx => Some(*excercise::quadratic::RootKind::ALL.get(idx as usize - 1)?),
+        };
+        Some(Settings {
+            negative_exponents: params & 1 != 0,
+            root_kind,
+            step_mode: params & 2 != 0,
+        })
+    }
+}
+
+impl ExcerciseKind {
+    const ALL: [ExcerciseKind; 18] = [
+        ExcerciseKind::DiffSquares,
+        ExcerciseKind::PowerRules,
+        ExcerciseKind::Fractions,
+        ExcerciseKind::Percentages,
+        ExcerciseKind::UnitConversion,
+        ExcerciseKind::OrderOfOperations,
+        ExcerciseKind::SpeedDrill,
+        ExcerciseKind::Divisibility,
+        ExcerciseKind::PrimeFactorization,
+        ExcerciseKind::GcdLcm,
+        ExcerciseKind::RationalExpressions,
+        ExcerciseKind::CommonFactor,
+        ExcerciseKind::LinearEquations,
+        ExcerciseKind::LinearInequalities,
+        ExcerciseKind::LinearSystems,
+        ExcerciseKind::LinearSystemsOfThree,
+        ExcerciseKind::QuadraticEquations,
+        ExcerciseKind::Roots,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ExcerciseKind::DiffSquares => "Rozklad rozdílu čtverců",
+            ExcerciseKind::PowerRules => "Zjednodušování mocnin",
+            ExcerciseKind::Fractions => "Počítání se zlomky",
+            ExcerciseKind::Percentages => "Slovní úlohy na procenta a úměrnost",
+            ExcerciseKind::UnitConversion => "Převody jednotek",
+            ExcerciseKind::OrderOfOperations => "Pořadí početních operací",
+            ExcerciseKind::SpeedDrill => "Počítání na čas",
+            ExcerciseKind::Divisibility => "Pravidla dělitelnosti",
+            ExcerciseKind::PrimeFactorization => "Rozklad na prvočísla",
+            ExcerciseKind::GcdLcm => "Největší společný dělitel a nejmenší společný násobek",
+            ExcerciseKind::RationalExpressions => "Zjednodušování lomených výrazů",
+            ExcerciseKind::CommonFactor => "Vytýkání před závorku",
+            ExcerciseKind::LinearEquations => "Lineární rovnice",
+            ExcerciseKind::LinearInequalities => "Lineární nerovnice a intervaly",
+            ExcerciseKind::LinearSystems => "Soustavy dvou lineárních rovnic",
+            ExcerciseKind::LinearSystemsOfThree => "Soustavy tří lineárních rovnic",
+            ExcerciseKind::QuadraticEquations => "Kvadratické rovnice",
+            ExcerciseKind::Roots => "Odmocniny a usměrňování zlomků",
+        }
+    }
+
+    /// The kind in the excercise code.
+    fn code(self) -> &'static str {
+        match self {
+            ExcerciseKind::DiffSquares => "DS",
+            ExcerciseKind::PowerRules => "PW",
+            ExcerciseKind::Fractions => "FR",
+            ExcerciseKind::Percentages => "PC",
+            ExcerciseKind::UnitConversion => "UC",
+            ExcerciseKind::OrderOfOperations => "OO",
+            ExcerciseKind::SpeedDrill => "SD",
+            ExcerciseKind::Divisibility => "DV",
+            ExcerciseKind::PrimeFactorization => "PF",
+            ExcerciseKind::GcdLcm => "GL",
+            ExcerciseKind::RationalExpressions => "RE",
+            ExcerciseKind::CommonFactor => "CF",
+            ExcerciseKind::LinearEquations => "LE",
+            ExcerciseKind::LinearInequalities => "LI",
+            ExcerciseKind::LinearSystems => "LS",
+            ExcerciseKind::LinearSystemsOfThree => "LT",
+            ExcerciseKind::QuadraticEquations => "QE",
+            ExcerciseKind::Roots => "RT",
+        }
+    }
+
+    /// Raised whenever the generator of the kind changes, so that an old code is not
+    /// silently turned into a different excercise. A new algorithm of `Random` changes
+    /// every kind, version 1 of all of them still used the `rand` library.
+    fn generator_version(self) -> u32 {
+        let revision = match self {
+            ExcerciseKind::DiffSquares => 1,
+            ExcerciseKind::PowerRules => 1,
+            ExcerciseKind::Fractions => 1,
+            ExcerciseKind::Percentages => 1,
+            ExcerciseKind::UnitConversion => 1,
+            ExcerciseKind::OrderOfOperations => 1,
+            ExcerciseKind::SpeedDrill => 1,
+            ExcerciseKind::Divisibility => 1,
+            ExcerciseKind::PrimeFactorization => 1,
+            ExcerciseKind::GcdLcm => 2,
+            ExcerciseKind::RationalExpressions => 2,
+            ExcerciseKind::CommonFactor => 2,
+            ExcerciseKind::LinearEquations => 3,
+            ExcerciseKind::LinearInequalities => 3,
+            ExcerciseKind::LinearSystems => 2,
+            ExcerciseKind::LinearSystemsOfThree => 2,
+            ExcerciseKind::QuadraticEquations => 3,
+            ExcerciseKind::Roots => 2,
+        };
+        revision + random::VERSION
+    }
+
+    fn has_exponents(self) -> bool {
+        matches!(self, ExcerciseKind::DiffSquares | ExcerciseKind::PowerRules)
+    }
+
+    fn ask_settings(self, io: &mut dyn Io) -> Answer<Settings> {
+        let negative_exponents =
+            self.has_exponents() && get_yes_no(io, "Mají být v příkladech i záporné exponenty?")?;
+        let root_kind = match self {
+            ExcerciseKind::QuadraticEquations => excercise::quadratic::pick_root_kind(io)?,
+            _ => None,
+        };
+        let step_mode = matches!(self, ExcerciseKind::OrderOfOperations)
+            && get_yes_no(io, "Chceš počítat po krocích a vybírat, co je na řadě?")?;
+        Ok(Settings {
+            negative_exponents,
+            root_kind,
+            step_mode,
+        })
+    }
+
+    /// `true` when the student solved the excercise.
+    fn do_random(
+        self,
+        io: &mut dyn Io,
+        rnd: &mut Random,
+        settings: &Settings,
+        stats: &mut SessionStats,
+    ) -> Answer<bool> {
+        let negative_exponents = settings.negative_exponents;
+        match self {
+            ExcerciseKind::DiffSquares => {
+                let config = TermConfig {
+                    max_vars: 4,
+                    ..TermConfig::default()
+                };
+                let ass =
+                    ExtractDiffSquares::generate_random(rnd, &config, 0.09f64, negative_exponents);
+                do_diff_squares(io, ass)
+            }
+            ExcerciseKind::PowerRules => {
+                let config = TermConfig::default();
+                let ass =
+                    excercise::power_rules::generate_power_rules(rnd, &config, negative_exponents);
+                excercise::power_rules::solve_excercise(io, ass)
+            }
+            ExcerciseKind::Fractions => {
+                let ass = excercise::fractions::generate_fraction_arithmetic(rnd, 0.3);
+                excercise::fractions::solve_excercise(io, ass)
+            }
+            ExcerciseKind::Percentages => {
+                let ass = excercise::percentages::generate_word_problem(rnd);
+                excercise::percentages::solve_excercise(io, ass)
+            }
+            ExcerciseKind::UnitConversion => {
+                let ass = excercise::unit_conversion::generate_unit_conversion(rnd);
+                excercise::unit_conversion::solve_excercise(io, ass)
+            }
+            ExcerciseKind::OrderOfOperations => {
+                let ass = excercise::order_of_operations::generate_order_of_operations(rnd);
+                excercise::order_of_operations::solve_excercise(io, ass, settings.step_mode)
+            }
+            ExcerciseKind::SpeedDrill => {
+                let ass = excercise::speed_drill::generate_speed_round(rnd);
+                excercise::speed_drill::solve_excercise(io, ass, stats)
+            }
+            ExcerciseKind::Divisibility => {
+                let ass = excercise::divisibility::generate_divisibility(rnd);
+                excercise::divisibility::solve_excercise(io, ass, stats)
+            }
+            ExcerciseKind::PrimeFactorization => {
+                let ass = excercise::primes::generate_factorization(rnd);
+                excercise::primes::solve_excercise(io, ass)
+            }
+            ExcerciseKind::GcdLcm => {
+                let ass = excercise::primes::generate_gcd_lcm(rnd);
+                excercise::primes::solve_excercise(io, ass)
+            }
+            ExcerciseKind::RationalExpressions => {
+                let config = TermConfig {
+                    variables: vec!['a', 'b', 'x', 'y'],
+                    max_vars: 1,
+                    coefficients: 1..=5,
+                    exponents: 1..=2,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::rational::generate_rational_expression(rnd, &config);
+                excercise::rational::solve_excercise(io, ass)
+            }
+            ExcerciseKind::CommonFactor => {
+                let config = TermConfig {
+                    coefficients: 1..=9,
+                    exponents: 1..=2,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::common_factor::generate_common_factor(rnd, &config);
+                excercise::common_factor::solve_excercise(io, ass)
+            }
+            ExcerciseKind::LinearEquations => {
+                let config = TermConfig {
+                    variables: vec!['x', 'y', 'a'],
+                    coefficients: 0..=9,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::linear_equation::generate_linear_equation(rnd, &config, 0.3);
+                excercise::linear_equation::solve_excercise(io, ass)
+            }
+            ExcerciseKind::LinearInequalities => {
+                let config = TermConfig {
+                    variables: vec!['x', 'y', 'a'],
+                    coefficients: 0..=9,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass =
+                    excercise::linear_inequality::generate_linear_inequality(rnd, &config, 0.3);
+                excercise::linear_inequality::solve_excercise(io, ass)
+            }
+            ExcerciseKind::LinearSystems => {
+                let config = TermConfig {
+                    variables: vec!['x', 'y'],
+                    coefficients: 0..=5,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::linear_system::generate_linear_system(rnd, &config, 2, 0.3);
+                excercise::linear_system::solve_excercise(io, ass)
+            }
+            ExcerciseKind::LinearSystemsOfThree => {
+                let config = TermConfig {
+                    variables: vec!['x', 'y', 'z'],
+                    coefficients: 0..=5,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::linear_system::generate_linear_system(rnd, &config, 3, 0.2);
+                excercise::linear_system::solve_excercise(io, ass)
+            }
+            ExcerciseKind::QuadraticEquations => {
+                let config = TermConfig {
+                    variables: vec!['x', 'y', 't'],
+                    coefficients: 0..=9,
+                    negative_coefficients: true,
+                    ..TermConfig::default()
+                };
+                let ass = excercise::quadratic::generate_quadratic_equation(
+                    rnd,
+                    &config,
+                    settings.root_kind,
+                );
+                excercise::quadratic::solve_excercise(io, ass)
+            }
+            ExcerciseKind::Roots => {
+                let config = TermConfig {
+                    variables: vec!['a', 'b', 'c', 'd'],
+                    ..TermConfig::default()
+                };
+                let ass = excercise::roots::generate_root_excercise(rnd, &config);
+                excercise::roots::solve_excercise(io, ass)
+            }
+        }
+    }
+}
+
+/// How the session begins, the code also determines the kind and the settings.
+enum Start {
+    Seed(u64),
+    Code(ExcerciseKind, Settings, u64),
+}
+
+fn parse_start(input: &str) -> Result<Start, String> {
+    if let Ok(seed) = input.parse() {
+        return Ok(Start::Seed(seed));
+    }
+    let code = ExcerciseCode::parse(input)?;
+    let kind = ExcerciseKind::ALL
+        .into_iter()
+        .find(|kind| kind.code() == code.kind)
+        .ok_or_else(|| format!("Druh příkladu \"{}\" neznám.", code.kind))?;
+    if code.version != kind.generator_version() {
+        return Err(format!(
+            "Kód je z jiné verze programu, příklady \"{}\" se teď generují jinak.",
+            kind.name()
+        ));
+    }
+    let settings =
+        Settings::from_params(code.params).ok_or_else(|| "Nastavení v kódu neznám.".to_string())?;
+    Ok(Start::Code(kind, settings, code.seed))
+}
+
+fn pick_excercise_kind(io: &mut dyn Io) -> Answer<ExcerciseKind> {
+    for (idx, kind) in ExcerciseKind::ALL.iter().enumerate() {
+        writeln!(io, "{}) {}", idx + 1, kind.name());
+    }
+    let choice = get_number_in_range(io, "Vyber cvičení", 1..=ExcerciseKind::ALL.len())?;
+    Ok(ExcerciseKind::ALL[choice - 1])
+}
+
+/// Excercises one after another, until the student quits or the input ends.
+fn play_session(io: &mut dyn Io, stats: &mut SessionStats) -> Answer<()> {
+    let start = get_input(
+        io,
+        "Zadej seed nebo kód příkladu",
+        Some("číslo nebo kód, např. DS2-4K9QX"),
+        parse_start,
+    )?;
+    let (mut rnd, kind, settings, mut next_seed) = match start {
+        Start::Seed(seed) => {
+            let kind = pick_excercise_kind(io)?;
+            let settings = kind.ask_settings(io)?;
+            (Random::from_seed(seed), kind, settings, None)
+        }
+        Start::Code(kind, settings, seed) => (Random::from_seed(seed), kind, settings, Some(seed)),
+    };
+    loop {
+        // Every excercise has its own seed, so that it can be generated again from its code
+        let seed = next_seed
+            .take()
+            .unwrap_or_else(|| rnd.random_range(0..SEED_COUNT));
+        let code = ExcerciseCode {
+            kind: kind.code().to_string(),
+            version: kind.generator_version(),
+            params: settings.to_params(),
+            seed,
+        };
+        writeln!(io, "\n\n\n");
+        writeln!(io, "========================");
+        writeln!(io, "Kód příkladu: {code}");
+        let mut excercise_rnd = Random::from_seed(seed);
+        let result = kind.do_random(io, &mut excercise_rnd, &settings, stats);
+        let hints_used = io.hints().take_used();
+        match result {
+            Ok(is_solved) => {
+                let points = stats.record_excercise(is_solved, hints_used);
+                write!(io, "Body za příklad: {points} z {POINTS_PER_EXCERCISE}");
+                if hints_used > 0 {
+                    write!(io, " (nápovědy: {hints_used})");
+                }
+                writeln!(io);
+            }
+            Err(Interrupt::Skip | Interrupt::TooManyInvalid) => {
+                writeln!(io, "Příklad přeskočen.");
+                stats.record_skipped();
+            }
+            Err(interrupt) => return Err(interrupt),
+        }
+    }
+}
+
 fn main() {
-    let seed: u64 = get_number("Zadej seed");
-    let mut rnd = StdRng::seed_from_u64(seed);
-    for _ in 0.. {
-        println!("\n\n\n");
-        println!("========================");
-        let ass = ExtractDiffSquares::generate_random(&mut rnd, 4, 0.09f64);
-        do_diff_squares(ass);
+    let io: &mut dyn Io = &mut TerminalIo::new();
+    let mut stats = SessionStats::default();
+    // The session never ends with an answer, only with `q` or the end of the input
+    let _ = play_session(io, &mut stats);
+    writeln!(io);
+    writeln!(io, "Konec, tady je shrnutí:");
+    stats.print_summary(io);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::console::ScriptedIo;
+    use input::get_number;
+
+    /// Plays the first excercise of `kind` generated from `seed` with the given answers.
+    fn replay(kind: ExcerciseKind, seed: u64, answers: &[&str]) -> String {
+        let mut io = ScriptedIo::new(answers);
+        let settings = Settings {
+            negative_exponents: false,
+            root_kind: None,
+            step_mode: false,
+        };
+        let mut rnd = Random::from_seed(seed);
+        let result = kind.do_random(&mut io, &mut rnd, &settings, &mut SessionStats::default());
+        assert!(result.is_ok(), "{result:?}");
+        io.output().to_string()
+    }
+
+    #[test]
+    fn session_ends_at_the_end_of_input() {
+        let mut io = ScriptedIo::new(&["5", "2", "n"]);
+        let result = play_session(&mut io, &mut SessionStats::default());
+        assert_eq!(result, Err(Interrupt::EndOfInput));
+    }
+
+    #[test]
+    fn skip_moves_to_the_next_excercise_and_q_quits() {
+        let mut io = ScriptedIo::new(&["5", "2", "n", "help", "skip", "q"]);
+        let mut stats = SessionStats::default();
+        let result = play_session(&mut io, &mut stats);
+        assert_eq!(result, Err(Interrupt::Quit));
+        assert!(io.output().contains("skip  přeskočit tenhle příklad"));
+        assert_eq!(io.output().matches("Příklad přeskočen.").count(), 1);
+        assert_eq!(io.output().matches("========================").count(), 2);
+    }
+
+    #[test]
+    fn unreadable_answers_are_given_up() {
+        let mut io = ScriptedIo::new(&["x"; 10]);
+        let result: Answer<u32> = get_number(&mut io, "Kolik?");
+        assert_eq!(result, Err(Interrupt::TooManyInvalid));
+        assert_eq!(io.output().matches("Prompt: Kolik?").count(), 5);
+    }
+
+    #[test]
+    fn menu_asks_again_until_the_choice_is_valid() {
+        let mut io = ScriptedIo::new(&["0", "pět", "4"]);
+        let kind = pick_excercise_kind(&mut io);
+        assert!(matches!(kind, Ok(ExcerciseKind::Percentages)));
+        assert!(io.output().contains("Číslo 0 není od 1 do 18"));
+        assert!(io.output().contains("\"pět\" není číslo."));
+    }
+
+    #[test]
+    fn diff_squares_shows_the_solution_after_enter() {
+        let output = replay(ExcerciseKind::DiffSquares, 7, &["", "a"]);
+        assert!(output.contains("Rozložte na součin podle vzorce:"));
+        assert!(output.contains("Tohle je řešení:"));
+    }
+
+    #[test]
+    fn hints_get_stronger_and_cost_points() {
+        let answers = ["UC2-0001C", "hint", "hint", "hint", "hint", "0,137", "q"];
+        let mut io = ScriptedIo::new(&answers);
+        let mut stats = SessionStats::default();
+        let result = play_session(&mut io, &mut stats);
+        assert_eq!(result, Err(Interrupt::Quit));
+        let output = io.output();
+        assert!(output.contains("Nápověda: Převádíš na větší jednotku, číslo bude menší."));
+        assert!(output.contains("Nápověda: 1 dm² = 100 cm²"));
+        assert!(output.contains("Nápověda: Vyděl číslem 100."));
+        assert!(output.contains("Víc nápovědy už nemám"));
+        assert!(output.contains("Body za příklad: 1 z 3 (nápovědy: 3)"));
+    }
+
+    #[test]
+    fn missed_rules_are_summed_up_after_the_session() {
+        let mut io = ScriptedIo::new(&["DV2-001ZA", "1", "n", "q"]);
+        let mut stats = SessionStats::default();
+        let result = play_session(&mut io, &mut stats);
+        assert_eq!(result, Err(Interrupt::Quit));
+        assert!(!io.output().contains("Chyby v pravidlech"));
+        let mut summary = ScriptedIo::new(&[]);
+        stats.print_summary(&mut summary);
+        assert!(summary.output().contains("dělitelnost 3: 1×"));
+    }
+
+    #[test]
+    fn excercise_code_generates_the_same_excercise() {
+        let first_excercise = |output: &str| {
+            let start = output.find("Kód příkladu: ").unwrap();
+            let end = start + output[start..].find("Prompt").unwrap();
+            output[start..end].to_string()
+        };
+        let mut io = ScriptedIo::new(&["42", "2", "a", "q"]);
+        play_session(&mut io, &mut SessionStats::default()).unwrap_err();
+        let excercise = first_excercise(io.output());
+        let code = excercise
+            .lines()
+            .next()
+            .unwrap()
+            .trim_start_matches("Kód příkladu: ");
+        assert!(code.starts_with("PW2B-"), "{code}");
+
+        let mut io = ScriptedIo::new(&[code, "q"]);
+        play_session(&mut io, &mut SessionStats::default()).unwrap_err();
+        assert_eq!(first_excercise(io.output()), excercise);
+        assert!(!io.output().contains("Vyber cvičení"));
+
+        let mut io = ScriptedIo::new(&["PW9A-00001", "XX1-00001", "DS2-", "q"]);
+        play_session(&mut io, &mut SessionStats::default()).unwrap_err();
+        assert!(io.output().contains("se teď generují jinak"));
+        assert!(io.output().contains("\"DS2-\" není kód příkladu"));
+        assert!(io.output().contains("Druh příkladu \"XX\" neznám."));
+    }
+
+    /// Codes are written on worksheets, they have to keep giving the same excercises.
+    /// If this fails on purpose, raise `random::VERSION` or the generator version of the kind
+    /// and write down the new code.
+    #[test]
+    fn code_generates_the_same_excercise() {
+        let golden = [
+            ("DS2-001ZA", "49·p^30 - 324·x^14"),
+            ("PW2-001ZA", "((15·b^3·x^11)^3·3)/(25·f^20·x^8)"),
+            ("FR2-001ZA", "5/8 : 5/6"),
+            ("PC2-001ZA", "Cena s DPH 21 % je 5082 korun."),
+            ("UC2-001ZA", "370 dm² = ? a"),
+            ("OO2-001ZA", "(11·9 + 10)·(-(-4))"),
+            ("SD2-001ZA", "81 + 54 = ?"),
+            ("DV2-001ZA", "Je číslo 3204 dělitelné 3?"),
+            ("PF2-001ZA", "Rozlož číslo 135 na prvočísla"),
+            ("GL3-001ZA", "n(968, 264)"),
+            ("RE3-001ZA", "(4·b^2 - 9·a^2)/(2·b + 3·a)"),
+            ("CF3-001ZA", "27·x^3·y + 6·x^2·y + 12·x·y^3"),
+            ("LE4-001ZA", "-8·y - 4 = -7·y - 5"),
+            ("LI4-001ZA", "-6·y - 4 > -7·y - 3"),
+            ("LS3-001ZA", "(1) -2·x - 4·y = -14"),
+            ("LT3-001ZA", "(2) -x - 2·y - 4·z = 16"),
+            ("QE4-001ZA", "t^2 + 6·t = 1"),
+            ("RT3-001ZA", "6/(2·√15)"),
+        ];
+        let mut kinds: Vec<&str> = golden.iter().map(|(code, _)| &code[..2]).collect();
+        kinds.sort();
+        let mut all: Vec<&str> = ExcerciseKind::ALL.iter().map(|kind| kind.code()).collect();
+        all.sort();
+        assert_eq!(kinds, all);
+
+        for (code, assignment) in golden {
+            // Enter starts the speed drill, the other excercises just ask again
+            let mut io = ScriptedIo::new(&[code, "", "q"]);
+            let result = play_session(&mut io, &mut SessionStats::default());
+            assert_eq!(result, Err(Interrupt::Quit), "{}", io.output());
+            let output = io.output();
+            assert!(
+                output.contains(&format!("Kód příkladu: {code}")),
+                "{output}"
+            );
+            assert!(output.contains(assignment), "{output}");
+        }
+    }
+
+    #[test]
+    fn term_config_shapes_the_terms() {
+        let config = TermConfig {
+            variables: vec!['x', 'y', 'z'],
+            max_vars: 3,
+            coefficients: 1..=5,
+            exponents: 1..=3,
+            max_degree: Some(4),
+            negative_coefficients: true,
+            fractional_coefficients: true,
+        };
+        let mut rnd = Random::from_seed(3);
+        let mut terms = vec![];
+        for _ in 0..300 {
+            let expr = generate_term(&mut rnd, &config);
+            assert!(expr
+                .variable_occurrences()
+                .iter()
+                .all(|var| "xyz".contains(*var)));
+            terms.push(ast::canonical::CanonicalTerm::from_expr(&expr).unwrap());
+        }
+        for term in &terms {
+            assert!(term.degree() <= 4);
+            let coefficient = term.coefficient();
+            assert!((1..=5).contains(&coefficient.numerator().abs()));
+            assert!((1..=5).contains(&coefficient.denominator()));
+        }
+        assert!(terms.iter().any(|term| term.coefficient().numerator() < 0));
+        assert!(terms
+            .iter()
+            .any(|term| term.coefficient().denominator() > 1));
+    }
+
+    #[test]
+    fn zero_coefficient_is_not_a_fraction() {
+        let config = TermConfig {
+            coefficients: 0..=1,
+            fractional_coefficients: true,
+            ..TermConfig::default()
+        };
+        let mut rnd = Random::from_seed(5);
+        let terms: Vec<Expr> = (0..100).map(|_| generate_term(&mut rnd, &config)).collect();
+        let zeros: Vec<&Expr> = terms
+            .iter()
+            .filter(|term| CanonicalTerm::from_expr(term).unwrap().is_zero())
+            .collect();
+        assert!(!zeros.is_empty());
+        for zero in zeros {
+            assert!(!zero.to_string().contains('/'), "{zero}");
+        }
+        assert!(terms.iter().any(|term| term.to_string().contains('/')));
+    }
+
+    #[test]
+    fn zero_to_a_negative_power_is_an_error() {
+        let pow = |input: &str, exponent| {
+            let powered = Expr::parse(input)
+                .unwrap()
+                .pow(exponent, NegativeExponents::Keep);
+            powered.map(|expr| expr.to_string())
+        };
+        assert_eq!(pow("0", 0), Err(PowError::ZeroToZero));
+        assert_eq!(pow("0", -2), Err(PowError::ZeroToNegative));
+        assert_eq!(pow("0^(-1)", 2), Err(PowError::ZeroToNegative));
+        assert_eq!(pow("0^2", 0), Err(PowError::ZeroToZero));
+        assert_eq!(pow("0^2", 3), Ok("0".to_string()));
+        assert_eq!(pow("a + b", 2), Err(PowError::Sum));
+    }
+
+    #[test]
+    fn unit_conversion_explains_the_squared_factor() {
+        let output = replay(ExcerciseKind::UnitConversion, 44, &["1,37", "0,137 dm2"]);
+        assert!(output.contains("13,7 cm² = ? dm²"));
+        assert!(output.contains("1 dm² = 10² cm² = 100 cm²"));
+        assert!(output.contains("Správně!"));
+    }
+
+    #[test]
+    fn unit_conversion_shows_the_solution_after_three_mistakes() {
+        let output = replay(ExcerciseKind::UnitConversion, 9, &["1", "1", "1"]);
+        assert_eq!(output.matches("Špatně.").count(), 3);
+        assert!(output.contains("7,66 g = 7,66 : 1000 kg = 0,00766 kg"));
     }
 }
@@ -0,0 +1,138 @@
+use std::ops::{Range, RangeInclusive};
+
+/*
+    Random numbers for the generators of the excercises.
+    The same seed has to give the same excercise in every build of the program,
+    seeds and codes of the excercises are written down and shared. That is why
+    the algorithm is our own and does not depend on a version of some library.
+
+    The algorithm is SplitMix64, a number from a range is picked by rejecting
+    the values which would make some numbers more likely than others.
+*/
+
+/// Version of the algorithm, any change of the numbers it gives has to raise it
+/// together with the versions of the excercise generators.
+pub const VERSION: u32 = 1;
+
+pub struct Random {
+    state: u64,
+}
+
+/// Values which can be picked from a range, they are numbered by `i128`.
+pub trait Uniform: Copy {
+    fn to_index(self) -> i128;
+    fn from_index(index: i128) -> Self;
+}
+
+macro_rules! uniform_integer {
+    ($($int:ty),*) => {
+        $(
+            impl Uniform for $int {
+                fn to_index(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_index(index: i128) -> Self {
+                    index as $int
+                }
+            }
+        )*
+    };
+}
+
+uniform_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Uniform for char {
+    fn to_index(self) -> i128 {
+        i128::from(u32::from(self))
+    }
+
+    fn from_index(index: i128) -> Self {
+        char::from_u32(index as u32).expect("Ranges of characters do not contain surrogates")
+    }
+}
+
+/// `a..b` and `a..=b`, given by the smallest and the largest value.
+pub trait SampleRange<T> {
+    fn bounds(&self) -> (i128, i128);
+}
+
+impl<T: Uniform> SampleRange<T> for Range<T> {
+    fn bounds(&self) -> (i128, i128) {
+        (self.start.to_index(), self.end.to_index() - 1)
+    }
+}
+
+impl<T: Uniform> SampleRange<T> for RangeInclusive<T> {
+    fn bounds(&self) -> (i128, i128) {
+        (self.start().to_index(), self.end().to_index())
+    }
+}
+
+impl Random {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// Uniformly distributed value from `range`, which must not be empty.
+    pub fn random_range<T: Uniform, R: SampleRange<T>>(&mut self, range: R) -> T {
+        let (low, high) = range.bounds();
+        assert!(low <= high, "Cannot pick a value from an empty range");
+        let count = (high - low + 1) as u128;
+        if count > u128::from(u64::MAX) {
+            return T::from_index(low + i128::from(self.next_u64()));
+        }
+        let count = count as u64;
+        // The largest multiple of `count`, values above it would be picked unevenly
+        let limit = u64::MAX - u64::MAX % count;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return T::from_index(low + i128::from(value % count));
+            }
+        }
+    }
+
+    /// `true` with the probability `chance`.
+    pub fn random_bool(&mut self, chance: f64) -> bool {
+        // The top 53 bits make a uniformly distributed float in [0, 1)
+        let value = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        value < chance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// If this fails on purpose, raise `VERSION`.
+    #[test]
+    fn random_numbers_stay_the_same() {
+        let mut rng = Random::from_seed(0);
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+        let mut rng = Random::from_seed(1);
+        let dice = (0..10)
+            .map(|_| rng.random_range(1..=6))
+            .collect::<Vec<i32>>();
+        assert_eq!(dice, [6, 2, 1, 6, 4, 3, 4, 4, 1, 5]);
+    }
+
+    #[test]
+    fn ranges_include_their_ends() {
+        let mut rng = Random::from_seed(7);
+        let values: Vec<i32> = (0..200).map(|_| rng.random_range(-2..=2)).collect();
+        assert!((-2..=2).all(|value| values.contains(&value)));
+        let values: Vec<char> = (0..200).map(|_| rng.random_range('a'..'d')).collect();
+        assert!(values.iter().all(|chr| ('a'..'d').contains(chr)));
+        assert!(values.contains(&'c'));
+    }
+}
@@ -0,0 +1,99 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::input::Io;
+
+/// Points for an excercise solved without hints, every hint costs one.
+pub const POINTS_PER_EXCERCISE: u32 = 3;
+
+/*
+    What went wrong during the session, so that a teacher can see
+    which topics the student should practise more.
+*/
+#[derive(Default)]
+pub struct SessionStats {
+    /// How many times the student got each rule wrong, e.g. `"dělitelnost 4"`.
+    wrong_rules: BTreeMap<String, u32>,
+    /// Mistakes in the speed drill by the fact, e.g. `"7·8"`.
+    wrong_facts: BTreeMap<String, u32>,
+    skipped: u32,
+    excercises: u32,
+    solved: u32,
+    hints_used: u32,
+    points: u32,
+}
+
+/// The most frequent mistakes first.
+fn by_count(map: &BTreeMap<String, u32>) -> Vec<(&str, u32)> {
+    let mut items: Vec<(&str, u32)> = map
+        .iter()
+        .map(|(item, count)| (item.as_str(), *count))
+        .collect();
+    items.sort_by_key(|(_, count)| Reverse(*count));
+    items
+}
+
+impl SessionStats {
+    pub fn record_wrong_rule(&mut self, rule: &str) {
+        *self.wrong_rules.entry(rule.to_string()).or_default() += 1;
+    }
+
+    pub fn record_wrong_fact(&mut self, fact: &str) {
+        *self.wrong_facts.entry(fact.to_string()).or_default() += 1;
+    }
+
+    pub fn record_skipped(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Returns the points for the excercise, a solved one is worth at least a point.
+    pub fn record_excercise(&mut self, is_solved: bool, hints_used: u32) -> u32 {
+        let points = if is_solved {
+            POINTS_PER_EXCERCISE.saturating_sub(hints_used).max(1)
+        } else {
+            0
+        };
+        self.excercises += 1;
+        self.solved += u32::from(is_solved);
+        self.hints_used += hints_used;
+        self.points += points;
+        points
+    }
+
+    pub fn print_summary(&self, io: &mut dyn Io) {
+        if self.excercises > 0 {
+            writeln!(
+                io,
+                "Vyřešeno {} z {} příkladů, skóre {} z {} bodů.",
+                self.solved,
+                self.excercises,
+                self.points,
+                self.excercises * POINTS_PER_EXCERCISE
+            );
+        }
+        if self.hints_used > 0 {
+            writeln!(io, "Použité nápovědy: {}", self.hints_used);
+        }
+        if self.skipped > 0 {
+            writeln!(io, "Přeskočené příklady: {}", self.skipped);
+        }
+        let rules = by_count(&self.wrong_rules);
+        let facts = by_count(&self.wrong_facts);
+        if rules.is_empty() && facts.is_empty() {
+            writeln!(io, "Zatím žádné chyby.");
+            return;
+        }
+        if !rules.is_empty() {
+            writeln!(io, "Chyby v pravidlech za tuto relaci:");
+            for (rule, count) in rules {
+                writeln!(io, "  {rule}: {count}×");
+            }
+        }
+        if !facts.is_empty() {
+            writeln!(io, "Nejčastěji špatně spočítané příklady:");
+            for (fact, count) in facts {
+                writeln!(io, "  {fact}   ({count}×)");
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use crate::ast::rational::Rational;
+
+/// What a unit measures, only units of the same quantity can be converted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Quantity {
+    Length,
+    Area,
+    Volume,
+    Mass,
+    Time,
+}
+
+impl Quantity {
+    pub const ALL: [Quantity; 5] = [
+        Quantity::Length,
+        Quantity::Area,
+        Quantity::Volume,
+        Quantity::Mass,
+        Quantity::Time,
+    ];
+}
+
+/*
+    A unit with its size in the base unit of its quantity,
+    which is m, m², m³, g and s. E.g. `dm³` has the size 1/1000.
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Unit {
+    pub symbol: &'static str,
+    pub quantity: Quantity,
+    size: (i64, i64),
+    /// `2` for `cm²`, so that the student can be told that the factor is squared.
+    pub power: u32,
+}
+
+const fn unit(symbol: &'static str, quantity: Quantity, size: (i64, i64), power: u32) -> Unit {
+    Unit {
+        symbol,
+        quantity,
+        size,
+        power,
+    }
+}
+
+/// Every unit the excercises use, from the smallest in each quantity.
+pub const UNITS: [Unit; 29] = [
+    unit("mm", Quantity::Length, (1, 1_000), 1),
+    unit("cm", Quantity::Length, (1, 100), 1),
+    unit("dm", Quantity::Length, (1, 10), 1),
+    unit("m", Quantity::Length, (1, 1), 1),
+    unit("km", Quantity::Length, (1_000, 1), 1),
+    unit("mm²", Quantity::Area, (1, 1_000_000), 2),
+    unit("cm²", Quantity::Area, (1, 10_000), 2),
+    unit("dm²", Quantity::Area, (1, 100), 2),
+    unit("m²", Quantity::Area, (1, 1), 2),
+    unit("a", Quantity::Area, (100, 1), 1),
+    unit("ha", Quantity::Area, (10_000, 1), 1),
+    unit("km²", Quantity::Area, (1_000_000, 1), 2),
+    unit("mm³", Quantity::Volume, (1, 1_000_000_000), 3),
+    unit("cm³", Quantity::Volume, (1, 1_000_000), 3),
+    unit("ml", Quantity::Volume, (1, 1_000_000), 1),
+    unit("cl", Quantity::Volume, (1, 100_000), 1),
+    unit("dl", Quantity::Volume, (1, 10_000), 1),
+    unit("dm³", Quantity::Volume, (1, 1_000), 3),
+    unit("l", Quantity::Volume, (1, 1_000), 1),
+    unit("hl", Quantity::Volume, (1, 10), 1),
+    unit("m³", Quantity::Volume, (1, 1), 3),
+    unit("mg", Quantity::Mass, (1, 1_000), 1),
+    unit("g", Quantity::Mass, (1, 1), 1),
+    unit("dag", Quantity::Mass, (10, 1), 1),
+    unit("kg", Quantity::Mass, (1_000, 1), 1),
+    unit("t", Quantity::Mass, (1_000_000, 1), 1),
+    unit("s", Quantity::Time, (1, 1), 1),
+    unit("min", Quantity::Time, (60, 1), 1),
+    unit("h", Quantity::Time, (3_600, 1), 1),
+];
+
+impl Unit {
+    /// Accepts also `m2`, `m^2`, `dkg` and `hod`, as they are typed on a keyboard.
+    pub fn parse(symbol: &str) -> Option<Unit> {
+        let normalized = symbol
+            .trim()
+            .replace("^2", "²")
+            .replace("^3", "³")
+            .replace('2', "²")
+            .replace('3', "³");
+        let normalized = match normalized.as_str() {
+            "dkg" => "dag",
+            "hod" => "h",
+            "L" => "l",
+            other => other,
+        };
+        UNITS.iter().find(|unit| unit.symbol == normalized).copied()
+    }
+
+    /// How many of `other` make one of `self`, e.g. `m²` to `cm²` is 10 000.
+    pub fn factor_to(self, other: Unit) -> Rational {
+        debug_assert_eq!(self.quantity, other.quantity);
+        let size = |unit: Unit| Rational::new(unit.size.0, unit.size.1).expect("Positive size");
+        size(self)
+            .div(size(other))
+            .expect("Units have a non-zero size")
+    }
+
+    pub fn convert(self, value: f64, other: Unit) -> f64 {
+        value * self.factor_to(other).to_f64()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factor(from: &str, to: &str) -> Rational {
+        Unit::parse(from)
+            .unwrap()
+            .factor_to(Unit::parse(to).unwrap())
+    }
+
+    #[test]
+    fn factors_follow_the_power_of_the_unit() {
+        assert_eq!(factor("m", "cm"), Rational::integer(100));
+        assert_eq!(factor("m2", "cm^2"), Rational::integer(10_000));
+        assert_eq!(factor("dm³", "l"), Rational::integer(1));
+        assert_eq!(factor("ha", "a"), Rational::integer(100));
+        assert_eq!(factor("min", "h"), Rational::new(1, 60).unwrap());
+        assert_eq!(factor("dkg", "kg"), Rational::new(1, 100).unwrap());
+    }
+
+    #[test]
+    fn factors_are_inverse() {
+        for from in UNITS {
+            for to in UNITS.iter().filter(|unit| unit.quantity == from.quantity) {
+                let there_and_back = from.factor_to(*to).mul(to.factor_to(from));
+                assert_eq!(there_and_back, Some(Rational::integer(1)), "{from} {to}");
+            }
+        }
+    }
+}